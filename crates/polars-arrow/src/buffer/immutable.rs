@@ -214,6 +214,12 @@ impl<T> Buffer<T> {
         self.storage.as_ptr()
     }
 
+    /// Returns the storage underlying this buffer.
+    #[inline]
+    pub(crate) fn into_storage(self) -> SharedStorage<T> {
+        self.storage
+    }
+
     /// Returns the start offset of this buffer within the underlying storage.
     #[inline]
     pub fn offset(&self) -> usize {
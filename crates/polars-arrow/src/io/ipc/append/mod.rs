@@ -30,8 +30,12 @@ impl<R: Read + Seek + Write> FileWriter<R> {
             polars_bail!(ComputeError: "appending to a file of a non-native endianness is not supported")
         }
 
-        let dictionaries =
-            read::read_file_dictionaries(&mut writer, &metadata, &mut Default::default())?;
+        let dictionaries = read::read_file_dictionaries(
+            &mut writer,
+            &metadata,
+            &mut Default::default(),
+            &Default::default(),
+        )?;
 
         let last_block = metadata.blocks.last().ok_or_else(|| {
             polars_err!(oos = "an Arrow IPC file must have at least 1 message (the schema message)")
@@ -64,9 +68,11 @@ impl<R: Read + Seek + Write> FileWriter<R> {
             dictionary_tracker: DictionaryTracker {
                 dictionaries,
                 cannot_replace: true,
+                emit_deltas: false,
             },
             encoded_message: Default::default(),
             custom_schema_metadata: None,
+            compression_by_column: Default::default(),
         })
     }
 }
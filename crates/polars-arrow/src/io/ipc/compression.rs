@@ -12,9 +12,15 @@ pub fn decompress_lz4(input_buf: &[u8], output_buf: &mut [u8]) -> PolarsResult<(
 
 #[cfg(feature = "io_ipc_compression")]
 #[cfg_attr(docsrs, doc(cfg(feature = "io_ipc_compression")))]
-pub fn decompress_zstd(input_buf: &[u8], output_buf: &mut [u8]) -> PolarsResult<()> {
+pub fn decompress_zstd(
+    input_buf: &[u8],
+    output_buf: &mut [u8],
+    max_window_size: usize,
+) -> PolarsResult<()> {
     use std::io::Read;
     let mut decoder = zstd::Decoder::with_buffer(input_buf)?;
+    // zstd only accepts window logs in 10..=31
+    decoder.window_log_max(max_window_size.max(1).ilog2().clamp(10, 31))?;
     decoder.read_exact(output_buf).map_err(|e| e.into())
 }
 
@@ -26,7 +32,11 @@ pub fn decompress_lz4(_input_buf: &[u8], _output_buf: &mut [u8]) -> PolarsResult
 }
 
 #[cfg(not(feature = "io_ipc_compression"))]
-pub fn decompress_zstd(_input_buf: &[u8], _output_buf: &mut [u8]) -> PolarsResult<()> {
+pub fn decompress_zstd(
+    _input_buf: &[u8],
+    _output_buf: &mut [u8],
+    _max_window_size: usize,
+) -> PolarsResult<()> {
     panic!(
         "The crate was compiled without IPC compression. Use `io_ipc_compression` to read compressed IPC."
     );
@@ -77,7 +87,7 @@ mod tests {
         compress_zstd(&data, &mut buffer).unwrap();
 
         let mut result = vec![0; 200];
-        decompress_zstd(&buffer, &mut result).unwrap();
+        decompress_zstd(&buffer, &mut result, 1 << 27).unwrap();
         assert_eq!(data, result);
     }
 
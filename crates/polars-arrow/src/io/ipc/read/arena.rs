@@ -0,0 +1,313 @@
+use polars_error::PolarsResult;
+use polars_utils::aliases::PlHashMap;
+
+use crate::array::*;
+use crate::bitmap::Bitmap;
+use crate::buffer::Buffer;
+use crate::datatypes::PhysicalType;
+use crate::offset::{Offset, OffsetsBuffer};
+use crate::record_batch::RecordBatchT;
+use crate::storage::SharedStorage;
+use crate::types::NativeType;
+use crate::{match_integer_type, with_match_primitive_type_full};
+
+/// The alignment of the buffers in an arena, as of the buffers in the body of an IPC message.
+const ARENA_ALIGNMENT: usize = 64;
+
+/// Record batches whose decoded buffers are views into a single allocation, the arena.
+///
+/// Keeping all batches of a file resident as separately allocated buffers fragments memory;
+/// [`read_into_arena`] instead packs them into one allocation, which improves their locality
+/// and leaves a single allocation to free.
+#[derive(Debug, Clone)]
+pub struct ArenaBatches {
+    arena: Buffer<u8>,
+    batches: Vec<RecordBatchT<Box<dyn Array>>>,
+}
+
+impl ArenaBatches {
+    /// Returns the record batches.
+    pub fn batches(&self) -> &[RecordBatchT<Box<dyn Array>>] {
+        &self.batches
+    }
+
+    /// Returns the record batches. The arena is freed once all of their arrays are dropped.
+    pub fn into_batches(self) -> Vec<RecordBatchT<Box<dyn Array>>> {
+        self.batches
+    }
+
+    /// Returns the arena that the buffers of the batches point into.
+    pub fn arena(&self) -> &Buffer<u8> {
+        &self.arena
+    }
+}
+
+enum Stage {
+    /// Assigns the buffers their offset in the arena.
+    Layout,
+    /// Copies the buffers to their offset in the arena.
+    Copy(Vec<u128>),
+    /// Replaces the buffers by views into the arena.
+    View(SharedStorage<u8>),
+}
+
+struct Packer {
+    stage: Stage,
+    // the offset in the arena of the buffers, by their address and length in bytes, such that a
+    // buffer shared by many arrays, e.g. a dictionary shared by many batches, is packed once
+    offsets: PlHashMap<(usize, usize), (usize, bool)>,
+    len: usize,
+}
+
+impl Packer {
+    /// Returns the offset in the arena of `bytes`.
+    fn place(&mut self, bytes: &[u8]) -> usize {
+        if bytes.is_empty() {
+            return 0;
+        }
+        let key = (bytes.as_ptr() as usize, bytes.len());
+        match &mut self.stage {
+            Stage::Layout => {
+                let len = &mut self.len;
+                self.offsets
+                    .entry(key)
+                    .or_insert_with(|| {
+                        let offset = *len;
+                        *len = (offset + bytes.len()).next_multiple_of(ARENA_ALIGNMENT);
+                        (offset, false)
+                    })
+                    .0
+            },
+            Stage::Copy(arena) => {
+                let (offset, copied) = self.offsets.get_mut(&key).unwrap();
+                if !*copied {
+                    let arena = bytemuck::cast_slice_mut::<u128, u8>(arena);
+                    arena[*offset..*offset + bytes.len()].copy_from_slice(bytes);
+                    *copied = true;
+                }
+                *offset
+            },
+            Stage::View(_) => self.offsets[&key].0,
+        }
+    }
+
+    fn buffer<T: NativeType>(&mut self, buffer: &Buffer<T>) -> Buffer<T> {
+        let offset = self.place(bytemuck::cast_slice(buffer.as_slice()));
+        let Stage::View(arena) = &self.stage else {
+            return buffer.clone();
+        };
+        // the arena is aligned to `u128` and its buffers to `ARENA_ALIGNMENT` bytes
+        let storage = arena
+            .clone()
+            .try_transmute::<T>()
+            .unwrap_or_else(|_| unreachable!());
+        Buffer::from_storage(storage).sliced(offset / size_of::<T>(), buffer.len())
+    }
+
+    fn offsets<O: Offset>(&mut self, offsets: &OffsetsBuffer<O>) -> OffsetsBuffer<O> {
+        // SAFETY: the offsets are unchanged
+        unsafe { OffsetsBuffer::new_unchecked(self.buffer(offsets.buffer())) }
+    }
+
+    fn bitmap(&mut self, bitmap: &Bitmap) -> Bitmap {
+        let (bytes, bit_offset, length) = bitmap.as_slice();
+        let offset = self.place(bytes);
+        let Stage::View(arena) = &self.stage else {
+            return bitmap.clone();
+        };
+        // SAFETY: the bits are unchanged
+        unsafe {
+            Bitmap::from_inner_unchecked(
+                arena.clone(),
+                offset * 8 + bit_offset,
+                length,
+                bitmap.lazy_unset_bits(),
+            )
+        }
+    }
+
+    fn validity(&mut self, array: &dyn Array) -> Option<Bitmap> {
+        array.validity().map(|validity| self.bitmap(validity))
+    }
+
+    fn view_array<T: ViewType + ?Sized>(
+        &mut self,
+        array: &BinaryViewArrayGeneric<T>,
+    ) -> BinaryViewArrayGeneric<T> {
+        let validity = self.validity(array);
+        let views = self.buffer(array.views());
+        let buffers = array
+            .data_buffers()
+            .iter()
+            .map(|buffer| self.buffer(buffer))
+            .collect();
+        // SAFETY: the views and the buffers are unchanged
+        unsafe {
+            BinaryViewArrayGeneric::new_unchecked(
+                array.dtype().clone(),
+                views,
+                buffers,
+                validity,
+                array.total_bytes_len(),
+                array.total_buffer_len(),
+            )
+        }
+    }
+
+    /// Returns `array` with its buffers replaced as of the current stage.
+    ///
+    /// Union arrays are returned unchanged, i.e. they are not packed.
+    fn array(&mut self, array: &dyn Array) -> Box<dyn Array> {
+        use PhysicalType::*;
+        let dtype = array.dtype().clone();
+        let any = array.as_any();
+        match array.dtype().to_physical_type() {
+            Null | Union => array.to_boxed(),
+            Boolean => {
+                let array = any.downcast_ref::<BooleanArray>().unwrap();
+                let validity = self.validity(array);
+                BooleanArray::new(dtype, self.bitmap(array.values()), validity).boxed()
+            },
+            Primitive(primitive) => with_match_primitive_type_full!(primitive, |$T| {
+                let array = any.downcast_ref::<PrimitiveArray<$T>>().unwrap();
+                let validity = self.validity(array);
+                PrimitiveArray::new(dtype, self.buffer(array.values()), validity).boxed()
+            }),
+            Binary => self.binary::<i32>(array),
+            LargeBinary => self.binary::<i64>(array),
+            Utf8 => self.utf8::<i32>(array),
+            LargeUtf8 => self.utf8::<i64>(array),
+            FixedSizeBinary => {
+                let array = any.downcast_ref::<FixedSizeBinaryArray>().unwrap();
+                let validity = self.validity(array);
+                FixedSizeBinaryArray::new(dtype, self.buffer(array.values()), validity).boxed()
+            },
+            BinaryView => self
+                .view_array(any.downcast_ref::<BinaryViewArray>().unwrap())
+                .boxed(),
+            Utf8View => self
+                .view_array(any.downcast_ref::<Utf8ViewArray>().unwrap())
+                .boxed(),
+            List => self.list::<i32>(array),
+            LargeList => self.list::<i64>(array),
+            FixedSizeList => {
+                let array = any.downcast_ref::<FixedSizeListArray>().unwrap();
+                let validity = self.validity(array);
+                let values = self.array(array.values().as_ref());
+                FixedSizeListArray::new(dtype, array.len(), values, validity).boxed()
+            },
+            Struct => {
+                let array = any.downcast_ref::<StructArray>().unwrap();
+                let validity = self.validity(array);
+                let values = array
+                    .values()
+                    .iter()
+                    .map(|values| self.array(values.as_ref()))
+                    .collect();
+                StructArray::new(dtype, array.len(), values, validity).boxed()
+            },
+            Map => {
+                let array = any.downcast_ref::<MapArray>().unwrap();
+                let validity = self.validity(array);
+                let offsets = self.offsets(array.offsets());
+                let field = self.array(array.field().as_ref());
+                MapArray::new(dtype, offsets, field, validity).boxed()
+            },
+            Dictionary(key_type) => match_integer_type!(key_type, |$T| {
+                let array = any.downcast_ref::<DictionaryArray<$T>>().unwrap();
+                let keys = array.keys();
+                let validity = self.validity(keys);
+                let keys = PrimitiveArray::new(
+                    keys.dtype().clone(),
+                    self.buffer(keys.values()),
+                    validity,
+                );
+                let values = self.array(array.values().as_ref());
+                // SAFETY: the keys and the values are unchanged
+                unsafe { DictionaryArray::try_new_unchecked(dtype, keys, values) }
+                    .unwrap()
+                    .boxed()
+            }),
+        }
+    }
+
+    fn binary<O: Offset>(&mut self, array: &dyn Array) -> Box<dyn Array> {
+        let array = array.as_any().downcast_ref::<BinaryArray<O>>().unwrap();
+        let validity = self.validity(array);
+        let offsets = self.offsets(array.offsets());
+        let values = self.buffer(array.values());
+        // SAFETY: the offsets and the values are unchanged
+        unsafe { BinaryArray::new_unchecked(array.dtype().clone(), offsets, values, validity) }
+            .boxed()
+    }
+
+    fn utf8<O: Offset>(&mut self, array: &dyn Array) -> Box<dyn Array> {
+        let array = array.as_any().downcast_ref::<Utf8Array<O>>().unwrap();
+        let validity = self.validity(array);
+        let offsets = self.offsets(array.offsets());
+        let values = self.buffer(array.values());
+        // SAFETY: the offsets and the values are unchanged
+        unsafe { Utf8Array::new_unchecked(array.dtype().clone(), offsets, values, validity) }
+            .boxed()
+    }
+
+    fn list<O: Offset>(&mut self, array: &dyn Array) -> Box<dyn Array> {
+        let array = array.as_any().downcast_ref::<ListArray<O>>().unwrap();
+        let validity = self.validity(array);
+        let offsets = self.offsets(array.offsets());
+        let values = self.array(array.values().as_ref());
+        ListArray::new(array.dtype().clone(), offsets, values, validity).boxed()
+    }
+
+    fn arrays(&mut self, batch: &RecordBatchT<Box<dyn Array>>) -> Vec<Box<dyn Array>> {
+        batch
+            .arrays()
+            .iter()
+            .map(|array| self.array(array.as_ref()))
+            .collect()
+    }
+}
+
+/// Reads all record batches of `reader`, e.g. a [`FileReader`], and packs their decoded
+/// buffers into a single arena, such that the arrays of the batches are views into it.
+///
+/// The buffers of compressed messages are packed decompressed, and buffers shared by many
+/// arrays, e.g. the dictionaries shared by many batches, are packed once. Union arrays are not
+/// packed. While packing, the decoded batches and the arena are both allocated.
+///
+/// [`FileReader`]: super::FileReader
+pub fn read_into_arena<I>(reader: I) -> PolarsResult<ArenaBatches>
+where
+    I: IntoIterator<Item = PolarsResult<RecordBatchT<Box<dyn Array>>>>,
+{
+    let batches = reader.into_iter().collect::<PolarsResult<Vec<_>>>()?;
+
+    let mut packer = Packer {
+        stage: Stage::Layout,
+        offsets: Default::default(),
+        len: 0,
+    };
+    batches.iter().for_each(|batch| drop(packer.arrays(batch)));
+
+    packer.stage = Stage::Copy(vec![0u128; packer.len / size_of::<u128>()]);
+    batches.iter().for_each(|batch| drop(packer.arrays(batch)));
+
+    let Stage::Copy(arena) = std::mem::replace(&mut packer.stage, Stage::Layout) else {
+        unreachable!()
+    };
+    let arena = SharedStorage::bytes_from_pod_vec(arena);
+    packer.stage = Stage::View(arena.clone());
+    let batches = batches
+        .into_iter()
+        .map(|batch| {
+            let (length, arrays) = (batch.len(), packer.arrays(&batch));
+            let (schema, _) = batch.into_schema_and_arrays();
+            RecordBatchT::new(length, schema, arrays)
+        })
+        .collect();
+
+    Ok(ArenaBatches {
+        arena: Buffer::from_storage(arena),
+        batches,
+    })
+}
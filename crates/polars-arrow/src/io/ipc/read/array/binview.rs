@@ -20,6 +20,7 @@ pub fn read_binview<T: ViewType + ?Sized, R: Read + Seek>(
     compression: Option<Compression>,
     limit: Option<usize>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<ArrayRef> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
 
@@ -32,6 +33,7 @@ pub fn read_binview<T: ViewType + ?Sized, R: Read + Seek>(
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let length = try_get_array_length(field_node, limit)?;
@@ -43,6 +45,7 @@ pub fn read_binview<T: ViewType + ?Sized, R: Read + Seek>(
         is_little_endian,
         compression,
         scratch,
+        options,
     )?;
 
     let n_variadic = variadic_buffer_counts.pop_front().ok_or_else(
@@ -58,6 +61,7 @@ pub fn read_binview<T: ViewType + ?Sized, R: Read + Seek>(
                 is_little_endian,
                 compression,
                 scratch,
+                options,
             )
         })
         .collect::<PolarsResult<Vec<Buffer<u8>>>>()?;
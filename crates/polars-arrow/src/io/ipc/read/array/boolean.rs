@@ -4,7 +4,7 @@ use std::io::{Read, Seek};
 use polars_error::{PolarsResult, polars_err};
 
 use super::super::read_basic::*;
-use super::super::{Compression, IpcBuffer, Node};
+use super::super::{Compression, IpcBuffer, Node, ReadOptions};
 use crate::array::BooleanArray;
 use crate::datatypes::ArrowDataType;
 use crate::io::ipc::read::array::{try_get_array_length, try_get_field_node};
@@ -20,6 +20,7 @@ pub fn read_boolean<R: Read + Seek>(
     compression: Option<Compression>,
     limit: Option<usize>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<BooleanArray> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
 
@@ -32,6 +33,7 @@ pub fn read_boolean<R: Read + Seek>(
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let length = try_get_array_length(field_node, limit)?;
@@ -44,6 +46,7 @@ pub fn read_boolean<R: Read + Seek>(
         is_little_endian,
         compression,
         scratch,
+        options,
     )?;
     BooleanArray::try_new(dtype, values, validity)
 }
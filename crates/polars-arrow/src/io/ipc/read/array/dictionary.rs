@@ -1,12 +1,14 @@
 use std::collections::VecDeque;
 use std::io::{Read, Seek};
 
-use polars_error::{PolarsResult, polars_bail, polars_err};
-use polars_utils::aliases::PlHashSet;
+use polars_error::{PolarsResult, polars_bail, polars_ensure};
 
-use super::super::{Compression, Dictionaries, IpcBuffer, Node};
+use super::super::{
+    Compression, Dictionaries, IpcBuffer, MissingDictionaryPolicy, Node, OutOfSpecKind, ReadOptions,
+};
 use super::{read_primitive, skip_primitive};
-use crate::array::{DictionaryArray, DictionaryKey};
+use crate::array::{DictionaryArray, DictionaryKey, new_empty_array};
+use crate::bitmap::Bitmap;
 use crate::datatypes::ArrowDataType;
 
 #[allow(clippy::too_many_arguments)]
@@ -22,6 +24,7 @@ pub fn read_dictionary<T: DictionaryKey, R: Read + Seek>(
     limit: Option<usize>,
     is_little_endian: bool,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<DictionaryArray<T>>
 where
     Vec<u8>: TryInto<T::Bytes>,
@@ -31,15 +34,12 @@ where
     } else {
         polars_bail!(oos = "Dictionary has no id.");
     };
-    let values = dictionaries
-        .get(&id)
-        .ok_or_else(|| {
-            let valid_ids = dictionaries.keys().collect::<PlHashSet<_>>();
-            polars_err!(ComputeError:
-                "Dictionary id {id} not found. Valid ids: {valid_ids:?}"
-            )
-        })?
-        .clone();
+    let values = dictionaries.get(&id).cloned().or_else(|| {
+        options
+            .dictionary_resolver
+            .as_ref()
+            .and_then(|resolver| resolver.resolve(id))
+    });
 
     let keys = read_primitive(
         field_nodes,
@@ -51,8 +51,19 @@ where
         compression,
         limit,
         scratch,
+        options,
     )?;
 
+    let Some(values) = values else {
+        polars_ensure!(
+            options.missing_dictionary_policy == MissingDictionaryPolicy::Empty,
+            oos = OutOfSpecKind::MissingDictionary { id }
+        );
+        // all keys are null, such that none is out of bounds of the empty dictionary
+        let validity = Bitmap::new_zeroed(keys.len());
+        let values = new_empty_array(DictionaryArray::<T>::try_get_child(&dtype)?.clone());
+        return DictionaryArray::<T>::try_new(dtype, keys.with_validity(Some(validity)), values);
+    };
     DictionaryArray::<T>::try_new(dtype, keys, values)
 }
 
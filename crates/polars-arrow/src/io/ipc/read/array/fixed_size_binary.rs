@@ -4,7 +4,7 @@ use std::io::{Read, Seek};
 use polars_error::{PolarsResult, polars_err};
 
 use super::super::read_basic::*;
-use super::super::{Compression, IpcBuffer, Node};
+use super::super::{Compression, IpcBuffer, Node, ReadOptions};
 use crate::array::FixedSizeBinaryArray;
 use crate::datatypes::ArrowDataType;
 use crate::io::ipc::read::array::{try_get_array_length, try_get_field_node};
@@ -20,6 +20,7 @@ pub fn read_fixed_size_binary<R: Read + Seek>(
     compression: Option<Compression>,
     limit: Option<usize>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<FixedSizeBinaryArray> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
 
@@ -32,6 +33,7 @@ pub fn read_fixed_size_binary<R: Read + Seek>(
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let length = try_get_array_length(field_node, limit)?;
@@ -45,6 +47,7 @@ pub fn read_fixed_size_binary<R: Read + Seek>(
         is_little_endian,
         compression,
         scratch,
+        options,
     )?;
 
     FixedSizeBinaryArray::try_new(dtype, values, validity)
@@ -6,7 +6,7 @@ use polars_error::{PolarsResult, polars_ensure, polars_err};
 use super::super::super::IpcField;
 use super::super::deserialize::{read, skip};
 use super::super::read_basic::*;
-use super::super::{Compression, Dictionaries, IpcBuffer, Node, Version};
+use super::super::{Compression, Dictionaries, IpcBuffer, Node, ReadOptions, Version};
 use crate::array::FixedSizeListArray;
 use crate::datatypes::ArrowDataType;
 use crate::io::ipc::read::array::try_get_field_node;
@@ -26,6 +26,7 @@ pub fn read_fixed_size_list<R: Read + Seek>(
     limit: Option<usize>,
     version: Version,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<FixedSizeListArray> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
 
@@ -38,6 +39,7 @@ pub fn read_fixed_size_list<R: Read + Seek>(
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let (field, size) = FixedSizeListArray::get_child_and_size(&dtype);
@@ -59,6 +61,7 @@ pub fn read_fixed_size_list<R: Read + Seek>(
         limit,
         version,
         scratch,
+        options,
     )?;
     FixedSizeListArray::try_new(dtype, values.len() / size, values, validity)
 }
@@ -68,6 +71,7 @@ pub fn skip_fixed_size_list(
     dtype: &ArrowDataType,
     buffers: &mut VecDeque<IpcBuffer>,
     variadic_buffer_counts: &mut VecDeque<usize>,
+    version: Version,
 ) -> PolarsResult<()> {
     let _ = field_nodes.pop_front().ok_or_else(|| {
         polars_err!(oos =
@@ -81,5 +85,11 @@ pub fn skip_fixed_size_list(
 
     let (field, _) = FixedSizeListArray::get_child_and_size(dtype);
 
-    skip(field_nodes, field.dtype(), buffers, variadic_buffer_counts)
+    skip(
+        field_nodes,
+        field.dtype(),
+        buffers,
+        variadic_buffer_counts,
+        version,
+    )
 }
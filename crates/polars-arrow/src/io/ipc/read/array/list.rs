@@ -6,9 +6,8 @@ use polars_error::{PolarsResult, polars_err};
 use super::super::super::IpcField;
 use super::super::deserialize::{read, skip};
 use super::super::read_basic::*;
-use super::super::{Compression, Dictionaries, IpcBuffer, Node, Version};
+use super::super::{Compression, Dictionaries, IpcBuffer, Node, ReadOptions, Version};
 use crate::array::ListArray;
-use crate::buffer::Buffer;
 use crate::datatypes::ArrowDataType;
 use crate::io::ipc::read::array::{try_get_array_length, try_get_field_node};
 use crate::offset::Offset;
@@ -18,6 +17,7 @@ pub fn read_list<O: Offset, R: Read + Seek>(
     field_nodes: &mut VecDeque<Node>,
     variadic_buffer_counts: &mut VecDeque<usize>,
     dtype: ArrowDataType,
+    name: &str,
     ipc_field: &IpcField,
     buffers: &mut VecDeque<IpcBuffer>,
     reader: &mut R,
@@ -28,6 +28,7 @@ pub fn read_list<O: Offset, R: Read + Seek>(
     limit: Option<usize>,
     version: Version,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<ListArray<O>>
 where
     Vec<u8>: TryInto<O::Bytes>,
@@ -43,21 +44,28 @@ where
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let length = try_get_array_length(field_node, limit)?;
 
-    let offsets = read_buffer::<O, _>(
+    let offsets = read_offsets::<O, _>(
         buffers,
-        1 + length,
+        length,
         reader,
         block_offset,
         is_little_endian,
         compression,
         scratch,
-    )
-    // Older versions of the IPC format sometimes do not report an offset
-    .or_else(|_| PolarsResult::Ok(Buffer::<O>::from(vec![O::default()])))?;
+        options,
+    )?;
+    if options.validate_offsets {
+        let values_length = field_nodes
+            .front()
+            .filter(|_| length as i64 == field_node.length())
+            .map(|node| node.length() as usize);
+        validate_offsets(name, &offsets, values_length)?;
+    }
 
     let last_offset = offsets.last().unwrap().to_usize();
 
@@ -77,6 +85,7 @@ where
         Some(last_offset),
         version,
         scratch,
+        options,
     )?;
     ListArray::try_new(dtype, offsets.try_into()?, values, validity)
 }
@@ -86,6 +95,7 @@ pub fn skip_list<O: Offset>(
     dtype: &ArrowDataType,
     buffers: &mut VecDeque<IpcBuffer>,
     variadic_buffer_counts: &mut VecDeque<usize>,
+    version: Version,
 ) -> PolarsResult<()> {
     let _ = field_nodes.pop_front().ok_or_else(|| {
         polars_err!(
@@ -102,5 +112,5 @@ pub fn skip_list<O: Offset>(
 
     let dtype = ListArray::<O>::get_child_type(dtype);
 
-    skip(field_nodes, dtype, buffers, variadic_buffer_counts)
+    skip(field_nodes, dtype, buffers, variadic_buffer_counts, version)
 }
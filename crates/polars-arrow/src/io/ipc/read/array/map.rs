@@ -6,9 +6,8 @@ use polars_error::{PolarsResult, polars_err};
 use super::super::super::IpcField;
 use super::super::deserialize::{read, skip};
 use super::super::read_basic::*;
-use super::super::{Compression, Dictionaries, IpcBuffer, Node, Version};
+use super::super::{Compression, Dictionaries, IpcBuffer, Node, ReadOptions, Version};
 use crate::array::MapArray;
-use crate::buffer::Buffer;
 use crate::datatypes::ArrowDataType;
 use crate::io::ipc::read::array::{try_get_array_length, try_get_field_node};
 
@@ -17,6 +16,7 @@ pub fn read_map<R: Read + Seek>(
     field_nodes: &mut VecDeque<Node>,
     variadic_buffer_counts: &mut VecDeque<usize>,
     dtype: ArrowDataType,
+    name: &str,
     ipc_field: &IpcField,
     buffers: &mut VecDeque<IpcBuffer>,
     reader: &mut R,
@@ -27,6 +27,7 @@ pub fn read_map<R: Read + Seek>(
     limit: Option<usize>,
     version: Version,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<MapArray> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
 
@@ -39,21 +40,28 @@ pub fn read_map<R: Read + Seek>(
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let length = try_get_array_length(field_node, limit)?;
 
-    let offsets = read_buffer::<i32, _>(
+    let offsets = read_offsets::<i32, _>(
         buffers,
-        1 + length,
+        length,
         reader,
         block_offset,
         is_little_endian,
         compression,
         scratch,
-    )
-    // Older versions of the IPC format sometimes do not report an offset
-    .or_else(|_| PolarsResult::Ok(Buffer::<i32>::from(vec![0i32])))?;
+        options,
+    )?;
+    if options.validate_offsets {
+        let values_length = field_nodes
+            .front()
+            .filter(|_| length as i64 == field_node.length())
+            .map(|node| node.length() as usize);
+        validate_offsets(name, &offsets, values_length)?;
+    }
 
     let field = MapArray::get_field(&dtype);
 
@@ -73,6 +81,7 @@ pub fn read_map<R: Read + Seek>(
         Some(last_offset),
         version,
         scratch,
+        options,
     )?;
     MapArray::try_new(dtype, offsets.try_into()?, field, validity)
 }
@@ -82,6 +91,7 @@ pub fn skip_map(
     dtype: &ArrowDataType,
     buffers: &mut VecDeque<IpcBuffer>,
     variadic_buffer_counts: &mut VecDeque<usize>,
+    version: Version,
 ) -> PolarsResult<()> {
     let _ = field_nodes.pop_front().ok_or_else(|| {
         polars_err!(
@@ -98,5 +108,5 @@ pub fn skip_map(
 
     let dtype = MapArray::get_field(dtype).dtype();
 
-    skip(field_nodes, dtype, buffers, variadic_buffer_counts)
+    skip(field_nodes, dtype, buffers, variadic_buffer_counts, version)
 }
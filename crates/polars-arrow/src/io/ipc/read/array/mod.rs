@@ -29,7 +29,7 @@ pub use binview::*;
 pub use map::*;
 use polars_error::{PolarsResult, *};
 
-use super::{Compression, IpcBuffer, Node, OutOfSpecKind};
+use super::{Compression, IpcBuffer, Node, OutOfSpecKind, ReadOptions};
 use crate::datatypes::ArrowDataType;
 
 fn try_get_field_node<'a>(
@@ -4,7 +4,7 @@ use std::io::{Read, Seek};
 use polars_error::{PolarsResult, polars_err};
 
 use super::super::read_basic::*;
-use super::super::{Compression, IpcBuffer, Node};
+use super::super::{Compression, IpcBuffer, Node, ReadOptions};
 use crate::array::PrimitiveArray;
 use crate::datatypes::ArrowDataType;
 use crate::io::ipc::read::array::{try_get_array_length, try_get_field_node};
@@ -21,6 +21,7 @@ pub fn read_primitive<T: NativeType, R: Read + Seek>(
     compression: Option<Compression>,
     limit: Option<usize>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<PrimitiveArray<T>>
 where
     Vec<u8>: TryInto<T::Bytes>,
@@ -36,6 +37,7 @@ where
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let length = try_get_array_length(field_node, limit)?;
@@ -48,6 +50,7 @@ where
         is_little_endian,
         compression,
         scratch,
+        options,
     )?;
     PrimitiveArray::<T>::try_new(dtype, values, validity)
 }
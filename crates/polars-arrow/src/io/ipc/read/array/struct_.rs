@@ -6,7 +6,7 @@ use polars_error::{PolarsResult, polars_err};
 use super::super::super::IpcField;
 use super::super::deserialize::{read, skip};
 use super::super::read_basic::*;
-use super::super::{Compression, Dictionaries, IpcBuffer, Node, Version};
+use super::super::{Compression, Dictionaries, IpcBuffer, Node, ReadOptions, Version};
 use super::try_get_array_length;
 use crate::array::StructArray;
 use crate::datatypes::ArrowDataType;
@@ -27,6 +27,7 @@ pub fn read_struct<R: Read + Seek>(
     limit: Option<usize>,
     version: Version,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<StructArray> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
     let length = try_get_array_length(field_node, limit)?;
@@ -40,6 +41,7 @@ pub fn read_struct<R: Read + Seek>(
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let fields = StructArray::get_fields(&dtype);
@@ -62,6 +64,7 @@ pub fn read_struct<R: Read + Seek>(
                 limit,
                 version,
                 scratch,
+                options,
             )
         })
         .collect::<PolarsResult<Vec<_>>>()?;
@@ -74,6 +77,7 @@ pub fn skip_struct(
     dtype: &ArrowDataType,
     buffers: &mut VecDeque<IpcBuffer>,
     variadic_buffer_counts: &mut VecDeque<usize>,
+    version: Version,
 ) -> PolarsResult<()> {
     let _ = field_nodes.pop_front().ok_or_else(|| {
         polars_err!(
@@ -87,7 +91,13 @@ pub fn skip_struct(
 
     let fields = StructArray::get_fields(dtype);
 
-    fields
-        .iter()
-        .try_for_each(|field| skip(field_nodes, field.dtype(), buffers, variadic_buffer_counts))
+    fields.iter().try_for_each(|field| {
+        skip(
+            field_nodes,
+            field.dtype(),
+            buffers,
+            variadic_buffer_counts,
+            version,
+        )
+    })
 }
@@ -6,7 +6,7 @@ use polars_error::{PolarsResult, polars_err};
 use super::super::super::IpcField;
 use super::super::deserialize::{read, skip};
 use super::super::read_basic::*;
-use super::super::{Compression, Dictionaries, IpcBuffer, Node, Version};
+use super::super::{Compression, Dictionaries, IpcBuffer, Node, ReadOptions, Version};
 use crate::array::UnionArray;
 use crate::datatypes::{ArrowDataType, UnionMode};
 use crate::io::ipc::read::array::{try_get_array_length, try_get_field_node};
@@ -26,6 +26,7 @@ pub fn read_union<R: Read + Seek>(
     limit: Option<usize>,
     version: Version,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<UnionArray> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
 
@@ -45,6 +46,7 @@ pub fn read_union<R: Read + Seek>(
         is_little_endian,
         compression,
         scratch,
+        options,
     )?;
 
     let offsets = if let ArrowDataType::Union(u) = &dtype {
@@ -57,6 +59,7 @@ pub fn read_union<R: Read + Seek>(
                 is_little_endian,
                 compression,
                 scratch,
+                options,
             )?)
         } else {
             None
@@ -85,6 +88,7 @@ pub fn read_union<R: Read + Seek>(
                 None,
                 version,
                 scratch,
+                options,
             )
         })
         .collect::<PolarsResult<Vec<_>>>()?;
@@ -97,28 +101,42 @@ pub fn skip_union(
     dtype: &ArrowDataType,
     buffers: &mut VecDeque<IpcBuffer>,
     variadic_buffer_counts: &mut VecDeque<usize>,
+    version: Version,
 ) -> PolarsResult<()> {
     let _ = field_nodes.pop_front().ok_or_else(|| {
         polars_err!(
-            oos = "IPC: unable to fetch the field for struct. The file or stream is corrupted."
+            oos = "IPC: unable to fetch the field for union. The file or stream is corrupted."
         )
     })?;
 
+    if version != Version::V5 {
+        let _ = buffers
+            .pop_front()
+            .ok_or_else(|| polars_err!(oos = "IPC: missing validity buffer."))?;
+    };
     let _ = buffers
         .pop_front()
-        .ok_or_else(|| polars_err!(oos = "IPC: missing validity buffer."))?;
+        .ok_or_else(|| polars_err!(oos = "IPC: missing types buffer."))?;
     if let ArrowDataType::Union(u) = dtype {
-        assert!(u.mode == UnionMode::Dense);
-        let _ = buffers
-            .pop_front()
-            .ok_or_else(|| polars_err!(oos = "IPC: missing offsets buffer."))?;
+        // only dense unions have an offsets buffer
+        if u.mode == UnionMode::Dense {
+            let _ = buffers
+                .pop_front()
+                .ok_or_else(|| polars_err!(oos = "IPC: missing offsets buffer."))?;
+        }
     } else {
         unreachable!()
     };
 
     let fields = UnionArray::get_fields(dtype);
 
-    fields
-        .iter()
-        .try_for_each(|field| skip(field_nodes, field.dtype(), buffers, variadic_buffer_counts))
+    fields.iter().try_for_each(|field| {
+        skip(
+            field_nodes,
+            field.dtype(),
+            buffers,
+            variadic_buffer_counts,
+            version,
+        )
+    })
 }
@@ -12,6 +12,7 @@ use crate::offset::Offset;
 pub fn read_utf8<O: Offset, R: Read + Seek>(
     field_nodes: &mut VecDeque<Node>,
     dtype: ArrowDataType,
+    name: &str,
     buffers: &mut VecDeque<IpcBuffer>,
     reader: &mut R,
     block_offset: u64,
@@ -19,6 +20,7 @@ pub fn read_utf8<O: Offset, R: Read + Seek>(
     compression: Option<Compression>,
     limit: Option<usize>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<Utf8Array<O>> {
     let field_node = try_get_field_node(field_nodes, &dtype)?;
 
@@ -31,21 +33,29 @@ pub fn read_utf8<O: Offset, R: Read + Seek>(
         compression,
         limit,
         scratch,
+        options,
     )?;
 
     let length = try_get_array_length(field_node, limit)?;
 
-    let offsets: Buffer<O> = read_buffer(
+    let offsets: Buffer<O> = read_offsets(
         buffers,
-        1 + length,
+        length,
         reader,
         block_offset,
         is_little_endian,
         compression,
         scratch,
-    )
-    // Older versions of the IPC format sometimes do not report an offset
-    .or_else(|_| PolarsResult::Ok(Buffer::<O>::from(vec![O::default()])))?;
+        options,
+    )?;
+    if options.validate_offsets {
+        // the length of a compressed values buffer is only known once it is decompressed
+        let values_length = buffers
+            .front()
+            .filter(|_| length as i64 == field_node.length() && compression.is_none())
+            .map(|buffer| buffer.length() as usize);
+        validate_offsets(name, &offsets, values_length)?;
+    }
 
     let last_offset = offsets.last().unwrap().to_usize();
     let values = read_buffer(
@@ -56,6 +66,7 @@ pub fn read_utf8<O: Offset, R: Read + Seek>(
         is_little_endian,
         compression,
         scratch,
+        options,
     )?;
 
     Utf8Array::<O>::try_new(dtype, offsets.try_into()?, values, validity)
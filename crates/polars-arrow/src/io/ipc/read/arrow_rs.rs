@@ -0,0 +1,155 @@
+//! Conversion of decoded record batches into arrays of the `arrow` crate (arrow-rs).
+use std::io::{Read, Seek};
+use std::sync::Arc;
+
+use arrow_array::ffi::{FFI_ArrowArray, FFI_ArrowSchema, from_ffi};
+use arrow_array::{RecordBatch, RecordBatchOptions, make_array};
+use arrow_schema::Schema;
+use polars_error::{PolarsResult, polars_err};
+
+use super::{Dictionaries, FileMetadata, ReadOptions, read_batch};
+use crate::array::Array;
+use crate::datatypes::Field;
+use crate::ffi::{export_array_to_c, export_field_to_c};
+use crate::record_batch::RecordBatchT;
+
+/// Reads the record batch at position `index` from the reader and converts it into an arrow-rs
+/// [`RecordBatch`]. See [`read_batch`] for the arguments.
+///
+/// The conversion goes through the C data interface, such that the buffers of the decoded
+/// arrays are shared with the arrow-rs arrays instead of being copied.
+#[allow(clippy::too_many_arguments)]
+pub fn read_record_batch_arrow_rs<R: Read + Seek>(
+    reader: &mut R,
+    dictionaries: &Dictionaries,
+    metadata: &FileMetadata,
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
+    index: usize,
+    message_scratch: &mut Vec<u8>,
+    data_scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatch> {
+    let chunk = read_batch(
+        reader,
+        dictionaries,
+        metadata,
+        projection,
+        limit,
+        index,
+        message_scratch,
+        data_scratch,
+        options,
+    )?;
+    to_arrow_rs(chunk)
+}
+
+/// Converts a [`RecordBatchT`] into an arrow-rs [`RecordBatch`] through the C data interface.
+pub fn to_arrow_rs(chunk: RecordBatchT<Box<dyn Array>>) -> PolarsResult<RecordBatch> {
+    let length = chunk.len();
+    let (schema, arrays) = chunk.into_schema_and_arrays();
+    let (fields, arrays): (Vec<_>, Vec<_>) = schema
+        .iter_values()
+        .zip(arrays)
+        .map(|(field, array)| array_to_arrow_rs(field, array))
+        .collect::<PolarsResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let options = RecordBatchOptions::new().with_row_count(Some(length));
+    RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &options).map_err(
+        |err| polars_err!(ComputeError: "unable to convert into an arrow-rs record batch: {err}"),
+    )
+}
+
+fn array_to_arrow_rs(
+    field: &Field,
+    array: Box<dyn Array>,
+) -> PolarsResult<(arrow_schema::Field, arrow_array::ArrayRef)> {
+    // SAFETY: both are `#[repr(C)]` definitions of the structs of the C data interface
+    let schema: FFI_ArrowSchema = unsafe { std::mem::transmute(export_field_to_c(field)) };
+    let array: FFI_ArrowArray = unsafe { std::mem::transmute(export_array_to_c(array)) };
+
+    let field = arrow_schema::Field::try_from(&schema).map_err(
+        |err| polars_err!(ComputeError: "unable to convert into an arrow-rs field: {err}"),
+    )?;
+    // SAFETY: the array and its schema were just exported from a valid array
+    let data = unsafe { from_ffi(array, &schema) }.map_err(
+        |err| polars_err!(ComputeError: "unable to convert into an arrow-rs array: {err}"),
+    )?;
+    Ok((field, make_array(data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int32Type;
+
+    use super::*;
+    use crate::array::{Int32Array, Utf8Array, Utf8ViewArray};
+    use crate::datatypes::{ArrowDataType, ArrowSchema};
+    use crate::io::ipc::read::read_file_metadata;
+    use crate::io::ipc::write::{FileWriter, WriteOptions};
+
+    #[test]
+    fn read_into_arrow_rs() -> PolarsResult<()> {
+        let columns = vec![
+            Int32Array::from([Some(1), None, Some(3)]).boxed(),
+            Utf8Array::<i32>::from([Some("a"), Some("bb"), None]).boxed(),
+            Utf8ViewArray::from_slice_values(["a long string that is not inlined", "x", "y"])
+                .boxed(),
+        ];
+        let schema = Arc::new(ArrowSchema::from_iter([
+            Field::new("ints".into(), ArrowDataType::Int32, true),
+            Field::new("strings".into(), ArrowDataType::Utf8, true),
+            Field::new("views".into(), ArrowDataType::Utf8View, false),
+        ]));
+        let chunk = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+        let mut writer = FileWriter::try_new(vec![], schema, None, WriteOptions::default())?;
+        writer.write(&chunk, None)?;
+        writer.finish()?;
+        let mut reader = Cursor::new(writer.into_inner());
+        let metadata = read_file_metadata(&mut reader)?;
+
+        let batch = read_record_batch_arrow_rs(
+            &mut reader,
+            &Default::default(),
+            &metadata,
+            None,
+            None,
+            0,
+            &mut vec![],
+            &mut vec![],
+            &Default::default(),
+        )?;
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "ints");
+        assert_eq!(schema.field(0).data_type(), &arrow_schema::DataType::Int32);
+        assert_eq!(schema.field(1).data_type(), &arrow_schema::DataType::Utf8);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &arrow_schema::DataType::Utf8View
+        );
+        assert!(!schema.field(2).is_nullable());
+
+        let ints = batch.column(0).as_primitive::<Int32Type>();
+        assert_eq!(ints.iter().collect::<Vec<_>>(), [Some(1), None, Some(3)]);
+        let strings = batch.column(1).as_string::<i32>();
+        assert_eq!(
+            strings.iter().collect::<Vec<_>>(),
+            [Some("a"), Some("bb"), None]
+        );
+        let views = batch.column(2).as_string_view();
+        assert_eq!(
+            views.iter().collect::<Vec<_>>(),
+            [
+                Some("a long string that is not inlined"),
+                Some("x"),
+                Some("y")
+            ]
+        );
+        Ok(())
+    }
+}
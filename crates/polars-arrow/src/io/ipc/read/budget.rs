@@ -0,0 +1,185 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use arrow_format::ipc::FieldRef;
+use polars_error::{PolarsError, PolarsResult, polars_bail, polars_err};
+
+use super::{MAX_NESTING_DEPTH, OutOfSpecKind};
+
+/// Bounds on the complexity of the schema and of the messages of an IPC file or stream, which
+/// bound the work of reading untrusted input. Every bound is checked before any of the work it
+/// bounds, e.g. before the fields of a schema are deserialized or before a column of a record
+/// batch is decoded.
+///
+/// The defaults are generous, such that only crafted input exceeds them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComplexityBudget {
+    /// The deepest that the fields of the schema may be nested.
+    pub max_depth: usize,
+    /// The largest number of fields of the schema, including the fields of nested types.
+    pub max_fields: usize,
+    /// The largest number of buffers of a message.
+    pub max_buffers: usize,
+    /// The largest number of field nodes of a message.
+    pub max_nodes: usize,
+}
+
+impl Default for ComplexityBudget {
+    fn default() -> Self {
+        Self {
+            max_depth: MAX_NESTING_DEPTH,
+            max_fields: 1 << 20,
+            max_buffers: 1 << 22,
+            max_nodes: 1 << 20,
+        }
+    }
+}
+
+impl ComplexityBudget {
+    /// Errors when the schema of `fields` is nested deeper than [`ComplexityBudget::max_depth`]
+    /// or has more than [`ComplexityBudget::max_fields`] fields. Walks the fields with an
+    /// explicit stack, as it guards the recursive deserialization of the fields.
+    pub(crate) fn check_fields<'a>(
+        &self,
+        fields: impl IntoIterator<Item = arrow_format::ipc::planus::Result<FieldRef<'a>>>,
+    ) -> PolarsResult<()> {
+        let mut stack = fields
+            .into_iter()
+            .map(|field| Ok((field?, 1)))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let mut count = stack.len();
+        if count > self.max_fields {
+            polars_bail!(
+                oos = OutOfSpecKind::TooManyFields {
+                    limit: self.max_fields
+                }
+            );
+        }
+        while let Some((field, depth)) = stack.pop() {
+            if depth > self.max_depth {
+                polars_bail!(
+                    oos = OutOfSpecKind::NestingTooDeep {
+                        limit: self.max_depth
+                    }
+                );
+            }
+            if let Some(children) = field.children()? {
+                count += children.len();
+                if count > self.max_fields {
+                    polars_bail!(
+                        oos = OutOfSpecKind::TooManyFields {
+                            limit: self.max_fields
+                        }
+                    );
+                }
+                for child in children {
+                    stack.push((child?, depth + 1));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Errors when `batch` has more buffers than [`ComplexityBudget::max_buffers`] or more
+    /// field nodes than [`ComplexityBudget::max_nodes`].
+    pub(crate) fn check_record_batch(
+        &self,
+        batch: arrow_format::ipc::RecordBatchRef,
+    ) -> PolarsResult<()> {
+        let buffers = batch
+            .buffers()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferBuffers(err)))?
+            .map_or(0, |buffers| buffers.len());
+        if buffers > self.max_buffers {
+            polars_bail!(
+                oos = OutOfSpecKind::TooManyBuffers {
+                    count: buffers,
+                    limit: self.max_buffers
+                }
+            );
+        }
+        let nodes = batch
+            .nodes()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferNodes(err)))?
+            .map_or(0, |nodes| nodes.len());
+        if nodes > self.max_nodes {
+            polars_bail!(
+                oos = OutOfSpecKind::TooManyNodes {
+                    count: nodes,
+                    limit: self.max_nodes
+                }
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Bounds on the decoding of every selected column of a record batch, e.g. to preview a file
+/// within a time budget. A column that exceeds them is not decoded, or its decoding is aborted,
+/// and it is replaced by an all-null array, whose error is reported in
+/// [`ReadDiagnostics::column_errors`](super::ReadDiagnostics::column_errors) regardless of
+/// [`ReadOptions::column_error_policy`](super::ReadOptions::column_error_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ColumnBudget {
+    /// The largest size in bytes of the buffers of a column, as stored in its message, e.g.
+    /// compressed. Checked before the column is decoded.
+    pub max_bytes: Option<usize>,
+    /// The longest time that decoding a column may take. Checked before every buffer of the
+    /// column is read, such that a column is aborted after its buffer that exceeds it.
+    pub max_duration: Option<Duration>,
+}
+
+thread_local! {
+    /// The time by which the column that is being decoded on this thread exceeds its
+    /// [`ColumnBudget::max_duration`], and whether it was aborted for exceeding it.
+    static COLUMN_DEADLINE: Cell<Option<(Instant, bool)>> = const { Cell::new(None) };
+}
+
+/// Why the decoding of a column under a [`ColumnBudget`] failed.
+pub(crate) enum ColumnFailure {
+    /// The column failed to decode.
+    Error(PolarsError),
+    /// The column was aborted for exceeding [`ColumnBudget::max_duration`].
+    Timeout(PolarsError),
+}
+
+impl ColumnBudget {
+    /// Runs `decode`, the decoding of a column on this thread, under
+    /// [`ColumnBudget::max_duration`]. A column that fails because it exceeded the duration
+    /// fails with [`ColumnFailure::Timeout`], any other error is a [`ColumnFailure::Error`],
+    /// even if it occurred past the duration.
+    pub(crate) fn decode<T>(
+        &self,
+        decode: impl FnOnce() -> PolarsResult<T>,
+    ) -> Result<T, ColumnFailure> {
+        let Some(max_duration) = self.max_duration else {
+            return decode().map_err(ColumnFailure::Error);
+        };
+        let deadline = Instant::now() + max_duration;
+        let previous = COLUMN_DEADLINE.replace(Some((deadline, false)));
+        let result = decode();
+        let timed_out = COLUMN_DEADLINE
+            .replace(previous)
+            .is_some_and(|(_, timed_out)| timed_out);
+        result.map_err(|error| {
+            if timed_out {
+                ColumnFailure::Timeout(error)
+            } else {
+                ColumnFailure::Error(error)
+            }
+        })
+    }
+}
+
+/// Errors when the column that is being decoded on this thread exceeded its
+/// [`ColumnBudget::max_duration`], marking it as aborted for it.
+pub(crate) fn check_column_deadline() -> PolarsResult<()> {
+    match COLUMN_DEADLINE.get() {
+        Some((deadline, _)) if Instant::now() >= deadline => {
+            COLUMN_DEADLINE.set(Some((deadline, true)));
+            polars_bail!(ComputeError: "IPC: decoding the column exceeded its time budget")
+        },
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,63 @@
+//! Export of decoded record batches through the Arrow C data interface.
+use std::io::{Read, Seek};
+
+use polars_error::PolarsResult;
+
+use super::{Dictionaries, FileMetadata, ReadOptions, read_batch};
+use crate::array::Array;
+use crate::ffi::{ArrowArray, ArrowSchema, export_array_to_c, export_field_to_c};
+use crate::record_batch::RecordBatchT;
+
+/// A record batch exported through the C data interface, e.g. to hand it to a consumer in
+/// another language.
+///
+/// Every [`ArrowArray`] owns the decoded array that it exports, which is freed once the array is
+/// released by its consumer, or once it is dropped if it was not handed over.
+pub struct CDataBatch {
+    /// The number of rows of the record batch.
+    pub length: usize,
+    /// The exported field and array of every column of the record batch, in order.
+    pub columns: Vec<(ArrowSchema, ArrowArray)>,
+}
+
+/// Reads the record batch at position `index` from the reader and exports it through the C
+/// data interface. See [`read_batch`] for the arguments.
+///
+/// The buffers of the decoded arrays are exported as they are, without being copied.
+#[allow(clippy::too_many_arguments)]
+pub fn read_record_batch_c_data<R: Read + Seek>(
+    reader: &mut R,
+    dictionaries: &Dictionaries,
+    metadata: &FileMetadata,
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
+    index: usize,
+    message_scratch: &mut Vec<u8>,
+    data_scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<CDataBatch> {
+    let chunk = read_batch(
+        reader,
+        dictionaries,
+        metadata,
+        projection,
+        limit,
+        index,
+        message_scratch,
+        data_scratch,
+        options,
+    )?;
+    Ok(to_c_data(chunk))
+}
+
+/// Exports a [`RecordBatchT`] through the C data interface.
+pub fn to_c_data(chunk: RecordBatchT<Box<dyn Array>>) -> CDataBatch {
+    let length = chunk.len();
+    let (schema, arrays) = chunk.into_schema_and_arrays();
+    let columns = schema
+        .iter_values()
+        .zip(arrays)
+        .map(|(field, array)| (export_field_to_c(field), export_array_to_c(array)))
+        .collect();
+    CDataBatch { length, columns }
+}
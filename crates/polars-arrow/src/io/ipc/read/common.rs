@@ -1,18 +1,363 @@
+use std::borrow::Cow;
 use std::collections::VecDeque;
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use polars_error::{PolarsResult, polars_bail, polars_err};
+use arrow_format::ipc::planus::ReadAsRoot;
+use polars_error::{PolarsError, PolarsResult, polars_bail, polars_ensure, polars_err};
+use polars_utils::IdxSize;
 use polars_utils::aliases::PlHashMap;
 use polars_utils::pl_str::PlSmallStr;
 
-use super::Dictionaries;
-use super::deserialize::{read, skip};
+use super::budget::{ColumnBudget, ColumnFailure, ComplexityBudget};
+use super::deserialize::{count_nodes, read, skip};
+use super::evolution::conform_to_schema;
+use super::parallel::{Parallelism, read_columns_parallel};
+use super::prefetch::BlockPrefetch;
+use super::shared_dictionary::{share_dictionaries, unify_dictionaries};
+use super::string_cache::{StringCache, intern_strings, relabel_interned_schema};
+use super::structure::check_field_nodes;
+use super::timezone::{coerce_timestamp_tz, relabel_schema};
+use super::{Compression, Dictionaries, DictionaryCache, IpcBuffer, Node, Version};
 use crate::array::*;
-use crate::datatypes::{ArrowDataType, ArrowSchema, Field};
+use crate::bitmap::Bitmap;
+use crate::compute::concatenate::concatenate;
+use crate::datatypes::{ArrowDataType, ArrowSchema, ArrowSchemaRef, Field, PhysicalType};
 use crate::io::ipc::read::OutOfSpecKind;
-use crate::io::ipc::{IpcField, IpcSchema};
+use crate::io::ipc::{IpcField, IpcSchema, write};
 use crate::record_batch::RecordBatchT;
+use crate::scalar::Scalar;
+use crate::types::NativeType;
+use crate::{match_integer_type, with_match_primitive_type_full};
+
+/// Supplies the storage of the buffers decoded by an IPC reader, e.g. to reuse the [`Vec`]s of
+/// released buffers, as [`BufferPool`](super::BufferPool) does, or to count the allocations of
+/// a read.
+///
+/// The allocator is called with the size in bytes of a buffer about to be decoded and returns a
+/// [`Vec`] that will hold it. Like any [`Vec<u8>`], its memory belongs to the global allocator:
+/// this does not place buffers in another allocator, e.g. an arena. Allocations that are not
+/// aligned for the buffer's type are replaced by a new one.
+#[derive(Clone)]
+pub struct BufferAllocator(Arc<dyn Fn(usize) -> Vec<u8> + Send + Sync>);
+
+impl BufferAllocator {
+    pub fn new(allocate: impl Fn(usize) -> Vec<u8> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(allocate))
+    }
+
+    /// Allocates a [`Vec`] for a buffer of `size` bytes.
+    pub fn allocate(&self, size: usize) -> Vec<u8> {
+        (self.0)(size)
+    }
+}
+
+impl std::fmt::Debug for BufferAllocator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferAllocator").finish_non_exhaustive()
+    }
+}
+
+/// Supplies the dictionaries that a file or stream refers to by id but does not contain, e.g.
+/// that are shared out-of-band by a registry of categories. Returns `None` for the ids that it
+/// does not know.
+#[derive(Clone)]
+pub struct DictionaryResolver(Arc<dyn Fn(i64) -> Option<Box<dyn Array>> + Send + Sync>);
+
+impl DictionaryResolver {
+    pub fn new(resolve: impl Fn(i64) -> Option<Box<dyn Array>> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(resolve))
+    }
+
+    /// A [`DictionaryResolver`] of the pre-populated `dictionaries`.
+    pub fn from_dictionaries(dictionaries: Dictionaries) -> Self {
+        Self::new(move |id| dictionaries.get(&id).cloned())
+    }
+
+    /// Returns the dictionary of `id`, if any.
+    pub fn resolve(&self, id: i64) -> Option<Box<dyn Array>> {
+        (self.0)(id)
+    }
+}
+
+impl std::fmt::Debug for DictionaryResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DictionaryResolver").finish_non_exhaustive()
+    }
+}
+
+/// Options declaring the behaviour of reading from IPC
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Only decode the first `max_columns` columns: in schema order, or in projection order
+    /// when a projection is given. The remaining columns are skipped.
+    pub max_columns: Option<usize>,
+    /// Sets the timezone of all `Timestamp` columns with a timezone to this timezone, including
+    /// the columns nested in lists and structs. Naive timestamps are left as they are.
+    pub timestamp_tz: Option<PlSmallStr>,
+    /// Whether `timestamp_tz` shifts the values to keep their wall-clock time, instead of only
+    /// relabeling the timezone. Requires the `timezones` feature.
+    pub shift_timestamps: bool,
+    /// Supplies the storage of decoded buffers, see [`BufferAllocator`].
+    pub allocator: Option<BufferAllocator>,
+    /// How the columns of every record batch are decoded.
+    pub parallelism: Parallelism,
+    /// What happens when a column fails to decode.
+    pub column_error_policy: ColumnErrorPolicy,
+    /// The largest window in bytes that a ZSTD compressed buffer may require to be decompressed,
+    /// protecting against untrusted input exhausting memory. Defaults to
+    /// [`DEFAULT_ZSTD_MAX_WINDOW_SIZE`].
+    pub zstd_max_window_size: Option<usize>,
+    /// The largest size in bytes that a compressed buffer, of any codec, may decompress to. It
+    /// is checked against the length that the buffer declares, or that its array implies,
+    /// before the decompressed buffer is allocated. Defaults to
+    /// [`DEFAULT_MAX_DECOMPRESSED_SIZE`].
+    pub max_decompressed_size: Option<usize>,
+    /// Whether top-level dictionary-encoded columns are read as their keys instead of as
+    /// [`DictionaryArray`]s. The keys are a [`PrimitiveArray`] of the declared index type of
+    /// their column, e.g. `UInt16` for a `Dictionary<UInt16, Utf8>` column. Their dictionaries
+    /// remain available by id, e.g. from
+    /// [`FileReader::dictionaries`](super::FileReader::dictionaries).
+    pub dictionary_keys: bool,
+    /// Whether the dictionaries of top-level dictionary-encoded columns are not read at all.
+    /// Implies [`ReadOptions::dictionary_keys`]: these columns are read as their keys and their
+    /// dictionaries can be resolved on demand, e.g. with
+    /// [`FileReader::resolve_dictionary`](super::FileReader::resolve_dictionary).
+    ///
+    /// Only applies to files; streams interleave their dictionaries with the record batches.
+    pub skip_dictionary_resolution: bool,
+    /// Splits record batches with more rows than this into multiple output batches of at most
+    /// this many rows each. The record batch is still decoded at once (compressed buffers can
+    /// only be decompressed whole); the output batches are zero-copy slices of it.
+    pub max_rows_per_output_batch: Option<usize>,
+    /// Reuses the dictionaries that were already decoded from an identical dictionary block,
+    /// e.g. of another file of the same dataset. See [`DictionaryCache`].
+    ///
+    /// Only applies to files.
+    pub dictionary_cache: Option<Arc<DictionaryCache>>,
+    /// Whether the output batches of [`ReadOptions::max_rows_per_output_batch`] are copied into
+    /// arrays of their own, whose buffers start at offset zero, instead of being zero-copy
+    /// slices of the decoded record batch. Required by consumers that do not support offsets,
+    /// e.g. across an FFI boundary.
+    ///
+    /// The values of dictionary arrays are not copied. Arrays that contain union or map arrays,
+    /// or nested dictionary arrays, are returned as slices.
+    pub contiguous: bool,
+    /// The schema that record batches are read into. Its columns are taken from the decoded
+    /// columns by name, in the order of this schema; columns that were not decoded, e.g. that
+    /// the file lacks, are filled with their value of [`ReadOptions::defaults`], or else with
+    /// nulls. A missing column that is not nullable and has no default is an error.
+    pub reader_schema: Option<ArrowSchemaRef>,
+    /// The default values of the columns of [`ReadOptions::reader_schema`] that are missing.
+    pub defaults: PlHashMap<PlSmallStr, Box<dyn Scalar>>,
+    /// Whether a stream whose last message is truncated, e.g. because its writer crashed, ends
+    /// before that message instead of failing to read it. The truncated tail is then available
+    /// from [`StreamReader::truncation`](super::StreamReader::truncation).
+    ///
+    /// Only applies to streams, including the messages of a file whose footer is missing. See
+    /// [`read_unfinished_file_metadata`](super::read_unfinished_file_metadata).
+    pub tolerate_truncation: bool,
+    /// Whether the offsets of every decoded string, binary, list and map array are checked to
+    /// increase monotonically and, for uncompressed buffers, to end at the length of the values.
+    /// Offsets that do not are reported with their column and index.
+    pub validate_offsets: bool,
+    /// Interns the values of the `Utf8` and `LargeUtf8` columns into a cache shared across
+    /// batches, which reads them as `Utf8View` columns. See [`StringCache`].
+    pub string_cache: Option<Arc<StringCache>>,
+    /// The alignment in bytes that the buffers of fixed-width values, e.g. of primitive arrays
+    /// and of offsets, start at, e.g. for SIMD kernels that require 64-byte aligned buffers.
+    /// Buffers that were not decoded at this alignment are copied. Must be a power of two;
+    /// `0` keeps the alignment of the allocation.
+    pub align: usize,
+    /// Prepends a column of this name that counts the rows of the file or stream, starting at
+    /// this offset. The column is not read but synthesized from the number of rows that precede
+    /// each batch, including the rows of record batches that were skipped, e.g. by a block
+    /// filter. It is taken into [`ReadOptions::reader_schema`] by name like any other column.
+    pub row_index: Option<(PlSmallStr, IdxSize)>,
+    /// Whether an array that declares no nulls must not carry a validity buffer. The declared
+    /// null count of every array is trusted, such that the validity buffer of an array that
+    /// declares no nulls is neither allocated nor read; with this option, one that is present
+    /// nonetheless, e.g. of a writer that under-counts its nulls, is an error instead of being
+    /// skipped unseen.
+    pub skip_validity_when_no_nulls: bool,
+    /// The largest number of variadic buffers that a view array, e.g. of `Utf8View`, may
+    /// declare. Larger counts are an error, as are counts that exceed the buffers of their
+    /// message regardless of this option, before any of their buffers is read.
+    pub max_variadic_buffers: Option<usize>,
+    /// Reads the record batches of a file from the last to the first, e.g. for scans of the
+    /// latest rows first. The dictionaries of the file, including its deltas, are still read
+    /// before the first record batch, and the row index of [`ReadOptions::row_index`] still
+    /// counts the rows in file order.
+    ///
+    /// Only applies to [`FileReader`](super::FileReader).
+    pub reverse: bool,
+    /// Reverses the order of the rows of every record batch. Together with
+    /// [`ReadOptions::reverse`], the rows of a file are read from the last to the first; the
+    /// limit of the reader then keeps the last rows of a record batch instead of its first.
+    ///
+    /// Only applies to [`FileReader`](super::FileReader).
+    pub reverse_rows: bool,
+    /// Whether every column of a record batch is checked to be of the type of the field of the
+    /// output schema at its index, which catches a projected schema whose order diverged from
+    /// the order in which the columns were read. This is always asserted in debug builds.
+    pub validate_projection: bool,
+    /// What happens when a dictionary-encoded column refers to a dictionary that was not read,
+    /// e.g. because its dictionary batch was skipped or is missing from the file.
+    pub missing_dictionary_policy: MissingDictionaryPolicy,
+    /// A flag that cancels the read once it is set, e.g. by a UI on another thread: the read
+    /// then fails with a [`PolarsError::ComputeError`] that it was cancelled before the next
+    /// record batch or column that it decodes, including the columns that are decoded in
+    /// parallel.
+    pub cancelled: Option<Arc<AtomicBool>>,
+    /// Stops the read after this many record batches were decoded, regardless of their number
+    /// of rows, e.g. to preview a file whose record batches vary widely in size. Record batches
+    /// that are skipped, e.g. by a block filter, are not counted.
+    pub max_batches: Option<usize>,
+    /// Whether a validity buffer that is shorter than its array is an error. Otherwise, as some
+    /// writers omit the trailing bytes of a validity buffer whose remaining bits are all set,
+    /// the missing bits are read as valid.
+    pub strict_validity_length: bool,
+    /// Merges the dictionaries of these dictionary-encoded columns into a single dictionary of
+    /// their distinct values, shared by these columns, whose keys are remapped to it, e.g. to
+    /// group or join by these columns on their keys. Merged for every output batch; columns
+    /// that were not read are ignored.
+    pub shared_dictionary_columns: Vec<PlSmallStr>,
+    /// Bounds the number of buffers and field nodes of every message, which are checked before
+    /// any of its columns is decoded. Its bounds on the schema apply to
+    /// [`read_file_metadata_with_budget`](super::read_file_metadata_with_budget) and
+    /// [`read_stream_metadata_with_budget`](super::read_stream_metadata_with_budget).
+    pub complexity_budget: ComplexityBudget,
+    /// Whether the field nodes of every message are checked to match the structure of its
+    /// schema before it is decoded: one node for every field, including nested fields, whose
+    /// length agrees with the length of its parent where that implies it. A divergence is
+    /// reported with the path of the field at which it occurs.
+    pub validate_structure: bool,
+    /// Whether the dictionary batches whose id no field of the schema refers to, e.g. of a
+    /// corrupt footer, are skipped instead of being an error. Their ids are reported in
+    /// [`ReadDiagnostics::orphan_dictionaries`].
+    pub skip_orphan_dictionaries: bool,
+    /// Supplies the dictionaries that dictionary-encoded columns refer to but that were not
+    /// read, e.g. that the file omits. Takes precedence over
+    /// [`ReadOptions::missing_dictionary_policy`], which applies to the ids that it does not
+    /// resolve. Called for every record batch that refers to such an id.
+    pub dictionary_resolver: Option<DictionaryResolver>,
+    /// Whether the view arrays, e.g. of `Utf8View`, that a message declares no variadic buffer
+    /// count for are read as having no variadic buffers instead of being an error, e.g. as some
+    /// producers omit the counts of the view arrays without variadic buffers, such as empty or
+    /// all-null ones.
+    pub tolerate_missing_variadic_counts: bool,
+    /// Bounds the size and the decoding time of every selected column, replacing the columns
+    /// that exceed them by all-null arrays, e.g. to preview a file within a time budget.
+    pub column_budget: ColumnBudget,
+    /// Reads the record batches that follow the record batch that a
+    /// [`FileReader`](super::FileReader) reads ahead of time and decodes them in parallel,
+    /// e.g. to decompress a compressed file on several threads.
+    pub block_prefetch: Option<BlockPrefetch>,
+}
+
+/// The default of [`ReadOptions::zstd_max_window_size`]: 128 MiB, the largest window that
+/// ZSTD decompresses without being explicitly allowed to.
+pub const DEFAULT_ZSTD_MAX_WINDOW_SIZE: usize = 1 << 27;
+
+/// The default of [`ReadOptions::max_decompressed_size`]: 4 GiB.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = u32::MAX as usize;
+
+impl ReadOptions {
+    pub(crate) fn zstd_max_window_size(&self) -> usize {
+        self.zstd_max_window_size
+            .unwrap_or(DEFAULT_ZSTD_MAX_WINDOW_SIZE)
+    }
+
+    /// Errors when a compressed buffer that decompresses to `size` bytes exceeds
+    /// [`ReadOptions::max_decompressed_size`].
+    pub(crate) fn check_decompressed_size(&self, size: usize) -> PolarsResult<()> {
+        let max_size = self
+            .max_decompressed_size
+            .unwrap_or(DEFAULT_MAX_DECOMPRESSED_SIZE);
+        polars_ensure!(
+            size <= max_size,
+            ComputeError: "IPC: a compressed buffer decompresses to {size} bytes, more than the \
+            limit of {max_size} bytes"
+        );
+        Ok(())
+    }
+
+    /// Whether top-level dictionary-encoded columns are read as their keys, as with
+    /// [`ReadOptions::dictionary_keys`], which [`ReadOptions::skip_dictionary_resolution`]
+    /// implies.
+    pub(crate) fn reads_dictionary_keys(&self) -> bool {
+        self.dictionary_keys || self.skip_dictionary_resolution
+    }
+
+    /// Whether `count` record batches reach [`ReadOptions::max_batches`].
+    pub(crate) fn reached_max_batches(&self, count: usize) -> bool {
+        self.max_batches
+            .is_some_and(|max_batches| count >= max_batches)
+    }
+
+    /// Fails iff the flag of [`ReadOptions::cancelled`] is set.
+    pub(crate) fn check_cancelled(&self) -> PolarsResult<()> {
+        match &self.cancelled {
+            Some(cancelled) if cancelled.load(Ordering::Relaxed) => {
+                polars_bail!(ComputeError: "IPC: the read was cancelled")
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What happens when a selected column of a record batch fails to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColumnErrorPolicy {
+    /// Fail the whole record batch.
+    #[default]
+    Fail,
+    /// Replace the column by an all-null array of its declared type and record the error in
+    /// [`ReadDiagnostics::column_errors`].
+    NullFill,
+}
+
+/// What happens when a dictionary-encoded column refers to a dictionary that was not read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MissingDictionaryPolicy {
+    /// Fail with [`OutOfSpecKind::MissingDictionary`].
+    #[default]
+    Fail,
+    /// Substitute an empty dictionary, such that every value of the column is null.
+    Empty,
+}
+
+/// An error of a column that was replaced by nulls.
+#[derive(Debug)]
+pub struct ColumnError {
+    /// The name of the column.
+    pub name: PlSmallStr,
+    /// The error that occurred while decoding it.
+    pub error: PolarsError,
+}
+
+/// What a read reports besides its record batches, as returned by
+/// [`FileReader::take_diagnostics`](super::FileReader::take_diagnostics) and
+/// [`StreamReader::take_diagnostics`](super::StreamReader::take_diagnostics). The functions that
+/// read a single record batch, e.g. [`read_record_batch`], discard them.
+#[derive(Debug, Default)]
+pub struct ReadDiagnostics {
+    /// The errors of the columns that were replaced by nulls under
+    /// [`ColumnErrorPolicy::NullFill`] or [`ReadOptions::column_budget`], in reading order.
+    pub column_errors: Vec<ColumnError>,
+    /// The ids of the dictionary batches that were skipped under
+    /// [`ReadOptions::skip_orphan_dictionaries`], in reading order.
+    pub orphan_dictionaries: Vec<i64>,
+}
+
+impl ReadDiagnostics {
+    /// Appends the diagnostics of `other`, which were reported after those of `self`.
+    pub(crate) fn append(&mut self, other: ReadDiagnostics) {
+        self.column_errors.extend(other.column_errors);
+        self.orphan_dictionaries.extend(other.orphan_dictionaries);
+    }
+}
 
 #[derive(Debug, Eq, PartialEq, Hash)]
 enum ProjectionResult<A> {
@@ -73,8 +418,10 @@ impl<A, I: Iterator<Item = A>> Iterator for ProjectionIter<'_, A, I> {
 }
 
 /// Returns a [`RecordBatchT`] from a reader.
-/// # Panic
-/// Panics iff the projection is not in increasing order (e.g. `[1, 0]` nor `[0, 1, 1]` are valid)
+///
+/// The columns of the batch are in the order of `projection`, which may be in any order: its
+/// columns are read in schema order and moved into its order. Errors if `projection` selects a
+/// column more than once or a column that is not in `fields`.
 #[allow(clippy::too_many_arguments)]
 pub fn read_record_batch<R: Read + Seek>(
     batch: arrow_format::ipc::RecordBatchRef,
@@ -88,17 +435,100 @@ pub fn read_record_batch<R: Read + Seek>(
     block_offset: u64,
     file_size: u64,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    read_record_batch_into(
+        batch,
+        fields,
+        ipc_schema,
+        projection,
+        limit,
+        dictionaries,
+        version,
+        reader,
+        block_offset,
+        file_size,
+        scratch,
+        &mut vec![],
+        options,
+    )
+}
+
+/// Returns a [`RecordBatchT`] from a reader, like [`read_record_batch`], whose columns are
+/// decoded into `columns` instead of into a newly allocated [`Vec`].
+///
+/// `columns` is cleared and moved into the returned batch, leaving it empty: its allocation is
+/// reused when it is handed back, e.g. from [`RecordBatchT::into_arrays`] of the previous batch.
+#[allow(clippy::too_many_arguments)]
+pub fn read_record_batch_into<R: Read + Seek>(
+    batch: arrow_format::ipc::RecordBatchRef,
+    fields: &ArrowSchema,
+    ipc_schema: &IpcSchema,
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
+    dictionaries: &Dictionaries,
+    version: arrow_format::ipc::MetadataVersion,
+    reader: &mut R,
+    block_offset: u64,
+    file_size: u64,
+    scratch: &mut Vec<u8>,
+    columns: &mut Vec<Box<dyn Array>>,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    read_record_batch_with_diagnostics(
+        batch,
+        fields,
+        ipc_schema,
+        projection,
+        limit,
+        dictionaries,
+        version,
+        reader,
+        block_offset,
+        file_size,
+        scratch,
+        columns,
+        options,
+        &mut ReadDiagnostics::default(),
+    )
+}
+
+/// Returns a [`RecordBatchT`] from a reader, like [`read_record_batch_into`], appending its
+/// diagnostics to `diagnostics`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn read_record_batch_with_diagnostics<R: Read + Seek>(
+    batch: arrow_format::ipc::RecordBatchRef,
+    fields: &ArrowSchema,
+    ipc_schema: &IpcSchema,
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
+    dictionaries: &Dictionaries,
+    version: arrow_format::ipc::MetadataVersion,
+    reader: &mut R,
+    block_offset: u64,
+    file_size: u64,
+    scratch: &mut Vec<u8>,
+    columns: &mut Vec<Box<dyn Array>>,
+    options: &ReadOptions,
+    diagnostics: &mut ReadDiagnostics,
 ) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
     assert_eq!(fields.len(), ipc_schema.fields.len());
+    options.complexity_budget.check_record_batch(batch)?;
     let buffers = batch
         .buffers()
         .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferBuffers(err)))?
         .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageBuffers))?;
-    let mut variadic_buffer_counts = batch
-        .variadic_buffer_counts()
-        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferRecordBatches(err)))?
-        .map(|v| v.iter().map(|v| v as usize).collect::<VecDeque<usize>>())
-        .unwrap_or_else(VecDeque::new);
+    let mut variadic_buffer_counts =
+        read_variadic_buffer_counts(batch, buffers.len(), options.max_variadic_buffers)?;
+    if options.tolerate_missing_variadic_counts {
+        let view_arrays = fields
+            .iter_values()
+            .map(|field| count_view_arrays(&field.dtype))
+            .sum::<usize>();
+        if view_arrays > variadic_buffer_counts.len() {
+            variadic_buffer_counts.resize(view_arrays, 0);
+        }
+    }
     let mut buffers: VecDeque<arrow_format::ipc::BufferRef> = buffers.iter().collect();
 
     // check that the sum of the sizes of all buffers is <= than the size of the file
@@ -125,70 +555,139 @@ pub fn read_record_batch<R: Read + Seek>(
         .nodes()
         .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferNodes(err)))?
         .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageNodes))?;
+    if options.validate_structure {
+        let length = batch
+            .length()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::MissingData))?
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+        check_field_nodes(fields, length, field_nodes.iter())?;
+    }
     let mut field_nodes = field_nodes.iter().collect::<VecDeque<_>>();
 
-    let columns = if let Some(projection) = projection {
+    // a projection out of schema order is read in schema order and reordered after
+    let ordered = match projection {
+        Some(projection) => {
+            if let Some(&index) = projection.iter().find(|&&index| index >= fields.len()) {
+                polars_bail!(
+                    OutOfBounds: "IPC: the projected field index {index} is out of bounds for a schema of {} fields",
+                    fields.len()
+                );
+            }
+            let ordered = (!projection.is_sorted_by(|a, b| a < b))
+                .then(|| OrderedProjection::new(projection));
+            polars_ensure!(
+                !ordered.as_ref().is_some_and(OrderedProjection::has_duplicates),
+                Duplicate: "IPC: the projection selects a field more than once"
+            );
+            ordered
+        },
+        None => None,
+    };
+    let output_projection = projection;
+    let projection = ordered
+        .as_ref()
+        .map_or(projection, |ordered| Some(ordered.read_indices()));
+
+    options.check_cancelled()?;
+    columns.clear();
+    columns.reserve(projection.map_or(fields.len(), |projection| projection.len()));
+    if projection.is_some_and(|projection| projection.is_empty()) {
+        // nothing to read, e.g. an empty schema or an empty projection, but the fields are
+        // still skipped, which checks that the batch has their nodes and buffers
+        for field in fields.iter_values() {
+            skip(
+                &mut field_nodes,
+                &field.dtype,
+                &mut buffers,
+                &mut variadic_buffer_counts,
+                version,
+            )?;
+        }
+    } else if options.parallelism != Parallelism::Serial {
+        columns.extend(read_columns_parallel(
+            fields,
+            ipc_schema,
+            projection,
+            field_nodes,
+            buffers,
+            variadic_buffer_counts,
+            reader,
+            dictionaries,
+            block_offset,
+            batch.compression().map_err(|err| {
+                polars_err!(oos = OutOfSpecKind::InvalidFlatbufferCompression(err))
+            })?,
+            limit,
+            version,
+            options,
+            &mut diagnostics.column_errors,
+        )?)
+    } else if let Some(projection) = projection {
         let projection = ProjectionIter::new(
             projection,
             fields.iter_values().zip(ipc_schema.fields.iter()),
         );
 
-        projection
-            .map(|maybe_field| match maybe_field {
-                ProjectionResult::Selected((field, ipc_field)) => Ok(Some(read(
-                    &mut field_nodes,
-                    &mut variadic_buffer_counts,
-                    field,
-                    ipc_field,
-                    &mut buffers,
-                    reader,
-                    dictionaries,
-                    block_offset,
-                    ipc_schema.is_little_endian,
-                    batch.compression().map_err(|err| {
-                        polars_err!(oos = OutOfSpecKind::InvalidFlatbufferCompression(err))
-                    })?,
-                    limit,
-                    version,
-                    scratch,
-                )?)),
+        for maybe_field in projection {
+            match maybe_field {
+                ProjectionResult::Selected((field, ipc_field)) => {
+                    options.check_cancelled()?;
+                    columns.push(read_column(
+                        &mut field_nodes,
+                        &mut variadic_buffer_counts,
+                        field,
+                        ipc_field,
+                        &mut buffers,
+                        reader,
+                        dictionaries,
+                        block_offset,
+                        ipc_schema.is_little_endian,
+                        batch.compression().map_err(|err| {
+                            polars_err!(oos = OutOfSpecKind::InvalidFlatbufferCompression(err))
+                        })?,
+                        limit,
+                        version,
+                        scratch,
+                        options,
+                        &mut diagnostics.column_errors,
+                    )?);
+                },
                 ProjectionResult::NotSelected((field, _)) => {
                     skip(
                         &mut field_nodes,
                         &field.dtype,
                         &mut buffers,
                         &mut variadic_buffer_counts,
+                        version,
                     )?;
-                    Ok(None)
                 },
-            })
-            .filter_map(|x| x.transpose())
-            .collect::<PolarsResult<Vec<_>>>()?
+            }
+        }
     } else {
-        fields
-            .iter_values()
-            .zip(ipc_schema.fields.iter())
-            .map(|(field, ipc_field)| {
-                read(
-                    &mut field_nodes,
-                    &mut variadic_buffer_counts,
-                    field,
-                    ipc_field,
-                    &mut buffers,
-                    reader,
-                    dictionaries,
-                    block_offset,
-                    ipc_schema.is_little_endian,
-                    batch.compression().map_err(|err| {
-                        polars_err!(oos = OutOfSpecKind::InvalidFlatbufferCompression(err))
-                    })?,
-                    limit,
-                    version,
-                    scratch,
-                )
-            })
-            .collect::<PolarsResult<Vec<_>>>()?
-    };
+        for (field, ipc_field) in fields.iter_values().zip(ipc_schema.fields.iter()) {
+            options.check_cancelled()?;
+            columns.push(read_column(
+                &mut field_nodes,
+                &mut variadic_buffer_counts,
+                field,
+                ipc_field,
+                &mut buffers,
+                reader,
+                dictionaries,
+                block_offset,
+                ipc_schema.is_little_endian,
+                batch.compression().map_err(|err| {
+                    polars_err!(oos = OutOfSpecKind::InvalidFlatbufferCompression(err))
+                })?,
+                limit,
+                version,
+                scratch,
+                options,
+                &mut diagnostics.column_errors,
+            )?);
+        }
+    }
 
     let length = batch
         .length()
@@ -198,11 +697,471 @@ pub fn read_record_batch<R: Read + Seek>(
         .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
     let length = limit.map(|limit| limit.min(length)).unwrap_or(length);
 
-    let mut schema: ArrowSchema = fields.iter_values().cloned().collect();
-    if let Some(projection) = projection {
+    let mut schema: ArrowSchema = fields
+        .iter_values()
+        .map(|field| decoded_field(field, options).into_owned())
+        .collect();
+    if let Some(projection) = output_projection {
         schema = schema.try_project_indices(projection).unwrap();
     }
-    RecordBatchT::try_new(length, Arc::new(schema), columns)
+    if let Some(ordered) = ordered {
+        *columns = ordered.reorder(std::mem::take(columns));
+    }
+    if options.validate_projection {
+        check_projected_columns(&schema, columns)?;
+    } else {
+        debug_assert!(check_projected_columns(&schema, columns).is_ok());
+    }
+    RecordBatchT::try_new(length, Arc::new(schema), std::mem::take(columns))
+}
+
+/// Checks that every column of `columns` is of the type of the field of `schema` at its index.
+/// See [`ReadOptions::validate_projection`].
+pub(crate) fn check_projected_columns(
+    schema: &ArrowSchema,
+    columns: &[Box<dyn Array>],
+) -> PolarsResult<()> {
+    polars_ensure!(
+        schema.len() == columns.len(),
+        ComputeError: "IPC: the output schema has {} fields but {} columns were read",
+        schema.len(),
+        columns.len()
+    );
+    for (index, (field, column)) in schema.iter_values().zip(columns).enumerate() {
+        polars_ensure!(
+            &field.dtype == column.dtype(),
+            ComputeError: "IPC: the field \"{}\" at index {index} of the output schema is of type {:?}, but its column is of type {:?}",
+            field.name,
+            field.dtype,
+            column.dtype()
+        );
+    }
+    Ok(())
+}
+
+/// Returns the field that is decoded for `field` when reading with `options`.
+pub(crate) fn decoded_field<'a>(field: &'a Field, options: &ReadOptions) -> Cow<'a, Field> {
+    match &field.dtype {
+        // the keys are laid out as a primitive array
+        ArrowDataType::Dictionary(key_type, _, _) if options.reads_dictionary_keys() => Cow::Owned(
+            Field::new(field.name.clone(), (*key_type).into(), field.is_nullable),
+        ),
+        _ => Cow::Borrowed(field),
+    }
+}
+
+/// Reads a column with [`read`]. Under [`ColumnErrorPolicy::NullFill`], a column that fails to
+/// decode is skipped instead and replaced by an all-null array, as is a column that exceeds
+/// [`ReadOptions::column_budget`]; their errors are appended to `column_errors`.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn read_column<R: Read + Seek>(
+    field_nodes: &mut VecDeque<Node>,
+    variadic_buffer_counts: &mut VecDeque<usize>,
+    field: &Field,
+    ipc_field: &IpcField,
+    buffers: &mut VecDeque<IpcBuffer>,
+    reader: &mut R,
+    dictionaries: &Dictionaries,
+    block_offset: u64,
+    is_little_endian: bool,
+    compression: Option<Compression>,
+    limit: Option<usize>,
+    version: Version,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    column_errors: &mut Vec<ColumnError>,
+) -> PolarsResult<Box<dyn Array>> {
+    let field = decoded_field(field, options);
+    let field = field.as_ref();
+    let budget = &options.column_budget;
+    let fallible =
+        options.column_error_policy == ColumnErrorPolicy::NullFill || budget.max_duration.is_some();
+    if !fallible && budget.max_bytes.is_none() {
+        return read(
+            field_nodes,
+            variadic_buffer_counts,
+            field,
+            ipc_field,
+            buffers,
+            reader,
+            dictionaries,
+            block_offset,
+            is_little_endian,
+            compression,
+            limit,
+            version,
+            scratch,
+            options,
+        );
+    }
+
+    // the column may be skipped, or fail half-way, so find where it ends to skip to its end
+    let span = ColumnSpan::new(
+        field_nodes,
+        variadic_buffer_counts,
+        &field.dtype,
+        buffers,
+        version,
+    )?;
+    let length: usize = field_nodes
+        .front()
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageNodes))?
+        .length()
+        .try_into()
+        .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+    let length = limit.map(|limit| limit.min(length)).unwrap_or(length);
+    let mut skip_column = |field_nodes: &mut VecDeque<Node>,
+                           variadic_buffer_counts: &mut VecDeque<usize>,
+                           buffers: &mut VecDeque<IpcBuffer>,
+                           error: PolarsError| {
+        span.skip_to_end(field_nodes, variadic_buffer_counts, buffers, error)
+            .map(|error| {
+                column_errors.push(ColumnError {
+                    name: field.name.clone(),
+                    error,
+                });
+                new_null_array(field.dtype.clone(), length)
+            })
+    };
+
+    if let Some(max_bytes) = budget.max_bytes {
+        let bytes = span.bytes(buffers)?;
+        if bytes > max_bytes {
+            let error = polars_err!(
+                ComputeError: "IPC: the {bytes} bytes of the buffers of the column exceed its budget of {max_bytes} bytes"
+            );
+            return skip_column(field_nodes, variadic_buffer_counts, buffers, error);
+        }
+    }
+    if !fallible {
+        return read(
+            field_nodes,
+            variadic_buffer_counts,
+            field,
+            ipc_field,
+            buffers,
+            reader,
+            dictionaries,
+            block_offset,
+            is_little_endian,
+            compression,
+            limit,
+            version,
+            scratch,
+            options,
+        );
+    }
+
+    let result = budget.decode(|| {
+        read(
+            field_nodes,
+            variadic_buffer_counts,
+            field,
+            ipc_field,
+            buffers,
+            reader,
+            dictionaries,
+            block_offset,
+            is_little_endian,
+            compression,
+            limit,
+            version,
+            scratch,
+            options,
+        )
+    });
+    match result {
+        Ok(array) => Ok(array),
+        Err(ColumnFailure::Error(error))
+            if options.column_error_policy == ColumnErrorPolicy::Fail =>
+        {
+            Err(error)
+        },
+        Err(ColumnFailure::Error(error) | ColumnFailure::Timeout(error)) => {
+            skip_column(field_nodes, variadic_buffer_counts, buffers, error)
+        },
+    }
+}
+
+/// Where the column at the front of the queues of a record batch ends: the number of field
+/// nodes, buffers and variadic buffer counts that remain in the queues after it.
+struct ColumnSpan {
+    field_nodes: usize,
+    buffers: usize,
+    variadic_buffer_counts: usize,
+}
+
+impl ColumnSpan {
+    /// Returns the span of the column of `dtype` at the front of the queues, without taking it.
+    ///
+    /// Only the front of the queues that the column can occupy is walked: its field nodes and
+    /// variadic buffer counts are determined by `dtype`, and every field node has at most three
+    /// buffers besides the variadic buffers of view arrays.
+    fn new(
+        field_nodes: &VecDeque<Node>,
+        variadic_buffer_counts: &VecDeque<usize>,
+        dtype: &ArrowDataType,
+        buffers: &VecDeque<IpcBuffer>,
+        version: Version,
+    ) -> PolarsResult<Self> {
+        let n_nodes = count_nodes(dtype).min(field_nodes.len());
+        let n_variadic = count_view_arrays(dtype).min(variadic_buffer_counts.len());
+        let mut column_variadic_buffer_counts = variadic_buffer_counts
+            .range(..n_variadic)
+            .copied()
+            .collect::<VecDeque<_>>();
+        let n_buffers = column_variadic_buffer_counts
+            .iter()
+            .fold(3 * n_nodes, |acc, count| acc.saturating_add(*count))
+            .min(buffers.len());
+        let mut column_nodes = field_nodes.range(..n_nodes).cloned().collect();
+        let mut column_buffers = buffers.range(..n_buffers).cloned().collect();
+        skip(
+            &mut column_nodes,
+            dtype,
+            &mut column_buffers,
+            &mut column_variadic_buffer_counts,
+            version,
+        )?;
+        Ok(Self {
+            field_nodes: field_nodes.len() - n_nodes + column_nodes.len(),
+            buffers: buffers.len() - n_buffers + column_buffers.len(),
+            variadic_buffer_counts: variadic_buffer_counts.len() - n_variadic
+                + column_variadic_buffer_counts.len(),
+        })
+    }
+
+    /// Returns the size in bytes of the buffers of the column, as stored in its message, given
+    /// the queue of buffers that it is at the front of.
+    fn bytes(&self, buffers: &VecDeque<IpcBuffer>) -> PolarsResult<usize> {
+        buffers
+            .range(..buffers.len() - self.buffers)
+            .map(|buffer| {
+                usize::try_from(buffer.length())
+                    .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))
+            })
+            .sum()
+    }
+
+    /// Takes what remains of the column from the front of the queues, e.g. after it failed to
+    /// decode with `error`, returning `error`. Fails with `error` if more than the column was
+    /// taken.
+    fn skip_to_end(
+        &self,
+        field_nodes: &mut VecDeque<Node>,
+        variadic_buffer_counts: &mut VecDeque<usize>,
+        buffers: &mut VecDeque<IpcBuffer>,
+        error: PolarsError,
+    ) -> PolarsResult<PolarsError> {
+        if field_nodes.len() < self.field_nodes
+            || buffers.len() < self.buffers
+            || variadic_buffer_counts.len() < self.variadic_buffer_counts
+        {
+            return Err(error);
+        }
+        field_nodes.drain(..field_nodes.len() - self.field_nodes);
+        buffers.drain(..buffers.len() - self.buffers);
+        variadic_buffer_counts.drain(..variadic_buffer_counts.len() - self.variadic_buffer_counts);
+        Ok(error)
+    }
+}
+
+/// Returns the codec that the record batch `message`, which was already decoded, declares; `None`
+/// if it is not compressed.
+pub(crate) fn batch_compression(message: &[u8]) -> Option<write::Compression> {
+    let message = arrow_format::ipc::MessageRef::read_as_root(message).ok()?;
+    let arrow_format::ipc::MessageHeaderRef::RecordBatch(batch) = message.header().ok()?? else {
+        return None;
+    };
+    Some(match batch.compression().ok()??.codec().ok()? {
+        arrow_format::ipc::CompressionType::Lz4Frame => write::Compression::LZ4,
+        arrow_format::ipc::CompressionType::Zstd => write::Compression::ZSTD,
+    })
+}
+
+/// Returns the number of variadic buffers of every view array of `batch`, checking that each
+/// is at most `max_variadic_buffers` and that together they are at most the `buffer_count`
+/// buffers of the message, such that no buffer is read for a crafted count.
+pub(crate) fn read_variadic_buffer_counts(
+    batch: arrow_format::ipc::RecordBatchRef,
+    buffer_count: usize,
+    max_variadic_buffers: Option<usize>,
+) -> PolarsResult<VecDeque<usize>> {
+    let Some(counts) = batch
+        .variadic_buffer_counts()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferRecordBatches(err)))?
+    else {
+        return Ok(VecDeque::new());
+    };
+    let mut remaining = buffer_count;
+    counts
+        .iter()
+        .map(|count| {
+            let limit = max_variadic_buffers.map_or(remaining, |max| max.min(remaining));
+            let invalid =
+                || polars_err!(oos = OutOfSpecKind::InvalidVariadicBufferCount { count, limit });
+            let count = usize::try_from(count).map_err(|_| invalid())?;
+            remaining = remaining.checked_sub(count).ok_or_else(invalid)?;
+            if max_variadic_buffers.is_some_and(|max| count > max) {
+                return Err(invalid());
+            }
+            Ok(count)
+        })
+        .collect()
+}
+
+/// Returns the number of view arrays, e.g. of `Utf8View`, of an array of `dtype`, including
+/// nested ones, each of which has a variadic buffer count. The values of dictionaries are not
+/// counted, as they are in dictionary batches.
+fn count_view_arrays(dtype: &ArrowDataType) -> usize {
+    use ArrowDataType::*;
+    match dtype.to_logical_type() {
+        BinaryView | Utf8View => 1,
+        List(field) | LargeList(field) | FixedSizeList(field, _) | Map(field, _) => {
+            count_view_arrays(&field.dtype)
+        },
+        Struct(fields) => fields
+            .iter()
+            .map(|field| count_view_arrays(&field.dtype))
+            .sum(),
+        Union(union) => union
+            .fields
+            .iter()
+            .map(|field| count_view_arrays(&field.dtype))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// Returns the index, the stored size and the decoded size in bytes of every column of `batch`
+/// selected by `projection`, without decoding them.
+///
+/// The sizes are the sums of the declared lengths of the buffers of a column. For compressed
+/// batches, the decoded length is the uncompressed length that prefixes every buffer, which is
+/// read from `reader`.
+fn column_sizes<R: Read + Seek>(
+    batch: arrow_format::ipc::RecordBatchRef,
+    fields: &ArrowSchema,
+    projection: Option<&[usize]>,
+    version: arrow_format::ipc::MetadataVersion,
+    reader: &mut R,
+    block_offset: u64,
+) -> PolarsResult<Vec<(usize, usize, usize)>> {
+    let projection = projection.map(OrderedProjection::new);
+    let mut buffers: VecDeque<arrow_format::ipc::BufferRef> = batch
+        .buffers()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferBuffers(err)))?
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageBuffers))?
+        .iter()
+        .collect();
+    let mut variadic_buffer_counts = read_variadic_buffer_counts(batch, buffers.len(), None)?;
+    let mut field_nodes = batch
+        .nodes()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferNodes(err)))?
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageNodes))?
+        .iter()
+        .collect::<VecDeque<_>>();
+    let is_compressed = batch
+        .compression()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferCompression(err)))?
+        .is_some();
+
+    let mut sizes = vec![];
+    for (i, field) in fields.iter_values().enumerate() {
+        let remaining = buffers.clone();
+        skip(
+            &mut field_nodes,
+            &field.dtype,
+            &mut buffers,
+            &mut variadic_buffer_counts,
+            version,
+        )?;
+        if projection
+            .as_ref()
+            .is_some_and(|p| p.read_indices().binary_search(&i).is_err())
+        {
+            continue;
+        }
+        let (mut stored, mut decoded) = (0, 0);
+        for buffer in remaining.iter().take(remaining.len() - buffers.len()) {
+            let length: usize = buffer
+                .length()
+                .try_into()
+                .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+            stored += length;
+            decoded += if is_compressed && length > 0 {
+                let offset: u64 = buffer
+                    .offset()
+                    .try_into()
+                    .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+                reader.seek(SeekFrom::Start(block_offset + offset))?;
+                let mut prefix = [0; 8];
+                reader.read_exact(&mut prefix)?;
+                match i64::from_le_bytes(prefix) {
+                    // the buffer is stored uncompressed
+                    -1 => length.checked_sub(prefix.len()).ok_or_else(|| {
+                        polars_err!(ComputeError:
+                            "IPC: a compressed buffer of {length} bytes is missing its \
+                            uncompressed length"
+                        )
+                    })?,
+                    prefix => usize::try_from(prefix)
+                        .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?,
+                }
+            } else {
+                length
+            };
+        }
+        sizes.push((i, stored, decoded));
+    }
+    Ok(sizes)
+}
+
+/// Returns an estimate of the size in bytes of the columns of `batch` selected by
+/// `projection`, without decoding them.
+///
+/// The estimate is the sum of the declared lengths of the buffers of the selected columns. For
+/// compressed batches, the uncompressed length that prefixes every buffer is read from `reader`
+/// instead.
+pub fn estimate_batch_size<R: Read + Seek>(
+    batch: arrow_format::ipc::RecordBatchRef,
+    fields: &ArrowSchema,
+    projection: Option<&[usize]>,
+    version: arrow_format::ipc::MetadataVersion,
+    reader: &mut R,
+    block_offset: u64,
+) -> PolarsResult<usize> {
+    let sizes = column_sizes(batch, fields, projection, version, reader, block_offset)?;
+    Ok(sizes.into_iter().map(|(_, _, decoded)| decoded).sum())
+}
+
+/// Returns the compression ratio of every column of `batch` selected by `projection`, by name:
+/// the decoded size of its buffers divided by their stored size, without decoding them.
+///
+/// The stored size of a buffer of a compressed batch includes the 8 bytes that prefix it with
+/// its uncompressed length, such that a column whose buffers are stored uncompressed, e.g.
+/// because they are incompressible, has a ratio slightly below 1. The columns of uncompressed
+/// batches and the columns without data have a ratio of 1.
+pub fn compression_ratios<R: Read + Seek>(
+    batch: arrow_format::ipc::RecordBatchRef,
+    fields: &ArrowSchema,
+    projection: Option<&[usize]>,
+    version: arrow_format::ipc::MetadataVersion,
+    reader: &mut R,
+    block_offset: u64,
+) -> PolarsResult<Vec<(PlSmallStr, f64)>> {
+    let sizes = column_sizes(batch, fields, projection, version, reader, block_offset)?;
+    Ok(sizes
+        .into_iter()
+        .map(|(i, stored, decoded)| {
+            let ratio = if stored == 0 {
+                1.0
+            } else {
+                decoded as f64 / stored as f64
+            };
+            (fields.get_at_index(i).unwrap().0.clone(), ratio)
+        })
+        .collect())
 }
 
 fn find_first_dict_field_d<'a>(
@@ -265,30 +1224,56 @@ pub(crate) fn first_dict_field<'a>(
     ))
 }
 
+/// Returns the first field that refers to the dictionary `id`, or `None` if no field does and
+/// orphan dictionaries are skipped, reporting `id` in `diagnostics`. See
+/// [`ReadOptions::skip_orphan_dictionaries`].
+pub(crate) fn dictionary_field<'a>(
+    id: i64,
+    fields: &'a ArrowSchema,
+    ipc_fields: &'a [IpcField],
+    options: &ReadOptions,
+    diagnostics: &mut ReadDiagnostics,
+) -> PolarsResult<Option<(&'a Field, &'a IpcField)>> {
+    match first_dict_field(id, fields, ipc_fields) {
+        Err(_) if options.skip_orphan_dictionaries => {
+            diagnostics.orphan_dictionaries.push(id);
+            Ok(None)
+        },
+        result => result.map(Some),
+    }
+}
+
 /// Reads a dictionary from the reader,
-/// updating `dictionaries` with the resulting dictionary
+/// updating `dictionaries` with the resulting dictionary.
+/// `version` is the metadata version of the dictionary batch's message, which determines the
+/// layout of its buffers, e.g. the validity buffer of unions before V5. The ids of skipped
+/// orphan dictionaries are appended to `diagnostics`.
 #[allow(clippy::too_many_arguments)]
 pub fn read_dictionary<R: Read + Seek>(
     batch: arrow_format::ipc::DictionaryBatchRef,
     fields: &ArrowSchema,
     ipc_schema: &IpcSchema,
     dictionaries: &mut Dictionaries,
+    version: arrow_format::ipc::MetadataVersion,
     reader: &mut R,
     block_offset: u64,
     file_size: u64,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    diagnostics: &mut ReadDiagnostics,
 ) -> PolarsResult<()> {
-    if batch
+    let is_delta = batch
         .is_delta()
-        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferIsDelta(err)))?
-    {
-        polars_bail!(ComputeError: "delta dictionary batches not supported")
-    }
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferIsDelta(err)))?;
 
     let id = batch
         .id()
         .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferId(err)))?;
-    let (first_field, first_ipc_field) = first_dict_field(id, fields, &ipc_schema.fields)?;
+    let Some((first_field, first_ipc_field)) =
+        dictionary_field(id, fields, &ipc_schema.fields, options, diagnostics)?
+    else {
+        return Ok(());
+    };
 
     let batch = batch
         .data()
@@ -319,26 +1304,127 @@ pub fn read_dictionary<R: Read + Seek>(
         None,
         None, // we must read the whole dictionary
         dictionaries,
-        arrow_format::ipc::MetadataVersion::V5,
+        version,
         reader,
         block_offset,
         file_size,
         scratch,
+        &dictionary_options(options),
     )?;
 
-    dictionaries.insert(id, chunk.into_arrays().pop().unwrap());
+    let mut values = chunk.into_arrays().pop().unwrap();
+    if is_delta {
+        values = append_delta_dictionary(id, dictionaries, values.as_ref())?;
+    }
+    dictionaries.insert(id, values);
+
+    Ok(())
+}
+
+/// The options by which the values of a dictionary batch are decoded: a dictionary is shared by
+/// the columns that refer to it, so it fails as a whole instead of being replaced by nulls, and
+/// is not bounded by [`ReadOptions::column_budget`], which bounds the columns of record batches.
+fn dictionary_options(options: &ReadOptions) -> Cow<'_, ReadOptions> {
+    if options.column_error_policy == ColumnErrorPolicy::Fail
+        && options.column_budget == ColumnBudget::default()
+    {
+        Cow::Borrowed(options)
+    } else {
+        Cow::Owned(ReadOptions {
+            column_error_policy: ColumnErrorPolicy::Fail,
+            column_budget: ColumnBudget::default(),
+            ..options.clone()
+        })
+    }
+}
+
+/// Appends the values of a delta dictionary batch to the dictionary with the same `id`.
+///
+/// Dictionaries are keyed by id, so this also covers dictionaries that are nested in other
+/// types (e.g. `List<Dictionary<..>>`): the delta always extends that dictionary's values.
+pub(crate) fn append_delta_dictionary(
+    id: i64,
+    dictionaries: &Dictionaries,
+    delta: &dyn Array,
+) -> PolarsResult<Box<dyn Array>> {
+    let values = dictionaries.get(&id).ok_or_else(|| {
+        polars_err!(ComputeError:
+            "IPC: delta dictionary batch for id {id} without a preceding dictionary batch"
+        )
+    })?;
+    concatenate(&[values.as_ref(), delta])
+}
+
+/// How a dictionary batch changed the dictionary of its id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DictionaryEventKind {
+    /// The batch replaced the dictionary, or was the first dictionary of its id.
+    Replacement,
+    /// The batch appended its values to the dictionary.
+    Delta,
+}
+
+/// A dictionary batch that was read for a dictionary id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DictionaryEvent {
+    /// The position of the dictionary batch among the dictionary batches of the file or stream.
+    pub block_index: usize,
+    pub kind: DictionaryEventKind,
+    /// The number of values of the dictionary batch.
+    pub length: usize,
+}
 
+/// The [`DictionaryEvent`]s of every dictionary id, in the order that they were read.
+pub type DictionaryHistory = PlHashMap<i64, Vec<DictionaryEvent>>;
+
+/// Appends the [`DictionaryEvent`] of the dictionary batch at `block_index` to `history`.
+pub(super) fn record_dictionary_event(
+    history: &mut DictionaryHistory,
+    batch: arrow_format::ipc::DictionaryBatchRef,
+    block_index: usize,
+) -> PolarsResult<()> {
+    let id = batch
+        .id()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferId(err)))?;
+    let is_delta = batch
+        .is_delta()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferIsDelta(err)))?;
+    let length = batch
+        .data()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferData(err)))?
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingData))?
+        .length()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferRecordBatches(err)))?
+        .try_into()
+        .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+    let kind = if is_delta {
+        DictionaryEventKind::Delta
+    } else {
+        DictionaryEventKind::Replacement
+    };
+    history.entry(id).or_default().push(DictionaryEvent {
+        block_index,
+        kind,
+        length,
+    });
     Ok(())
 }
 
 #[derive(Clone)]
 pub struct ProjectionInfo {
+    /// The indices of the projected columns, in output order. The columns are decoded in this
+    /// order, see [`read_record_batch`].
     pub columns: Vec<usize>,
+    /// Maps the position of every column of a record batch to its position in the output,
+    /// which are the same: the columns are decoded in output order.
+    #[deprecated(
+        note = "the columns are decoded in the order of `columns`; this map is the identity"
+    )]
     pub map: PlHashMap<usize, usize>,
     pub schema: ArrowSchema,
 }
 
-pub fn prepare_projection(schema: &ArrowSchema, mut projection: Vec<usize>) -> ProjectionInfo {
+pub fn prepare_projection(schema: &ArrowSchema, projection: Vec<usize>) -> ProjectionInfo {
     let schema = projection
         .iter()
         .map(|x| {
@@ -347,38 +1433,534 @@ pub fn prepare_projection(schema: &ArrowSchema, mut projection: Vec<usize>) -> P
         })
         .collect();
 
-    // todo: find way to do this more efficiently
-    let mut indices = (0..projection.len()).collect::<Vec<_>>();
-    indices.sort_unstable_by_key(|&i| &projection[i]);
-    let map = indices.iter().copied().enumerate().fold(
-        PlHashMap::default(),
-        |mut acc, (index, new_index)| {
-            acc.insert(index, new_index);
-            acc
-        },
+    assert!(
+        !OrderedProjection::new(&projection).has_duplicates(),
+        "The projection on IPC must not contain duplicates"
     );
-    projection.sort_unstable();
 
-    // check unique
-    if !projection.is_empty() {
-        let mut previous = projection[0];
+    #[allow(deprecated)]
+    ProjectionInfo {
+        map: (0..projection.len()).map(|index| (index, index)).collect(),
+        columns: projection,
+        schema,
+    }
+}
 
-        for &i in &projection[1..] {
-            assert!(
-                previous < i,
-                "The projection on IPC must not contain duplicates"
+/// Prepares the projection `projection` of the columns of `schema` like [`prepare_projection`],
+/// but errors instead of panicking if it selects a column more than once or a column that is
+/// not in `schema`.
+pub(crate) fn try_prepare_projection(
+    schema: &ArrowSchema,
+    projection: Vec<usize>,
+) -> PolarsResult<ProjectionInfo> {
+    if let Some(&index) = projection.iter().find(|&&index| index >= schema.len()) {
+        polars_bail!(
+            OutOfBounds: "IPC: the projected field index {index} is out of bounds for a schema of {} fields",
+            schema.len()
+        );
+    }
+    polars_ensure!(
+        !OrderedProjection::new(&projection).has_duplicates(),
+        Duplicate: "IPC: the projection selects a field more than once"
+    );
+    Ok(prepare_projection(schema, projection))
+}
+
+/// Prepares the projection of the columns of `schema` whose entry in `mask` is `true`, in
+/// schema order. See [`prepare_projection`].
+pub fn prepare_projection_mask(
+    schema: &ArrowSchema,
+    mask: &[bool],
+) -> PolarsResult<ProjectionInfo> {
+    polars_ensure!(
+        mask.len() == schema.len(),
+        InvalidOperation: "projection mask of length {} does not match a schema of {} fields",
+        mask.len(),
+        schema.len()
+    );
+    let projection = mask
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &selected)| selected.then_some(index))
+        .collect();
+    Ok(prepare_projection(schema, projection))
+}
+
+/// Prepares the projection of the columns of `schema` whose data type satisfies `predicate`, in
+/// schema order. See [`prepare_projection`].
+pub fn prepare_projection_by_type(
+    schema: &ArrowSchema,
+    predicate: impl Fn(&ArrowDataType) -> bool,
+) -> ProjectionInfo {
+    let projection = schema
+        .iter_values()
+        .enumerate()
+        .filter_map(|(index, field)| predicate(&field.dtype).then_some(index))
+        .collect();
+    prepare_projection(schema, projection)
+}
+
+/// The keys of the field metadata that [`prepare_projection_by_field_id`] reads the field id of
+/// a field from, by precedence.
+pub const FIELD_ID_KEYS: [&str; 2] = ["PARQUET:field_id", "polars:field_id"];
+
+/// Returns the field id of `field`, i.e. the value of the first of [`FIELD_ID_KEYS`] in its
+/// metadata.
+fn field_id(field: &Field) -> PolarsResult<Option<i64>> {
+    let Some(metadata) = &field.metadata else {
+        return Ok(None);
+    };
+    let Some(value) = FIELD_ID_KEYS.iter().find_map(|key| metadata.get(*key)) else {
+        return Ok(None);
+    };
+    value.parse().map(Some).map_err(|_| {
+        polars_err!(ComputeError: "IPC: the field id \"{value}\" of field \"{}\" is not an integer", field.name)
+    })
+}
+
+/// Prepares the projection of the columns of `schema` whose field ids are `ids`, in the order of
+/// `ids`, such that a projection survives the reordering and renaming of the columns of a
+/// dataset. The field id of a field is read from its metadata; see [`FIELD_ID_KEYS`].
+///
+/// An id that no field has, or that many fields have, is an error, as is an id requested twice.
+/// See [`prepare_projection`].
+pub fn prepare_projection_by_field_id(
+    schema: &ArrowSchema,
+    ids: &[i64],
+) -> PolarsResult<ProjectionInfo> {
+    let mut indices = PlHashMap::default();
+    for (index, field) in schema.iter_values().enumerate() {
+        if let Some(id) = field_id(field)? {
+            polars_ensure!(
+                indices.insert(id, index).is_none(),
+                Duplicate: "IPC: the field id {id} is not unique in the schema"
             );
-            previous = i;
         }
     }
+    let projection = ids
+        .iter()
+        .map(|id| {
+            indices.remove(id).ok_or_else(|| {
+                match ids.iter().filter(|other| *other == id).count() {
+                    1 => polars_err!(ColumnNotFound: "IPC: no field has the field id {id}"),
+                    _ => {
+                        polars_err!(Duplicate: "IPC: the field id {id} is projected more than once")
+                    },
+                }
+            })
+        })
+        .collect::<PolarsResult<_>>()?;
+    Ok(prepare_projection(schema, projection))
+}
 
-    ProjectionInfo {
-        columns: projection,
-        map,
-        schema,
+/// A projection in output order, split into the strictly increasing indices in which the
+/// columns are read and the permutation that restores the output order.
+///
+/// The output order is arbitrary and may contain duplicates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OrderedProjection {
+    read_indices: Vec<usize>,
+    /// The position in `read_indices` of every output column.
+    permutation: Vec<usize>,
+}
+
+impl OrderedProjection {
+    pub fn new(projection: &[usize]) -> Self {
+        let mut order = (0..projection.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| projection[i]);
+
+        let mut read_indices = Vec::with_capacity(projection.len());
+        let mut permutation = vec![0; projection.len()];
+        for i in order {
+            if read_indices.last() != Some(&projection[i]) {
+                read_indices.push(projection[i]);
+            }
+            permutation[i] = read_indices.len() - 1;
+        }
+        Self {
+            read_indices,
+            permutation,
+        }
+    }
+
+    /// The indices of the columns to read, strictly increasing.
+    pub fn read_indices(&self) -> &[usize] {
+        &self.read_indices
+    }
+
+    /// The indices of the columns in output order, i.e. the projection this was created from.
+    pub fn output_indices(&self) -> impl ExactSizeIterator<Item = usize> + '_ {
+        self.permutation.iter().map(|&i| self.read_indices[i])
+    }
+
+    /// Whether a column is output more than once.
+    pub fn has_duplicates(&self) -> bool {
+        self.read_indices.len() != self.permutation.len()
+    }
+
+    /// Reorders `columns`, read in the order of [`OrderedProjection::read_indices`], to the
+    /// output order. Duplicated columns are cloned.
+    /// # Panics
+    /// Panics iff `columns` does not have one column per read index.
+    pub fn reorder<T: Clone>(&self, columns: Vec<T>) -> Vec<T> {
+        assert_eq!(columns.len(), self.read_indices.len());
+        let mut last_use = vec![0; columns.len()];
+        for (position, &i) in self.permutation.iter().enumerate() {
+            last_use[i] = position;
+        }
+
+        let mut columns = columns.into_iter().map(Some).collect::<Vec<_>>();
+        self.permutation
+            .iter()
+            .enumerate()
+            .map(|(position, &i)| {
+                if last_use[i] == position {
+                    columns[i].take().unwrap()
+                } else {
+                    columns[i].clone().unwrap()
+                }
+            })
+            .collect()
     }
 }
 
+/// Returns the projection of a reader of `schema` that is reading with `options`.
+pub(crate) fn options_projection(
+    schema: &ArrowSchema,
+    mut projection: Option<ProjectionInfo>,
+    options: &ReadOptions,
+) -> Option<ProjectionInfo> {
+    if let Some(max_columns) = options.max_columns {
+        projection = Some(limit_projection(schema, projection, max_columns));
+    }
+    if let Some(tz) = &options.timestamp_tz {
+        let mut info =
+            projection.unwrap_or_else(|| prepare_projection(schema, (0..schema.len()).collect()));
+        relabel_schema(&mut info.schema, tz);
+        projection = Some(info);
+    }
+    if options.string_cache.is_some() {
+        let mut info =
+            projection.unwrap_or_else(|| prepare_projection(schema, (0..schema.len()).collect()));
+        relabel_interned_schema(&mut info.schema);
+        projection = Some(info);
+    }
+    if options.reads_dictionary_keys() {
+        let mut info =
+            projection.unwrap_or_else(|| prepare_projection(schema, (0..schema.len()).collect()));
+        for field in info.schema.iter_values_mut() {
+            *field = decoded_field(field, options).into_owned();
+        }
+        projection = Some(info);
+    }
+    if let Some((name, _)) = &options.row_index {
+        let mut info =
+            projection.unwrap_or_else(|| prepare_projection(schema, (0..schema.len()).collect()));
+        info.schema = std::iter::once(row_index_field(name.clone()))
+            .chain(info.schema.iter_values().cloned())
+            .collect();
+        projection = Some(info);
+    }
+    projection
+}
+
+fn row_index_field(name: PlSmallStr) -> Field {
+    Field::new(name, ArrowDataType::IDX_DTYPE, false)
+}
+
+/// Prepends the row index column of [`ReadOptions::row_index`] to `chunk`, whose first row is
+/// the row at position `row` of the file or stream.
+pub(crate) fn prepend_row_index(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    row: usize,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let Some((name, offset)) = &options.row_index else {
+        return Ok(chunk);
+    };
+    let length = chunk.len();
+    let start = (*offset as usize).checked_add(row);
+    let indices = start
+        .and_then(|start| {
+            let end = start.checked_add(length)?;
+            // the index of the last row must fit
+            IdxSize::try_from(end.saturating_sub(1)).ok()?;
+            Some((start..end).map(|index| index as IdxSize).collect::<Vec<_>>())
+        })
+        .ok_or_else(|| {
+            polars_err!(ComputeError: "IPC: the row index \"{name}\" overflows its type {:?}", ArrowDataType::IDX_DTYPE)
+        })?;
+
+    let (schema, arrays) = chunk.into_schema_and_arrays();
+    let schema = std::iter::once(row_index_field(name.clone()))
+        .chain(schema.iter_values().cloned())
+        .collect::<ArrowSchema>();
+    let arrays = std::iter::once(PrimitiveArray::from_vec(indices).boxed())
+        .chain(arrays)
+        .collect();
+    RecordBatchT::try_new(length, Arc::new(schema), arrays)
+}
+
+/// Applies the [`ReadOptions`] that transform a batch after it is read.
+pub(crate) fn apply_options(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let chunk = match &options.timestamp_tz {
+        Some(tz) => coerce_timestamp_tz(chunk, tz, options.shift_timestamps)?,
+        None => chunk,
+    };
+    let chunk = match &options.string_cache {
+        Some(cache) => intern_strings(chunk, cache),
+        None => chunk,
+    };
+    let chunk = share_dictionaries(chunk, &options.shared_dictionary_columns)?;
+    match &options.reader_schema {
+        Some(schema) => conform_to_schema(chunk, schema, &options.defaults),
+        None => Ok(chunk),
+    }
+}
+
+/// Splits `chunk` into slices of at most `max_rows` rows. See [`slice_arrays`].
+pub(crate) fn split_batch(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    max_rows: usize,
+    contiguous: bool,
+) -> VecDeque<RecordBatchT<Box<dyn Array>>> {
+    let max_rows = max_rows.max(1);
+    if chunk.len() <= max_rows {
+        return VecDeque::from([chunk]);
+    }
+    let length = chunk.len();
+    let (schema, arrays) = chunk.into_schema_and_arrays();
+    (0..length)
+        .step_by(max_rows)
+        .map(|offset| {
+            let slice_length = max_rows.min(length - offset);
+            let arrays = slice_arrays(&arrays, offset, slice_length, contiguous);
+            RecordBatchT::new(slice_length, schema.clone(), arrays)
+        })
+        .collect()
+}
+
+/// Returns the `length` rows of `arrays` from `offset`. The slices are zero-copy, i.e. they
+/// share the buffers of `arrays` at a logical offset, unless `contiguous` is set. See
+/// [`ReadOptions::contiguous`].
+pub(crate) fn slice_arrays(
+    arrays: &[Box<dyn Array>],
+    offset: usize,
+    length: usize,
+    contiguous: bool,
+) -> Vec<Box<dyn Array>> {
+    arrays
+        .iter()
+        .map(|array| {
+            let slice = array.sliced(offset, length);
+            if contiguous { compact(slice) } else { slice }
+        })
+        .collect()
+}
+
+/// Whether arrays of `dtype` can be concatenated, i.e. contain no union, map nor dictionary
+/// arrays.
+fn is_concatenable(dtype: &ArrowDataType) -> bool {
+    use ArrowDataType::*;
+    match dtype.to_logical_type() {
+        Union(_) | Map(_, _) | Dictionary(_, _, _) => false,
+        List(field) | LargeList(field) | FixedSizeList(field, _) => is_concatenable(&field.dtype),
+        Struct(fields) => fields.iter().all(|field| is_concatenable(&field.dtype)),
+        _ => true,
+    }
+}
+
+/// Concatenates `arrays` of the same type of the column `name`, e.g. the columns of many record
+/// batches of a file. The dictionaries of top-level dictionary arrays are unified first, such
+/// that only their keys are concatenated; dictionaries whose values are not compared, see
+/// [`concatenate_batches`], are expected to be the dictionary of the first array.
+pub(crate) fn concatenate_columns(
+    arrays: &[Box<dyn Array>],
+    name: &str,
+) -> PolarsResult<Box<dyn Array>> {
+    let dtype = arrays[0].dtype();
+    if let ArrowDataType::Dictionary(key_type, _, _) = dtype.to_logical_type() {
+        let unified = unify_dictionaries(arrays, name)?;
+        let arrays = unified.as_deref().unwrap_or(arrays);
+        return match_integer_type!(key_type, |$T| {
+            let dictionary = |array: &Box<dyn Array>| {
+                array
+                    .as_any()
+                    .downcast_ref::<DictionaryArray<$T>>()
+                    .unwrap()
+                    .clone()
+            };
+            let keys = arrays
+                .iter()
+                .map(|array| dictionary(array).keys().clone().boxed())
+                .collect::<Vec<_>>();
+            let keys = concatenate_columns(&keys, name)?;
+            let keys = keys.as_any().downcast_ref::<PrimitiveArray<$T>>().unwrap();
+            let values = dictionary(&arrays[0]).values().clone();
+            Ok(DictionaryArray::<$T>::try_new(dtype.clone(), keys.clone(), values)?.boxed())
+        });
+    }
+    polars_ensure!(
+        is_concatenable(dtype),
+        nyi = "IPC: concatenating arrays of type {dtype:?}"
+    );
+    concatenate(
+        &arrays
+            .iter()
+            .map(|array| array.as_ref())
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Concatenates the record batches `batches` of the same schema into a single record batch, e.g.
+/// to read a whole stream at once.
+///
+/// The record batches of a stream may have different dictionaries for the same column, as a
+/// dictionary batch may replace it or append to it in between. The dictionaries of every
+/// top-level dictionary-encoded column are unified into the dictionary of their distinct values,
+/// to which the keys of every record batch are remapped, such that the column has a single
+/// dictionary. This requires the values of the dictionaries to be of a primitive, string or
+/// binary type; other dictionaries are expected to be the same for all record batches.
+pub fn concatenate_batches(
+    batches: &[RecordBatchT<Box<dyn Array>>],
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let Some(first) = batches.first() else {
+        polars_bail!(InvalidOperation: "IPC: concatenating no record batches")
+    };
+    let schema = first.schema().clone();
+    let length = batches.iter().map(|batch| batch.len()).sum();
+    let arrays = schema
+        .iter_names()
+        .enumerate()
+        .map(|(column, name)| {
+            let columns = batches
+                .iter()
+                .map(|batch| batch.arrays()[column].clone())
+                .collect::<Vec<_>>();
+            concatenate_columns(&columns, name)
+        })
+        .collect::<PolarsResult<_>>()?;
+    RecordBatchT::try_new(length, Arc::new(schema), arrays)
+}
+
+/// Reverses the rows of `chunk` and keeps the first `length` of them, i.e. its last `length`
+/// rows in reverse order. See [`ReadOptions::reverse_rows`].
+pub(crate) fn reverse_rows(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    length: usize,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let offset = chunk.len() - length;
+    let (schema, arrays) = chunk.into_schema_and_arrays();
+    let arrays = slice_arrays(&arrays, offset, length, false)
+        .into_iter()
+        .map(reverse_array)
+        .collect::<PolarsResult<_>>()?;
+    RecordBatchT::try_new(length, schema, arrays)
+}
+
+/// Gathers the rows of `array` in reverse order.
+fn reverse_array(array: Box<dyn Array>) -> PolarsResult<Box<dyn Array>> {
+    fn reverse_primitive<T: NativeType>(array: &PrimitiveArray<T>) -> PrimitiveArray<T> {
+        let values = array.values().iter().rev().copied().collect::<Vec<_>>();
+        let validity = array
+            .validity()
+            .map(|validity| validity.iter().rev().collect::<Bitmap>());
+        PrimitiveArray::new(array.dtype().clone(), values.into(), validity)
+    }
+
+    match array.dtype().to_physical_type() {
+        PhysicalType::Primitive(primitive) => {
+            return Ok(with_match_primitive_type_full!(primitive, |$T| {
+                reverse_primitive(array.as_any().downcast_ref::<PrimitiveArray<$T>>().unwrap())
+                    .boxed()
+            }));
+        },
+        PhysicalType::Dictionary(key_type) => {
+            return Ok(match_integer_type!(key_type, |$T| {
+                let array = array
+                    .as_any()
+                    .downcast_ref::<DictionaryArray<$T>>()
+                    .unwrap();
+                // SAFETY: the keys are the same keys, which are in bounds of the same values
+                unsafe {
+                    DictionaryArray::<$T>::try_new_unchecked(
+                        array.dtype().clone(),
+                        reverse_primitive(array.keys()),
+                        array.values().clone(),
+                    )
+                }
+                .unwrap()
+                .boxed()
+            }));
+        },
+        _ => {},
+    }
+    polars_ensure!(
+        is_concatenable(array.dtype()),
+        nyi = "IPC: reversing the rows of an array of type {:?}",
+        array.dtype()
+    );
+    if array.len() <= 1 {
+        return Ok(array);
+    }
+    let rows = (0..array.len())
+        .rev()
+        .map(|index| array.sliced(index, 1))
+        .collect::<Vec<_>>();
+    concatenate(&rows.iter().map(|row| row.as_ref()).collect::<Vec<_>>())
+}
+
+/// Copies `array` into an array whose buffers start at offset zero.
+fn compact(array: Box<dyn Array>) -> Box<dyn Array> {
+    if let ArrowDataType::Dictionary(key_type, _, _) = array.dtype().to_logical_type() {
+        return match_integer_type!(key_type, |$T| {
+            let array = array
+                .as_any()
+                .downcast_ref::<DictionaryArray<$T>>()
+                .unwrap();
+            let keys = compact(array.keys().clone().boxed());
+            let keys = keys.as_any().downcast_ref::<PrimitiveArray<$T>>().unwrap();
+            // SAFETY: the keys are the same keys, which are in bounds of the same values
+            unsafe {
+                DictionaryArray::<$T>::try_new_unchecked(
+                    array.dtype().clone(),
+                    keys.clone(),
+                    array.values().clone(),
+                )
+            }
+            .unwrap()
+            .boxed()
+        });
+    }
+    if !is_concatenable(array.dtype()) {
+        return array;
+    }
+    // concatenating with an empty array always copies, unlike concatenating a single array
+    let empty = new_empty_array(array.dtype().clone());
+    concatenate(&[array.as_ref(), empty.as_ref()]).unwrap()
+}
+
+/// Limits the read to the first `max_columns` columns of `projection`, or of `schema` when
+/// there is no projection.
+pub(crate) fn limit_projection(
+    schema: &ArrowSchema,
+    projection: Option<ProjectionInfo>,
+    max_columns: usize,
+) -> ProjectionInfo {
+    let mut columns = match projection {
+        Some(ProjectionInfo { columns, .. }) => columns,
+        None => (0..schema.len()).collect(),
+    };
+    columns.truncate(max_columns);
+    prepare_projection(schema, columns)
+}
+
+#[deprecated(
+    note = "the readers return the columns in the order of the projection; there is nothing to reorder"
+)]
 pub fn apply_projection(
     chunk: RecordBatchT<Box<dyn Array>>,
     map: &PlHashMap<usize, usize>,
@@ -424,4 +2006,42 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn ordered_projection_round_trip() {
+        for projection in [
+            vec![],
+            vec![0, 1, 2],
+            vec![2, 0, 1],
+            vec![4, 3, 2, 1, 0],
+            vec![3, 1, 3, 0, 1],
+            vec![2, 2, 2],
+        ] {
+            let ordered = OrderedProjection::new(&projection);
+            assert!(ordered.read_indices().is_sorted_by(|a, b| a < b));
+            assert_eq!(ordered.output_indices().collect::<Vec<_>>(), projection);
+
+            // the columns read are their own indices
+            let columns = ordered.read_indices().to_vec();
+            assert_eq!(ordered.reorder(columns), projection);
+        }
+        assert!(OrderedProjection::new(&[1, 0, 1]).has_duplicates());
+        assert!(!OrderedProjection::new(&[1, 0]).has_duplicates());
+    }
+
+    #[test]
+    fn check_mismatched_projection() {
+        let schema = ArrowSchema::from_iter([
+            Field::new("a".into(), ArrowDataType::Int32, true),
+            Field::new("b".into(), ArrowDataType::Utf8, true),
+        ]);
+        let a = PrimitiveArray::<i32>::from_vec(vec![1]).boxed();
+        let b = Utf8Array::<i32>::from_slice(["b"]).boxed();
+
+        assert!(check_projected_columns(&schema, &[a.clone(), b.clone()]).is_ok());
+        // columns read in another order than the schema was projected in
+        let err = check_projected_columns(&schema, &[b.clone(), a.clone()]).unwrap_err();
+        assert!(err.to_string().contains("\"a\" at index 0"));
+        assert!(check_projected_columns(&schema, &[a]).is_err());
+    }
 }
@@ -2,10 +2,10 @@ use std::collections::VecDeque;
 use std::io::{Read, Seek};
 
 use arrow_format::ipc::{BodyCompressionRef, MetadataVersion};
-use polars_error::PolarsResult;
+use polars_error::{PolarsResult, polars_bail, polars_ensure};
 
 use super::array::*;
-use super::{Dictionaries, IpcBuffer, Node};
+use super::{Dictionaries, IpcBuffer, Node, OutOfSpecKind, ReadOptions};
 use crate::array::*;
 use crate::datatypes::{ArrowDataType, Field, PhysicalType};
 use crate::io::ipc::IpcField;
@@ -26,6 +26,7 @@ pub fn read<R: Read + Seek>(
     limit: Option<usize>,
     version: MetadataVersion,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<Box<dyn Array>> {
     use PhysicalType::*;
     let dtype = field.dtype.clone();
@@ -42,6 +43,7 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         Primitive(primitive) => with_match_primitive_type_full!(primitive, |$T| {
@@ -55,12 +57,14 @@ pub fn read<R: Read + Seek>(
                 compression,
                 limit,
                 scratch,
+                options,
             )
             .map(|x| x.boxed())
         }),
         Binary => read_binary::<i32, _>(
             field_nodes,
             dtype,
+            &field.name,
             buffers,
             reader,
             block_offset,
@@ -68,11 +72,13 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         LargeBinary => read_binary::<i64, _>(
             field_nodes,
             dtype,
+            &field.name,
             buffers,
             reader,
             block_offset,
@@ -80,6 +86,7 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         FixedSizeBinary => read_fixed_size_binary(
@@ -92,11 +99,13 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         Utf8 => read_utf8::<i32, _>(
             field_nodes,
             dtype,
+            &field.name,
             buffers,
             reader,
             block_offset,
@@ -104,11 +113,13 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         LargeUtf8 => read_utf8::<i64, _>(
             field_nodes,
             dtype,
+            &field.name,
             buffers,
             reader,
             block_offset,
@@ -116,12 +127,14 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         List => read_list::<i32, _>(
             field_nodes,
             variadic_buffer_counts,
             dtype,
+            &field.name,
             ipc_field,
             buffers,
             reader,
@@ -132,12 +145,14 @@ pub fn read<R: Read + Seek>(
             limit,
             version,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         LargeList => read_list::<i64, _>(
             field_nodes,
             variadic_buffer_counts,
             dtype,
+            &field.name,
             ipc_field,
             buffers,
             reader,
@@ -148,6 +163,7 @@ pub fn read<R: Read + Seek>(
             limit,
             version,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         FixedSizeList => read_fixed_size_list(
@@ -164,6 +180,7 @@ pub fn read<R: Read + Seek>(
             limit,
             version,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         Struct => read_struct(
@@ -180,6 +197,7 @@ pub fn read<R: Read + Seek>(
             limit,
             version,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         Dictionary(key_type) => {
@@ -196,6 +214,7 @@ pub fn read<R: Read + Seek>(
                     limit,
                     is_little_endian,
                     scratch,
+                    options,
                 )
                 .map(|x| x.boxed())
             })
@@ -214,12 +233,14 @@ pub fn read<R: Read + Seek>(
             limit,
             version,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         Map => read_map(
             field_nodes,
             variadic_buffer_counts,
             dtype,
+            &field.name,
             ipc_field,
             buffers,
             reader,
@@ -230,6 +251,7 @@ pub fn read<R: Read + Seek>(
             limit,
             version,
             scratch,
+            options,
         )
         .map(|x| x.boxed()),
         Utf8View => read_binview::<str, _>(
@@ -243,6 +265,7 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         ),
         BinaryView => read_binview::<[u8], _>(
             field_nodes,
@@ -255,6 +278,7 @@ pub fn read<R: Read + Seek>(
             compression,
             limit,
             scratch,
+            options,
         ),
     }
 }
@@ -264,7 +288,26 @@ pub fn skip(
     dtype: &ArrowDataType,
     buffers: &mut VecDeque<IpcBuffer>,
     variadic_buffer_counts: &mut VecDeque<usize>,
+    version: MetadataVersion,
 ) -> PolarsResult<()> {
+    // Fast path: if the layout of the column is fully determined by its type we can advance
+    // the queues in bulk instead of walking the (possibly nested) type node by node.
+    if let Some(n_buffers) = count_buffers(dtype) {
+        let n_nodes = count_nodes(dtype);
+        if field_nodes.len() < n_nodes {
+            polars_bail!(ComputeError:
+                "IPC: unable to fetch the field for {:?}\n\nThe file or stream is corrupted.", dtype
+            )
+        }
+        polars_ensure!(
+            buffers.len() >= n_buffers,
+            oos = OutOfSpecKind::ExpectedBuffer
+        );
+        field_nodes.drain(..n_nodes);
+        buffers.drain(..n_buffers);
+        return Ok(());
+    }
+
     use PhysicalType::*;
     match dtype.to_physical_type() {
         Null => skip_null(field_nodes),
@@ -273,13 +316,66 @@ pub fn skip(
         LargeBinary | Binary => skip_binary(field_nodes, buffers),
         LargeUtf8 | Utf8 => skip_utf8(field_nodes, buffers),
         FixedSizeBinary => skip_fixed_size_binary(field_nodes, buffers),
-        List => skip_list::<i32>(field_nodes, dtype, buffers, variadic_buffer_counts),
-        LargeList => skip_list::<i64>(field_nodes, dtype, buffers, variadic_buffer_counts),
-        FixedSizeList => skip_fixed_size_list(field_nodes, dtype, buffers, variadic_buffer_counts),
-        Struct => skip_struct(field_nodes, dtype, buffers, variadic_buffer_counts),
+        List => skip_list::<i32>(field_nodes, dtype, buffers, variadic_buffer_counts, version),
+        LargeList => skip_list::<i64>(field_nodes, dtype, buffers, variadic_buffer_counts, version),
+        FixedSizeList => {
+            skip_fixed_size_list(field_nodes, dtype, buffers, variadic_buffer_counts, version)
+        },
+        Struct => skip_struct(field_nodes, dtype, buffers, variadic_buffer_counts, version),
         Dictionary(_) => skip_dictionary(field_nodes, buffers),
-        Union => skip_union(field_nodes, dtype, buffers, variadic_buffer_counts),
-        Map => skip_map(field_nodes, dtype, buffers, variadic_buffer_counts),
+        Union => skip_union(field_nodes, dtype, buffers, variadic_buffer_counts, version),
+        Map => skip_map(field_nodes, dtype, buffers, variadic_buffer_counts, version),
         BinaryView | Utf8View => skip_binview(field_nodes, buffers, variadic_buffer_counts),
     }
 }
+
+/// Returns the number of field nodes that a column of type `dtype` occupies in a record batch.
+pub fn count_nodes(dtype: &ArrowDataType) -> usize {
+    use PhysicalType::*;
+    match dtype.to_physical_type() {
+        Null | Boolean | Primitive(_) | Binary | LargeBinary | FixedSizeBinary | Utf8
+        | LargeUtf8 | Dictionary(_) | BinaryView | Utf8View => 1,
+        List => 1 + count_nodes(ListArray::<i32>::get_child_type(dtype)),
+        LargeList => 1 + count_nodes(ListArray::<i64>::get_child_type(dtype)),
+        FixedSizeList => 1 + count_nodes(FixedSizeListArray::get_child_and_size(dtype).0.dtype()),
+        Map => 1 + count_nodes(MapArray::get_field(dtype).dtype()),
+        Struct => {
+            1 + StructArray::get_fields(dtype)
+                .iter()
+                .map(|field| count_nodes(field.dtype()))
+                .sum::<usize>()
+        },
+        Union => {
+            1 + UnionArray::get_fields(dtype)
+                .iter()
+                .map(|field| count_nodes(field.dtype()))
+                .sum::<usize>()
+        },
+    }
+}
+
+/// Returns the number of buffers that a column of type `dtype` occupies in a record batch.
+///
+/// Returns `None` if this can't be determined from the type alone: view types carry a variadic
+/// number of data buffers and the buffers of a union depend on the metadata version.
+pub fn count_buffers(dtype: &ArrowDataType) -> Option<usize> {
+    use PhysicalType::*;
+    Some(match dtype.to_physical_type() {
+        Null => 0,
+        Boolean | Primitive(_) | FixedSizeBinary | Dictionary(_) => 2,
+        Binary | LargeBinary | Utf8 | LargeUtf8 => 3,
+        List => 2 + count_buffers(ListArray::<i32>::get_child_type(dtype))?,
+        LargeList => 2 + count_buffers(ListArray::<i64>::get_child_type(dtype))?,
+        FixedSizeList => {
+            1 + count_buffers(FixedSizeListArray::get_child_and_size(dtype).0.dtype())?
+        },
+        Map => 2 + count_buffers(MapArray::get_field(dtype).dtype())?,
+        Struct => {
+            1 + StructArray::get_fields(dtype)
+                .iter()
+                .map(|field| count_buffers(field.dtype()))
+                .sum::<Option<usize>>()?
+        },
+        Union | BinaryView | Utf8View => return None,
+    })
+}
@@ -0,0 +1,65 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use polars_utils::aliases::PlHashMap;
+
+use crate::array::Array;
+use crate::datatypes::ArrowDataType;
+
+/// The content of a dictionary block: two blocks with the same content decode to the same
+/// dictionary.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct DictionaryBlockContent {
+    pub dtype: ArrowDataType,
+    pub is_little_endian: bool,
+    pub message: Vec<u8>,
+    pub body: Vec<u8>,
+}
+
+/// A thread-safe cache of decoded dictionaries, shared across the readers of many files.
+///
+/// Dictionaries are keyed by the content of their dictionary block, such that a dictionary
+/// that is written identically to many files (e.g. a common set of categories of a partitioned
+/// dataset) is only decoded once. Set it as [`ReadOptions::dictionary_cache`] of every reader.
+///
+/// Delta dictionary batches and dictionaries whose values are dictionary-encoded themselves
+/// are not cached.
+///
+/// [`ReadOptions::dictionary_cache`]: super::ReadOptions::dictionary_cache
+#[derive(Debug, Default)]
+pub struct DictionaryCache {
+    dictionaries: Mutex<PlHashMap<DictionaryBlockContent, Box<dyn Array>>>,
+    hits: AtomicUsize,
+}
+
+impl DictionaryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of cached dictionaries.
+    pub fn len(&self) -> usize {
+        self.dictionaries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of dictionaries that were taken from this cache instead of decoded.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn get(&self, content: &DictionaryBlockContent) -> Option<Box<dyn Array>> {
+        let values = self.dictionaries.lock().unwrap().get(content).cloned();
+        if values.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        values
+    }
+
+    pub(super) fn insert(&self, content: DictionaryBlockContent, values: Box<dyn Array>) {
+        self.dictionaries.lock().unwrap().insert(content, values);
+    }
+}
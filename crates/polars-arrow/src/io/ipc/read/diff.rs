@@ -0,0 +1,180 @@
+use std::io::{Read, Seek};
+
+use polars_error::PolarsResult;
+use polars_utils::aliases::PlHashSet;
+use polars_utils::pl_str::PlSmallStr;
+
+use super::{Dictionaries, read_file_dictionaries, read_file_metadata};
+use crate::array::{Array, get_display};
+use crate::datatypes::{ArrowDataType, ArrowSchema};
+
+/// A difference between a field of two schemas, whose fields are matched by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FieldChange {
+    /// The field is only in the second schema.
+    Added {
+        name: PlSmallStr,
+        dtype: ArrowDataType,
+    },
+    /// The field is only in the first schema.
+    Removed {
+        name: PlSmallStr,
+        dtype: ArrowDataType,
+    },
+    /// The type of the field differs.
+    TypeChanged {
+        name: PlSmallStr,
+        from: ArrowDataType,
+        to: ArrowDataType,
+    },
+    /// The nullability of the field differs.
+    NullabilityChanged {
+        name: PlSmallStr,
+        from: bool,
+        to: bool,
+    },
+}
+
+/// The difference between the values of a dictionary of two files, by the id of the dictionary.
+/// Values are compared by their display, with nulls displayed as `null`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DictionaryChange {
+    pub id: i64,
+    /// The values that are only in the dictionary of the second file.
+    pub added: Vec<String>,
+    /// The values that are only in the dictionary of the first file.
+    pub removed: Vec<String>,
+}
+
+/// The differences between the schemas and the dictionaries of two IPC files. See
+/// [`diff_files`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileDiff {
+    /// The fields that differ, in the order of the first schema followed by the fields that were
+    /// added.
+    pub fields: Vec<FieldChange>,
+    /// The dictionaries of both files whose values differ, by increasing id.
+    pub dictionaries: Vec<DictionaryChange>,
+    /// The ids of the dictionaries that are only in the second file.
+    pub added_dictionaries: Vec<i64>,
+    /// The ids of the dictionaries that are only in the first file.
+    pub removed_dictionaries: Vec<i64>,
+}
+
+impl FileDiff {
+    /// Whether the files have the same schema and dictionaries.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.dictionaries.is_empty()
+            && self.added_dictionaries.is_empty()
+            && self.removed_dictionaries.is_empty()
+    }
+}
+
+fn diff_schemas(a: &ArrowSchema, b: &ArrowSchema) -> Vec<FieldChange> {
+    let mut changes = vec![];
+    for field in a.iter_values() {
+        let name = field.name.clone();
+        let Some(other) = b.get(&field.name) else {
+            changes.push(FieldChange::Removed {
+                name,
+                dtype: field.dtype.clone(),
+            });
+            continue;
+        };
+        if field.dtype != other.dtype {
+            changes.push(FieldChange::TypeChanged {
+                name: name.clone(),
+                from: field.dtype.clone(),
+                to: other.dtype.clone(),
+            });
+        }
+        if field.is_nullable != other.is_nullable {
+            changes.push(FieldChange::NullabilityChanged {
+                name,
+                from: field.is_nullable,
+                to: other.is_nullable,
+            });
+        }
+    }
+    changes.extend(
+        b.iter_values()
+            .filter(|field| !a.contains(&field.name))
+            .map(|field| FieldChange::Added {
+                name: field.name.clone(),
+                dtype: field.dtype.clone(),
+            }),
+    );
+    changes
+}
+
+fn display_values(array: &dyn Array) -> Vec<String> {
+    let display = get_display(array, "null");
+    (0..array.len())
+        .map(|index| {
+            let mut value = String::new();
+            display(&mut value, index).unwrap();
+            value
+        })
+        .collect()
+}
+
+fn diff_dictionaries(a: &Dictionaries, b: &Dictionaries, diff: &mut FileDiff) {
+    let mut ids = a.keys().chain(b.keys()).copied().collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+
+    for id in ids {
+        let (a, b) = match (a.get(&id), b.get(&id)) {
+            (Some(a), Some(b)) => (display_values(a.as_ref()), display_values(b.as_ref())),
+            (Some(_), None) => {
+                diff.removed_dictionaries.push(id);
+                continue;
+            },
+            (None, _) => {
+                diff.added_dictionaries.push(id);
+                continue;
+            },
+        };
+        let difference = |values: &[String], others: &[String]| {
+            let others = others.iter().collect::<PlHashSet<_>>();
+            let mut seen = PlHashSet::default();
+            values
+                .iter()
+                .filter(|value| !others.contains(value) && seen.insert(*value))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let (added, removed) = (difference(&b, &a), difference(&a, &b));
+        if !added.is_empty() || !removed.is_empty() {
+            diff.dictionaries
+                .push(DictionaryChange { id, added, removed });
+        }
+    }
+}
+
+/// Compares the schemas and the dictionaries of the IPC files `a` and `b`, without reading any
+/// of their record batches, e.g. to check that a regenerated file did not unexpectedly change.
+///
+/// Fields are matched by name, including the fields that were reordered. Dictionaries are
+/// matched by id and compared by their set of values; the values of the deltas of a dictionary
+/// are included.
+pub fn diff_files<A: Read + Seek, B: Read + Seek>(a: &mut A, b: &mut B) -> PolarsResult<FileDiff> {
+    let metadata_a = read_file_metadata(a)?;
+    let metadata_b = read_file_metadata(b)?;
+    let mut diff = FileDiff {
+        fields: diff_schemas(&metadata_a.schema, &metadata_b.schema),
+        ..Default::default()
+    };
+    let mut scratch = vec![];
+    let options = Default::default();
+    diff_dictionaries(
+        &read_file_dictionaries(a, &metadata_a, &mut scratch, &options)?,
+        &read_file_dictionaries(b, &metadata_b, &mut scratch, &options)?,
+        &mut diff,
+    );
+    Ok(diff)
+}
@@ -97,6 +97,42 @@ pub enum OutOfSpecKind {
     },
     /// FixedSizeBinaryArray has invalid datatype.
     InvalidDataType,
+    /// A field of the schema is nested deeper than allowed
+    NestingTooDeep {
+        /// The deepest allowed nesting
+        limit: usize,
+    },
+    /// The schema has more fields, including nested fields, than allowed
+    TooManyFields {
+        /// The largest allowed number of fields
+        limit: usize,
+    },
+    /// A message has more buffers than allowed
+    TooManyBuffers {
+        /// The number of buffers of the message
+        count: usize,
+        /// The largest allowed number of buffers
+        limit: usize,
+    },
+    /// A message has more field nodes than allowed
+    TooManyNodes {
+        /// The number of field nodes of the message
+        count: usize,
+        /// The largest allowed number of field nodes
+        limit: usize,
+    },
+    /// A view array declares more variadic buffers than allowed or than the message has
+    InvalidVariadicBufferCount {
+        /// The declared number of variadic buffers
+        count: i64,
+        /// The largest allowed number of variadic buffers
+        limit: usize,
+    },
+    /// A dictionary-encoded column refers to a dictionary that was not read
+    MissingDictionary {
+        /// The id of the dictionary
+        id: i64,
+    },
 }
 
 impl Display for OutOfSpecKind {
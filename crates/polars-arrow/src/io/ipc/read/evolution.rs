@@ -0,0 +1,120 @@
+use polars_error::{PolarsResult, polars_bail, polars_ensure};
+use polars_utils::aliases::PlHashMap;
+use polars_utils::pl_str::PlSmallStr;
+
+use crate::array::*;
+use crate::datatypes::{ArrowSchemaRef, Field, PhysicalType};
+use crate::record_batch::RecordBatchT;
+use crate::scalar::*;
+use crate::types::Offset;
+use crate::with_match_primitive_type_full;
+
+/// Takes the columns of `schema` from `chunk` by name, in the order of `schema`.
+///
+/// Columns that `chunk` lacks are filled with their default of `defaults`, or else with nulls
+/// when they are nullable.
+pub(crate) fn conform_to_schema(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    schema: &ArrowSchemaRef,
+    defaults: &PlHashMap<PlSmallStr, Box<dyn Scalar>>,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let length = chunk.len();
+    let (found, arrays) = chunk.into_schema_and_arrays();
+    let columns = schema
+        .iter_values()
+        .map(|field| {
+            if let Some(index) = found.index_of(&field.name) {
+                let array = arrays[index].clone();
+                polars_ensure!(
+                    array.dtype() == &field.dtype,
+                    SchemaMismatch: "column \"{}\" is of type {:?} in the file, but of type {:?} in the reader schema",
+                    field.name, array.dtype(), field.dtype
+                );
+                return Ok(array);
+            }
+            match defaults.get(&field.name) {
+                Some(default) => broadcast_default(field, default.as_ref(), length),
+                None => {
+                    polars_ensure!(
+                        field.is_nullable,
+                        SchemaMismatch: "column \"{}\" is missing from the file, is not nullable and has no default",
+                        field.name
+                    );
+                    Ok(new_null_array(field.dtype.clone(), length))
+                },
+            }
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    RecordBatchT::try_new(length, schema.clone(), columns)
+}
+
+/// Returns an array of `length` times the default value of `field`.
+fn broadcast_default(
+    field: &Field,
+    default: &dyn Scalar,
+    length: usize,
+) -> PolarsResult<Box<dyn Array>> {
+    polars_ensure!(
+        default.dtype() == &field.dtype,
+        SchemaMismatch: "the default of column \"{}\" is of type {:?}, but the column is of type {:?}",
+        field.name, default.dtype(), field.dtype
+    );
+    polars_ensure!(
+        field.is_nullable || default.is_valid(),
+        SchemaMismatch: "the default of column \"{}\" is null, but the column is not nullable",
+        field.name
+    );
+
+    let dtype = field.dtype.clone();
+    let default = default.as_any();
+    use PhysicalType::*;
+    let array = match dtype.to_physical_type() {
+        Null => NullArray::new(dtype, length).boxed(),
+        Boolean => {
+            let value = default.downcast_ref::<BooleanScalar>().unwrap().value();
+            BooleanArray::from_iter(std::iter::repeat_n(value, length)).boxed()
+        },
+        Primitive(primitive) => with_match_primitive_type_full!(primitive, |$T| {
+            let value = *default.downcast_ref::<PrimitiveScalar<$T>>().unwrap().value();
+            PrimitiveArray::<$T>::from_iter(std::iter::repeat_n(value, length))
+                .to(dtype)
+                .boxed()
+        }),
+        Utf8 => broadcast_utf8::<i32>(default, length),
+        LargeUtf8 => broadcast_utf8::<i64>(default, length),
+        Binary => broadcast_binary::<i32>(default, length),
+        LargeBinary => broadcast_binary::<i64>(default, length),
+        Utf8View => {
+            let value = default
+                .downcast_ref::<BinaryViewScalar<str>>()
+                .unwrap()
+                .value();
+            MutableBinaryViewArray::<str>::from_iter(std::iter::repeat_n(value, length))
+                .freeze()
+                .boxed()
+        },
+        BinaryView => {
+            let value = default
+                .downcast_ref::<BinaryViewScalar<[u8]>>()
+                .unwrap()
+                .value();
+            MutableBinaryViewArray::<[u8]>::from_iter(std::iter::repeat_n(value, length))
+                .freeze()
+                .boxed()
+        },
+        _ => polars_bail!(
+            ComputeError: "IPC: default values of type {:?} are not supported", field.dtype
+        ),
+    };
+    Ok(array)
+}
+
+fn broadcast_utf8<O: Offset>(default: &dyn std::any::Any, length: usize) -> Box<dyn Array> {
+    let value = default.downcast_ref::<Utf8Scalar<O>>().unwrap().value();
+    Utf8Array::<O>::from_iter(std::iter::repeat_n(value, length)).boxed()
+}
+
+fn broadcast_binary<O: Offset>(default: &dyn std::any::Any, length: usize) -> Box<dyn Array> {
+    let value = default.downcast_ref::<BinaryScalar<O>>().unwrap().value();
+    BinaryArray::<O>::from_iter(std::iter::repeat_n(value, length)).boxed()
+}
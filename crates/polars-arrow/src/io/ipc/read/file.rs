@@ -8,11 +8,12 @@ use polars_utils::aliases::{InitHashMaps, PlHashMap};
 
 use super::super::{ARROW_MAGIC_V1, ARROW_MAGIC_V2, CONTINUATION_MARKER};
 use super::common::*;
-use super::schema::fb_to_schema;
-use super::{Dictionaries, OutOfSpecKind, SendableIterator};
+use super::dictionary_cache::DictionaryBlockContent;
+use super::schema::{fb_to_schema, rewrite_schema};
+use super::{ComplexityBudget, Dictionaries, OutOfSpecKind, SendableIterator, WriterInfo};
 use crate::array::Array;
-use crate::datatypes::{ArrowSchemaRef, Metadata};
-use crate::io::ipc::IpcSchema;
+use crate::datatypes::{ArrowSchema, ArrowSchemaRef, Metadata};
+use crate::io::ipc::{IpcField, IpcSchema};
 use crate::record_batch::RecordBatchT;
 
 /// Metadata of an Arrow IPC file, written in the footer of the file.
@@ -37,12 +38,103 @@ pub struct FileMetadata {
 
     /// The total size of the file in bytes
     pub size: u64,
+
+    /// The bytes that follow the footer's magic, e.g. a metadata block appended to the file
+    pub(crate) trailing_bytes: Vec<u8>,
+}
+
+/// The kind of message of a block of an IPC file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlockKind {
+    Dictionary,
+    RecordBatch,
+}
+
+/// Where a block of an IPC file is, as declared by the footer of the file, e.g. to persist an
+/// index of the file for later random access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockDescriptor {
+    /// The position of the block among the blocks of its kind, e.g. the index of a record batch
+    /// for [`read_batch`].
+    pub index: usize,
+    pub kind: BlockKind,
+    /// The offset in bytes of the block from the start of the file.
+    pub offset: u64,
+    /// The length in bytes of the message of the block, including its prefix and padding.
+    pub metadata_len: u64,
+    /// The length in bytes of the body of the block, which follows its message.
+    pub body_len: u64,
+}
+
+impl FileMetadata {
+    /// Returns the [`BlockDescriptor`]s of the dictionary batches and the record batches of the
+    /// file, by increasing offset. Only the footer is read.
+    pub fn block_descriptors(&self) -> PolarsResult<Vec<BlockDescriptor>> {
+        let descriptor = |kind, (index, block): (usize, &arrow_format::ipc::Block)| {
+            let length = |length: i64| {
+                u64::try_from(length)
+                    .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))
+            };
+            Ok(BlockDescriptor {
+                index,
+                kind,
+                offset: length(block.offset)?,
+                metadata_len: length(block.meta_data_length.into())?,
+                body_len: length(block.body_length)?,
+            })
+        };
+        let mut descriptors = self
+            .dictionary_blocks()
+            .iter()
+            .enumerate()
+            .map(|block| descriptor(BlockKind::Dictionary, block))
+            .chain(
+                self.blocks
+                    .iter()
+                    .enumerate()
+                    .map(|block| descriptor(BlockKind::RecordBatch, block)),
+            )
+            .collect::<PolarsResult<Vec<_>>>()?;
+        descriptors.sort_by_key(|descriptor| descriptor.offset);
+        Ok(descriptors)
+    }
+
+    /// Returns the blocks of the file's dictionary batches, in file order.
+    pub fn dictionary_blocks(&self) -> &[arrow_format::ipc::Block] {
+        self.dictionaries.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the bytes that follow the magic at the end of the file, if any, e.g. a metadata
+    /// block that was appended to the file.
+    pub fn trailing_bytes(&self) -> &[u8] {
+        &self.trailing_bytes
+    }
+
+    /// Rewrites the schema of the file before any of it is decoded, e.g. to correct the
+    /// timezone of a timestamp column that a producer is known to mislabel. Fields may be
+    /// renamed and relabeled, but the rewritten schema must have the same number of fields, each
+    /// laid out in the same buffers as before.
+    pub fn rewrite_schema(
+        &mut self,
+        rewrite: impl FnOnce(ArrowSchema) -> PolarsResult<ArrowSchema>,
+    ) -> PolarsResult<()> {
+        self.schema = Arc::new(rewrite_schema(&self.schema, rewrite)?);
+        Ok(())
+    }
+
+    /// Returns what the writer of the file recorded about how it was written. See
+    /// [`WriterInfo`].
+    pub fn writer_info(&self) -> PolarsResult<WriterInfo> {
+        WriterInfo::try_from_metadata(self.custom_schema_metadata.as_deref())
+    }
 }
 
 /// Read the row count by summing the length of the of the record batches
 pub fn get_row_count<R: Read + Seek>(reader: &mut R) -> PolarsResult<i64> {
-    let (_, footer_len) = read_footer_len(reader)?;
-    let footer = read_footer(reader, footer_len)?;
+    let (end, footer_len) = read_footer_len(reader)?;
+    let footer = read_footer(reader, end, footer_len)?;
     let (_, blocks) = deserialize_footer_blocks(&footer)?;
 
     get_row_count_from_blocks(reader, &blocks)
@@ -78,6 +170,7 @@ pub(crate) fn get_dictionary_batch<'a>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn read_dictionary_block<R: Read + Seek>(
     reader: &mut R,
     metadata: &FileMetadata,
@@ -85,9 +178,33 @@ fn read_dictionary_block<R: Read + Seek>(
     dictionaries: &mut Dictionaries,
     message_scratch: &mut Vec<u8>,
     dictionary_scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    filter: &impl Fn(i64) -> bool,
+    history: Option<&mut DictionaryHistory>,
+    block_index: usize,
+    diagnostics: &mut ReadDiagnostics,
 ) -> PolarsResult<()> {
-    let message = get_message_from_block(reader, block, message_scratch)?;
+    get_message_from_block(reader, block, message_scratch)?;
+    // the message is part of the content that dictionaries are cached by
+    let cached_message = options
+        .dictionary_cache
+        .as_ref()
+        .map(|_| message_scratch.clone());
+    let message = arrow_format::ipc::MessageRef::read_as_root(message_scratch)
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferMessage(err)))?;
     let batch = get_dictionary_batch(&message)?;
+    let version = message
+        .version()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferVersion(err)))?;
+    let id = batch
+        .id()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferId(err)))?;
+    if !filter(id) {
+        return Ok(());
+    }
+    if let Some(history) = history {
+        record_dictionary_event(history, batch, block_index)?;
+    }
 
     let offset: u64 = block
         .offset
@@ -99,15 +216,74 @@ fn read_dictionary_block<R: Read + Seek>(
         .try_into()
         .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
 
+    if let (Some(cache), Some(message)) = (&options.dictionary_cache, cached_message) {
+        let is_delta = batch
+            .is_delta()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferIsDelta(err)))?;
+        let Some((field, ipc_field)) = dictionary_field(
+            id,
+            &metadata.schema,
+            &metadata.ipc_schema.fields,
+            options,
+            diagnostics,
+        )?
+        else {
+            return Ok(());
+        };
+        let mut nested_ids = vec![];
+        for child in &ipc_field.fields {
+            collect_dictionary_ids(child, &mut nested_ids);
+        }
+        if !is_delta && nested_ids.is_empty() {
+            let body_length: u64 = block
+                .body_length
+                .try_into()
+                .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+            let mut body = vec![];
+            body.try_reserve(body_length as usize)?;
+            reader.seek(SeekFrom::Start(offset + length))?;
+            reader.by_ref().take(body_length).read_to_end(&mut body)?;
+            let content = DictionaryBlockContent {
+                dtype: field.dtype.clone(),
+                is_little_endian: metadata.ipc_schema.is_little_endian,
+                message,
+                body,
+            };
+            if let Some(values) = cache.get(&content) {
+                dictionaries.insert(id, values);
+                return Ok(());
+            }
+
+            read_dictionary(
+                batch,
+                &metadata.schema,
+                &metadata.ipc_schema,
+                dictionaries,
+                version,
+                &mut std::io::Cursor::new(content.body.as_slice()),
+                0,
+                content.body.len() as u64,
+                dictionary_scratch,
+                options,
+                diagnostics,
+            )?;
+            cache.insert(content, dictionaries[&id].clone());
+            return Ok(());
+        }
+    }
+
     read_dictionary(
         batch,
         &metadata.schema,
         &metadata.ipc_schema,
         dictionaries,
+        version,
         reader,
         offset + length,
         metadata.size,
         dictionary_scratch,
+        options,
+        diagnostics,
     )
 }
 
@@ -117,6 +293,161 @@ pub fn read_file_dictionaries<R: Read + Seek>(
     reader: &mut R,
     metadata: &FileMetadata,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<Dictionaries> {
+    read_dictionaries_where(
+        reader,
+        metadata,
+        scratch,
+        options,
+        &|_| true,
+        None,
+        &mut ReadDiagnostics::default(),
+    )
+}
+
+/// Reads all file's dictionaries, recording how each dictionary id was built in `history` and
+/// appending the diagnostics to `diagnostics`.
+pub(super) fn read_file_dictionaries_with_history<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetadata,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    history: &mut DictionaryHistory,
+    diagnostics: &mut ReadDiagnostics,
+) -> PolarsResult<Dictionaries> {
+    read_dictionaries_where(
+        reader,
+        metadata,
+        scratch,
+        options,
+        &|_| true,
+        Some(history),
+        diagnostics,
+    )
+}
+
+/// Reads the file's dictionaries that the column at `field_index` refers to, including the
+/// dictionaries nested in its children.
+pub(super) fn read_column_dictionaries<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetadata,
+    field_index: usize,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<Dictionaries> {
+    let mut ids = vec![];
+    collect_dictionary_ids(&metadata.ipc_schema.fields[field_index], &mut ids);
+    read_dictionaries_where(
+        reader,
+        metadata,
+        scratch,
+        options,
+        &|id| ids.contains(&id),
+        None,
+        &mut ReadDiagnostics::default(),
+    )
+}
+
+/// Reads the file's dictionaries that are nested in columns that are not dictionary-encoded
+/// themselves, i.e. the dictionaries that are needed when the top-level dictionary-encoded
+/// columns are read as their keys. See [`read_file_dictionaries_with_history`].
+pub(super) fn read_nested_dictionaries<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetadata,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    history: &mut DictionaryHistory,
+    diagnostics: &mut ReadDiagnostics,
+) -> PolarsResult<Dictionaries> {
+    let ids = nested_dictionary_ids(&metadata.ipc_schema.fields);
+    read_dictionaries_where(
+        reader,
+        metadata,
+        scratch,
+        options,
+        &|id| ids.contains(&id),
+        Some(history),
+        diagnostics,
+    )
+}
+
+/// Reads the dictionaries that are nested in the columns `columns` that are not
+/// dictionary-encoded themselves. See [`read_nested_dictionaries`].
+pub(super) fn read_column_nested_dictionaries<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetadata,
+    columns: &[usize],
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<Dictionaries> {
+    let fields = &metadata.ipc_schema.fields;
+    let ids = nested_dictionary_ids(columns.iter().map(|&column| &fields[column]));
+    read_dictionaries_where(
+        reader,
+        metadata,
+        scratch,
+        options,
+        &|id| ids.contains(&id),
+        None,
+        &mut ReadDiagnostics::default(),
+    )
+}
+
+fn nested_dictionary_ids<'a>(ipc_fields: impl IntoIterator<Item = &'a IpcField>) -> Vec<i64> {
+    let mut ids = vec![];
+    for ipc_field in ipc_fields {
+        if ipc_field.dictionary_id.is_none() {
+            collect_dictionary_ids(ipc_field, &mut ids);
+        }
+    }
+    ids
+}
+
+/// Reads the dictionary with id `id` of an Arrow IPC file, without reading any of the other
+/// dictionaries except those nested in it.
+///
+/// This resolves the dictionaries of columns read with
+/// [`ReadOptions::skip_dictionary_resolution`] on demand.
+pub fn read_dictionary_by_id<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetadata,
+    id: i64,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<Box<dyn Array>> {
+    let (_, ipc_field) = first_dict_field(id, &metadata.schema, &metadata.ipc_schema.fields)?;
+    let mut ids = vec![];
+    collect_dictionary_ids(ipc_field, &mut ids);
+    let mut dictionaries = read_dictionaries_where(
+        reader,
+        metadata,
+        scratch,
+        options,
+        &|id| ids.contains(&id),
+        None,
+        &mut ReadDiagnostics::default(),
+    )?;
+    dictionaries
+        .remove(&id)
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::InvalidId { requested_id: id }))
+}
+
+fn collect_dictionary_ids(ipc_field: &IpcField, ids: &mut Vec<i64>) {
+    ids.extend(ipc_field.dictionary_id);
+    for child in &ipc_field.fields {
+        collect_dictionary_ids(child, ids);
+    }
+}
+
+fn read_dictionaries_where<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetadata,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    filter: &impl Fn(i64) -> bool,
+    mut history: Option<&mut DictionaryHistory>,
+    diagnostics: &mut ReadDiagnostics,
 ) -> PolarsResult<Dictionaries> {
     let mut dictionaries = Default::default();
 
@@ -128,7 +459,7 @@ pub fn read_file_dictionaries<R: Read + Seek>(
     // use a temporary smaller scratch for the messages
     let mut message_scratch = Default::default();
 
-    for block in blocks {
+    for (block_index, block) in blocks.iter().enumerate() {
         read_dictionary_block(
             reader,
             metadata,
@@ -136,11 +467,30 @@ pub fn read_file_dictionaries<R: Read + Seek>(
             &mut dictionaries,
             &mut message_scratch,
             scratch,
+            options,
+            filter,
+            history.as_deref_mut(),
+            block_index,
+            diagnostics,
         )?;
     }
     Ok(dictionaries)
 }
 
+/// Reads all dictionaries of an Arrow IPC file into a standalone map, without reading any of its
+/// record batches.
+///
+/// This is useful to inspect or compare the dictionaries of files.
+pub fn read_all_dictionaries<R: Read + Seek>(reader: &mut R) -> PolarsResult<Dictionaries> {
+    let metadata = read_file_metadata(reader)?;
+    read_file_dictionaries(
+        reader,
+        &metadata,
+        &mut Default::default(),
+        &Default::default(),
+    )
+}
+
 pub(super) fn decode_footer_len(footer: [u8; 10], end: u64) -> PolarsResult<(u64, usize)> {
     let footer_len = i32::from_le_bytes(footer[..4].try_into().unwrap());
 
@@ -157,20 +507,57 @@ pub(super) fn decode_footer_len(footer: [u8; 10], end: u64) -> PolarsResult<(u64
     Ok((end, footer_len))
 }
 
-/// Reads the footer's length and magic number in footer
-fn read_footer_len<R: Read + Seek>(reader: &mut R) -> PolarsResult<(u64, usize)> {
+/// The number of trailing bytes of a file that are searched for the magic that ends its footer,
+/// when the file does not end with it, e.g. because a metadata block was appended to the file.
+const TRAILING_SEARCH_WINDOW: u64 = 1 << 16;
+
+/// Reads the footer's length and magic number in footer, returning the position right after
+/// the magic and the footer's length.
+pub(super) fn read_footer_len<R: Read + Seek>(reader: &mut R) -> PolarsResult<(u64, usize)> {
     // read footer length and magic number in footer
     let end = reader.seek(SeekFrom::End(-10))? + 10;
 
     let mut footer: [u8; 10] = [0; 10];
 
     reader.read_exact(&mut footer)?;
-    decode_footer_len(footer, end)
+    if footer[4..] == ARROW_MAGIC_V2 || footer[..4] == ARROW_MAGIC_V1 {
+        return decode_footer_len(footer, end);
+    }
+
+    // the file has trailing bytes: the footer ends at the last magic that follows a valid footer
+    let window_start = end.saturating_sub(TRAILING_SEARCH_WINDOW);
+    reader.seek(SeekFrom::Start(window_start))?;
+    let mut window = vec![];
+    reader
+        .by_ref()
+        .take(end - window_start)
+        .read_to_end(&mut window)?;
+    for position in (4..window.len().saturating_sub(5)).rev() {
+        let footer: [u8; 10] = window[position - 4..position + 6].try_into().unwrap();
+        let footer_end = window_start + position as u64 + 6;
+        let Ok((footer_end, footer_len)) = decode_footer_len(footer, footer_end) else {
+            continue;
+        };
+        let is_valid = footer_end >= 10 + footer_len as u64
+            && read_footer(reader, footer_end, footer_len)
+                .is_ok_and(|footer| deserialize_footer_ref(&footer).is_ok());
+        if is_valid {
+            return Ok((footer_end, footer_len));
+        }
+    }
+    Err(polars_err!(oos = OutOfSpecKind::InvalidFooter))
 }
 
-fn read_footer<R: Read + Seek>(reader: &mut R, footer_len: usize) -> PolarsResult<Vec<u8>> {
+fn read_footer<R: Read + Seek>(
+    reader: &mut R,
+    end: u64,
+    footer_len: usize,
+) -> PolarsResult<Vec<u8>> {
     // read footer
-    reader.seek(SeekFrom::End(-10 - footer_len as i64))?;
+    let start = end
+        .checked_sub(10 + footer_len as u64)
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::InvalidFooter))?;
+    reader.seek(SeekFrom::Start(start))?;
 
     let mut serialized_footer = vec![];
     serialized_footer.try_reserve(footer_len)?;
@@ -251,13 +638,21 @@ pub(super) fn iter_dictionary_blocks_from_footer(
 }
 
 pub fn deserialize_footer(footer_data: &[u8], size: u64) -> PolarsResult<FileMetadata> {
+    deserialize_footer_with_budget(footer_data, size, &Default::default())
+}
+
+fn deserialize_footer_with_budget(
+    footer_data: &[u8],
+    size: u64,
+    budget: &ComplexityBudget,
+) -> PolarsResult<FileMetadata> {
     let footer = deserialize_footer_ref(footer_data)?;
     let blocks = iter_recordbatch_blocks_from_footer(footer)?.collect::<PolarsResult<Vec<_>>>()?;
     let dictionaries = iter_dictionary_blocks_from_footer(footer)?
         .map(|dicts| dicts.collect::<PolarsResult<Vec<_>>>())
         .transpose()?;
     let ipc_schema = deserialize_schema_ref_from_footer(footer)?;
-    let (schema, ipc_schema, custom_schema_metadata) = fb_to_schema(ipc_schema)?;
+    let (schema, ipc_schema, custom_schema_metadata) = fb_to_schema(ipc_schema, budget)?;
 
     Ok(FileMetadata {
         schema: Arc::new(schema),
@@ -266,15 +661,109 @@ pub fn deserialize_footer(footer_data: &[u8], size: u64) -> PolarsResult<FileMet
         dictionaries,
         size,
         custom_schema_metadata: custom_schema_metadata.map(Arc::new),
+        trailing_bytes: vec![],
     })
 }
 
+/// The number of leading bytes of a file that are searched for its magic, when the file does
+/// not start with it, e.g. because a header block was prepended to the IPC file.
+const MAGIC_SEARCH_WINDOW: u64 = 4096;
+
+/// Returns the position of the leading magic of the IPC file in `reader`, searching from `start`
+/// up to the footer at `footer_start`, or `None` if the magic is not found.
+pub(super) fn find_leading_magic<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    footer_start: u64,
+) -> PolarsResult<Option<u64>> {
+    reader.seek(SeekFrom::Start(start))?;
+    let mut magic = Vec::with_capacity(ARROW_MAGIC_V2.len());
+    reader
+        .by_ref()
+        .take(ARROW_MAGIC_V2.len() as u64)
+        .read_to_end(&mut magic)?;
+    if magic == ARROW_MAGIC_V2 {
+        return Ok(Some(start));
+    }
+
+    reader.seek(SeekFrom::Start(start))?;
+    let mut window = vec![];
+    reader
+        .by_ref()
+        .take(MAGIC_SEARCH_WINDOW.min(footer_start.saturating_sub(start)))
+        .read_to_end(&mut window)?;
+    Ok(window
+        .windows(ARROW_MAGIC_V2.len())
+        .position(|bytes| bytes == ARROW_MAGIC_V2)
+        .map(|position| start + position as u64))
+}
+
 /// Read the Arrow IPC file's metadata
+///
+/// The file may be followed by trailing bytes, which are available from
+/// [`FileMetadata::trailing_bytes`]. See [`read_prefixed_file_metadata`] for files that are
+/// preceded by a header block.
 pub fn read_file_metadata<R: Read + Seek>(reader: &mut R) -> PolarsResult<FileMetadata> {
+    read_file_metadata_with_budget(reader, &Default::default())
+}
+
+/// Read the Arrow IPC file's metadata, erroring before its schema is deserialized if the schema
+/// exceeds `budget`. See [`read_file_metadata`].
+pub fn read_file_metadata_with_budget<R: Read + Seek>(
+    reader: &mut R,
+    budget: &ComplexityBudget,
+) -> PolarsResult<FileMetadata> {
+    let start = reader.stream_position()?;
+    let (end, footer_len) = read_footer_len(reader)?;
+    read_metadata_from_footer(reader, start, end, footer_len, budget)
+}
+
+/// Reads the metadata of the IPC file from `start` to `end` of `reader`, whose footer of
+/// `footer_len` bytes was found by [`read_footer_len`].
+fn read_metadata_from_footer<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    end: u64,
+    footer_len: usize,
+    budget: &ComplexityBudget,
+) -> PolarsResult<FileMetadata> {
+    let serialized_footer = read_footer(reader, end, footer_len)?;
+    let mut metadata = deserialize_footer_with_budget(&serialized_footer, end - start, budget)?;
+
+    reader.seek(SeekFrom::Start(end))?;
+    reader.read_to_end(&mut metadata.trailing_bytes)?;
+    Ok(metadata)
+}
+
+/// Read the metadata of an Arrow IPC file that may be preceded by a small header block, see
+/// [`read_file_metadata_with_budget`].
+///
+/// The first 4096 bytes of the file are searched for its leading magic, and the offsets of its
+/// blocks are shifted by the length of the header block before it. Unlike
+/// [`read_file_metadata`], this errors if the file does not contain the leading magic.
+pub fn read_prefixed_file_metadata<R: Read + Seek>(
+    reader: &mut R,
+    budget: &ComplexityBudget,
+) -> PolarsResult<FileMetadata> {
     let start = reader.stream_position()?;
     let (end, footer_len) = read_footer_len(reader)?;
-    let serialized_footer = read_footer(reader, footer_len)?;
-    deserialize_footer(&serialized_footer, end - start)
+    let mut metadata = read_metadata_from_footer(reader, start, end, footer_len, budget)?;
+
+    let footer_start = end.saturating_sub(10 + footer_len as u64);
+    let prefix_length = find_leading_magic(reader, start, footer_start)?
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::InvalidHeader))?
+        - start;
+    if prefix_length > 0 {
+        let prefix_length = prefix_length as i64;
+        for block in metadata
+            .blocks
+            .iter_mut()
+            .chain(metadata.dictionaries.iter_mut().flatten())
+        {
+            block.offset += prefix_length;
+        }
+    }
+    Ok(metadata)
 }
 
 pub(crate) fn get_record_batch(
@@ -348,6 +837,75 @@ pub fn read_batch<R: Read + Seek>(
     index: usize,
     message_scratch: &mut Vec<u8>,
     data_scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    read_batch_into(
+        reader,
+        dictionaries,
+        metadata,
+        projection,
+        limit,
+        index,
+        message_scratch,
+        data_scratch,
+        &mut vec![],
+        options,
+    )
+}
+
+/// Reads the record batch at position `index` from the reader, like [`read_batch`], decoding its
+/// columns into `columns` instead of into a newly allocated [`Vec`].
+///
+/// `columns` is cleared and moved into the returned batch. Hand the columns of a batch back
+/// with [`RecordBatchT::into_arrays`] once it is consumed to reuse their allocation for the next
+/// batch, avoiding an allocation per batch in hot loops.
+/// # Panics
+/// This function panics iff `index >= metadata.blocks.len()`
+#[allow(clippy::too_many_arguments)]
+pub fn read_batch_into<R: Read + Seek>(
+    reader: &mut R,
+    dictionaries: &Dictionaries,
+    metadata: &FileMetadata,
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
+    index: usize,
+    message_scratch: &mut Vec<u8>,
+    data_scratch: &mut Vec<u8>,
+    columns: &mut Vec<Box<dyn Array>>,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    read_batch_with_diagnostics(
+        reader,
+        dictionaries,
+        metadata,
+        projection,
+        limit,
+        index,
+        message_scratch,
+        data_scratch,
+        columns,
+        options,
+        &mut ReadDiagnostics::default(),
+    )
+}
+
+/// Reads the record batch at position `index` from the reader, like [`read_batch_into`],
+/// appending its diagnostics to `diagnostics`.
+/// # Panics
+/// This function panics iff `index >= metadata.blocks.len()`
+#[allow(clippy::too_many_arguments)]
+pub(super) fn read_batch_with_diagnostics<R: Read + Seek>(
+    reader: &mut R,
+    dictionaries: &Dictionaries,
+    metadata: &FileMetadata,
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
+    index: usize,
+    message_scratch: &mut Vec<u8>,
+    data_scratch: &mut Vec<u8>,
+    columns: &mut Vec<Box<dyn Array>>,
+    options: &ReadOptions,
+    diagnostics: &mut ReadDiagnostics,
 ) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
     let block = metadata.blocks[index];
 
@@ -364,7 +922,7 @@ pub fn read_batch<R: Read + Seek>(
     let message = get_message_from_block_offset(reader, offset, message_scratch)?;
     let batch = get_record_batch(message)?;
 
-    read_record_batch(
+    read_record_batch_with_diagnostics(
         batch,
         &metadata.schema,
         &metadata.ipc_schema,
@@ -378,5 +936,8 @@ pub fn read_batch<R: Read + Seek>(
         offset + length,
         metadata.size,
         data_scratch,
+        columns,
+        options,
+        diagnostics,
     )
 }
@@ -0,0 +1,301 @@
+//! Async reading of Arrow IPC files, e.g. from an object store or a socket.
+use std::io::{Cursor, SeekFrom};
+
+use arrow_format::ipc::Block;
+use futures::Stream;
+use polars_error::{PolarsResult, polars_err};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+use super::common::*;
+use super::file::{
+    decode_footer_len, get_dictionary_batch, get_message_from_block, get_record_batch,
+};
+use super::{Dictionaries, FileMetadata, OutOfSpecKind, deserialize_footer};
+use crate::array::Array;
+use crate::datatypes::ArrowSchema;
+use crate::record_batch::RecordBatchT;
+
+pub(super) async fn read_footer_len<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+) -> PolarsResult<(u64, usize)> {
+    // read footer length and magic number in footer
+    let end = reader.seek(SeekFrom::End(-10)).await? + 10;
+
+    let mut footer: [u8; 10] = [0; 10];
+    reader.read_exact(&mut footer).await?;
+
+    decode_footer_len(footer, end)
+}
+
+pub(super) async fn read_footer<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    footer_len: usize,
+) -> PolarsResult<Vec<u8>> {
+    // read footer
+    reader.seek(SeekFrom::End(-10 - footer_len as i64)).await?;
+
+    let mut serialized_footer = vec![];
+    serialized_footer.try_reserve(footer_len)?;
+
+    reader
+        .take(footer_len as u64)
+        .read_to_end(&mut serialized_footer)
+        .await?;
+    Ok(serialized_footer)
+}
+
+/// Reads the metadata of the Arrow IPC file of `reader`, without blocking. See
+/// [`read_file_metadata`](super::read_file_metadata), which also supports files that are
+/// preceded or followed by other bytes.
+pub async fn read_file_metadata_async<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+) -> PolarsResult<FileMetadata> {
+    let (end, footer_len) = read_footer_len(reader).await?;
+    let serialized_footer = read_footer(reader, footer_len).await?;
+    deserialize_footer(&serialized_footer, end)
+}
+
+/// Reads the message and the body of `block` into `data`, returning a reader of them and the
+/// block of the message in that reader.
+async fn read_block<'a, R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    block: &Block,
+    data: &'a mut Vec<u8>,
+) -> PolarsResult<(Cursor<&'a [u8]>, Block)> {
+    let offset: u64 = block
+        .offset
+        .try_into()
+        .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+    let length: u64 = (block.meta_data_length as i64)
+        .checked_add(block.body_length)
+        .and_then(|length| length.try_into().ok())
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+
+    reader.seek(SeekFrom::Start(offset)).await?;
+    data.clear();
+    data.try_reserve(length as usize)?;
+    reader.take(length).read_to_end(data).await?;
+    let block = Block {
+        offset: 0,
+        ..*block
+    };
+    Ok((Cursor::new(data.as_slice()), block))
+}
+
+/// An async reader of the [`RecordBatchT`]s of an Arrow IPC file, which reads every block of
+/// the file without blocking and decodes it in memory, like a [`FileReader`].
+///
+/// Its [`ReadOptions`] apply as in a [`FileReader`], except for the options that skip, reverse
+/// or split record batches, which this reader ignores.
+///
+/// [`FileReader`]: super::FileReader
+pub struct AsyncFileReader<R: AsyncRead + AsyncSeek + Unpin> {
+    reader: R,
+    metadata: FileMetadata,
+    dictionaries: Option<Dictionaries>,
+    current_block: usize,
+    projection: Option<ProjectionInfo>,
+    remaining: usize,
+    options: ReadOptions,
+    data: Vec<u8>,
+    message_scratch: Vec<u8>,
+    data_scratch: Vec<u8>,
+    // the number of rows of the record batches that were read
+    row_count: usize,
+    // the diagnostics of the dictionaries and record batches that were read
+    diagnostics: ReadDiagnostics,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncFileReader<R> {
+    /// Creates a new [`AsyncFileReader`] of the file of `metadata`, e.g. as read by
+    /// [`read_file_metadata_async`]. Use `projection` to only take certain columns, in any order.
+    pub fn new(
+        reader: R,
+        metadata: FileMetadata,
+        projection: Option<Vec<usize>>,
+        limit: Option<usize>,
+    ) -> Self {
+        let projection =
+            projection.map(|projection| prepare_projection(&metadata.schema, projection));
+        Self {
+            reader,
+            metadata,
+            dictionaries: None,
+            current_block: 0,
+            projection,
+            remaining: limit.unwrap_or(usize::MAX),
+            options: Default::default(),
+            data: vec![],
+            message_scratch: vec![],
+            data_scratch: vec![],
+            row_count: 0,
+            diagnostics: Default::default(),
+        }
+    }
+
+    /// Sets the [`ReadOptions`] of this reader.
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.projection =
+            options_projection(&self.metadata.schema, self.projection.take(), &options);
+        self.options = options;
+        self
+    }
+
+    /// Returns the metadata of the file.
+    pub fn metadata(&self) -> &FileMetadata {
+        &self.metadata
+    }
+
+    /// Returns the schema of the record batches of this reader.
+    pub fn schema(&self) -> &ArrowSchema {
+        if let Some(schema) = &self.options.reader_schema {
+            return schema;
+        }
+        self.projection
+            .as_ref()
+            .map(|x| &x.schema)
+            .unwrap_or(&self.metadata.schema)
+    }
+
+    /// Takes the [`ReadDiagnostics`] of the dictionaries and record batches read so far. See
+    /// [`FileReader::take_diagnostics`](super::FileReader::take_diagnostics).
+    pub fn take_diagnostics(&mut self) -> ReadDiagnostics {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Consumes this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    async fn read_dictionaries(&mut self) -> PolarsResult<()> {
+        if self.dictionaries.is_some() {
+            return Ok(());
+        }
+        let mut dictionaries = Dictionaries::default();
+        for block in self.metadata.dictionaries.iter().flatten() {
+            let (mut reader, block) = read_block(&mut self.reader, block, &mut self.data).await?;
+            let message = get_message_from_block(&mut reader, &block, &mut self.message_scratch)?;
+            let batch = get_dictionary_batch(&message)?;
+            let version = message
+                .version()
+                .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferVersion(err)))?;
+            let file_size = reader.get_ref().len() as u64;
+            read_dictionary(
+                batch,
+                &self.metadata.schema,
+                &self.metadata.ipc_schema,
+                &mut dictionaries,
+                version,
+                &mut reader,
+                block.meta_data_length as u64,
+                file_size,
+                &mut self.data_scratch,
+                &self.options,
+                &mut self.diagnostics,
+            )?;
+        }
+        self.dictionaries = Some(dictionaries);
+        Ok(())
+    }
+
+    /// Reads the next record batch of the file, or `None` once every record batch was read.
+    pub async fn next_batch(&mut self) -> PolarsResult<Option<RecordBatchT<Box<dyn Array>>>> {
+        if self.current_block == self.metadata.blocks.len() || self.remaining == 0 {
+            return Ok(None);
+        }
+        self.read_dictionaries().await?;
+
+        let block = self.metadata.blocks[self.current_block];
+        self.current_block += 1;
+        let (mut reader, block) = read_block(&mut self.reader, &block, &mut self.data).await?;
+        let message = get_message_from_block(&mut reader, &block, &mut self.message_scratch)?;
+        let batch = get_record_batch(message)?;
+        let version = message
+            .version()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferVersion(err)))?;
+        let file_size = reader.get_ref().len() as u64;
+        let chunk = read_record_batch_with_diagnostics(
+            batch,
+            &self.metadata.schema,
+            &self.metadata.ipc_schema,
+            self.projection.as_ref().map(|x| x.columns.as_ref()),
+            Some(self.remaining),
+            self.dictionaries.as_ref().unwrap(),
+            version,
+            &mut reader,
+            block.meta_data_length as u64,
+            file_size,
+            &mut self.data_scratch,
+            &mut vec![],
+            &self.options,
+            &mut self.diagnostics,
+        )?;
+        let row = self.row_count;
+        self.remaining -= chunk.len();
+        self.row_count += chunk.len();
+
+        let chunk = prepend_row_index(chunk, row, &self.options)?;
+        apply_options(chunk, &self.options).map(Some)
+    }
+
+    /// Converts this reader into a [`Stream`] of its record batches.
+    pub fn into_stream(self) -> impl Stream<Item = PolarsResult<RecordBatchT<Box<dyn Array>>>> {
+        futures::stream::try_unfold(self, |mut reader| async move {
+            Ok(reader.next_batch().await?.map(|chunk| (chunk, reader)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::{DictionaryArray, Int64Array, PrimitiveArray, Utf8Array};
+    use crate::datatypes::Field;
+    use crate::io::ipc::read::{FileReader, read_file_metadata};
+    use crate::io::ipc::write::{FileWriter, WriteOptions};
+
+    #[tokio::test]
+    async fn test_async_file_reader() -> PolarsResult<()> {
+        let dictionary = DictionaryArray::try_from_keys(
+            PrimitiveArray::<u32>::from_slice([1, 0, 1]),
+            Utf8Array::<i64>::from_slice(["x", "y"]).boxed(),
+        )?;
+        let schema = Arc::new(ArrowSchema::from_iter([
+            Field::new("ints".into(), crate::datatypes::ArrowDataType::Int64, true),
+            Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+        ]));
+        let mut writer = FileWriter::try_new(
+            vec![],
+            schema.clone(),
+            None,
+            WriteOptions { compression: None },
+        )?;
+        for i in 0..3 {
+            let ints = Int64Array::from_slice([i, i + 1, i + 2]).boxed();
+            let batch =
+                RecordBatchT::try_new(3, schema.clone(), vec![ints, dictionary.clone().boxed()])?;
+            writer.write(&batch, None)?;
+        }
+        writer.finish()?;
+        let data = writer.into_inner();
+
+        let mut reader = Cursor::new(data.as_slice());
+        let metadata = read_file_metadata(&mut reader)?;
+        let expected = FileReader::new(reader, metadata, Some(vec![1, 0]), Some(7))
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        let mut reader = Cursor::new(data.as_slice());
+        let metadata = read_file_metadata_async(&mut reader).await?;
+        let reader = AsyncFileReader::new(reader, metadata, Some(vec![1, 0]), Some(7));
+        assert_eq!(reader.schema().len(), 2);
+        let batches = reader.into_stream().try_collect::<Vec<_>>().await?;
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches, expected);
+        Ok(())
+    }
+}
@@ -11,9 +11,10 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 use crate::datatypes::ArrowSchema;
 use crate::io::ipc::read::common::read_record_batch;
 use crate::io::ipc::read::file::{
-    decode_footer_len, deserialize_schema_ref_from_footer, iter_dictionary_blocks_from_footer,
+    deserialize_schema_ref_from_footer, iter_dictionary_blocks_from_footer,
     iter_recordbatch_blocks_from_footer,
 };
+use crate::io::ipc::read::file_async::{read_footer, read_footer_len};
 use crate::io::ipc::read::schema::deserialize_stream_metadata;
 use crate::io::ipc::read::{Dictionaries, OutOfSpecKind, SendableIterator, StreamMetadata};
 use crate::io::ipc::write::common::EncodedData;
@@ -61,35 +62,6 @@ async fn read_ipc_message<'a, R: AsyncRead + Unpin>(
         .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferMessage(err)))
 }
 
-async fn read_footer_len<R: AsyncRead + AsyncSeek + Unpin>(
-    reader: &mut R,
-) -> PolarsResult<(u64, usize)> {
-    // read footer length and magic number in footer
-    let end = reader.seek(SeekFrom::End(-10)).await? + 10;
-
-    let mut footer: [u8; 10] = [0; 10];
-    reader.read_exact(&mut footer).await?;
-
-    decode_footer_len(footer, end)
-}
-
-async fn read_footer<R: AsyncRead + AsyncSeek + Unpin>(
-    reader: &mut R,
-    footer_len: usize,
-) -> PolarsResult<Vec<u8>> {
-    // read footer
-    reader.seek(SeekFrom::End(-10 - footer_len as i64)).await?;
-
-    let mut serialized_footer = vec![];
-    serialized_footer.try_reserve(footer_len)?;
-
-    reader
-        .take(footer_len as u64)
-        .read_to_end(&mut serialized_footer)
-        .await?;
-    Ok(serialized_footer)
-}
-
 fn schema_to_raw_message(schema: arrow_format::ipc::SchemaRef) -> EncodedData {
     // Turn the IPC schema into an encapsulated message
     let message = arrow_format::ipc::Message {
@@ -281,7 +253,7 @@ pub struct FlightConsumer {
 
 impl FlightConsumer {
     pub fn new(first: EncodedData) -> PolarsResult<Self> {
-        let md = deserialize_stream_metadata(&first.ipc_message)?;
+        let md = deserialize_stream_metadata(&first.ipc_message, &Default::default())?;
         Ok(Self {
             dictionaries: Default::default(),
             md,
@@ -339,6 +311,7 @@ impl FlightConsumer {
                         0,
                         data_size,
                         &mut self.scratch,
+                        &Default::default(),
                     )
                     .map(Some)
                 } else {
@@ -0,0 +1,200 @@
+use std::io::{Read, Seek};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, channel};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+use polars_error::PolarsResult;
+
+use super::FileReader;
+use crate::array::Array;
+use crate::record_batch::RecordBatchT;
+
+/// The bytes held by the batches that were decoded but not yet dropped by the consumer.
+#[derive(Debug)]
+struct InflightBudget {
+    max_bytes: usize,
+    // the in-flight bytes, and whether the consumer stopped reading
+    state: Mutex<(usize, bool)>,
+    freed: Condvar,
+    produced: AtomicUsize,
+}
+
+impl InflightBudget {
+    /// Blocks until the in-flight bytes are below the cap. Returns `false` when the consumer
+    /// stopped reading.
+    fn wait_below_cap(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        while state.0 >= self.max_bytes && !state.1 {
+            state = self.freed.wait(state).unwrap();
+        }
+        !state.1
+    }
+
+    fn acquire(&self, bytes: usize) {
+        self.state.lock().unwrap().0 += bytes;
+        self.produced.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn release(&self, bytes: usize) {
+        self.state.lock().unwrap().0 -= bytes;
+        self.freed.notify_all();
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().1 = true;
+        self.freed.notify_all();
+    }
+}
+
+/// A record batch of [`InflightBatches`]. Its bytes count towards the in-flight bytes until it
+/// is dropped.
+#[derive(Debug)]
+pub struct InflightBatch {
+    batch: RecordBatchT<Box<dyn Array>>,
+    bytes: usize,
+    budget: Arc<InflightBudget>,
+}
+
+impl InflightBatch {
+    /// Returns the estimated size in bytes that this batch holds.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl Deref for InflightBatch {
+    type Target = RecordBatchT<Box<dyn Array>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.batch
+    }
+}
+
+impl Drop for InflightBatch {
+    fn drop(&mut self) {
+        self.budget.release(self.bytes);
+    }
+}
+
+/// An iterator of the record batches of a [`FileReader`] that is decoded on a background thread
+/// under a cap on the bytes held by batches that were decoded but not yet dropped.
+/// See [`read_with_inflight_cap`].
+pub struct InflightBatches {
+    receiver: Receiver<PolarsResult<InflightBatch>>,
+    budget: Arc<InflightBudget>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InflightBatches {
+    /// Returns the estimated size in bytes of the batches that were decoded but not yet dropped.
+    pub fn inflight_bytes(&self) -> usize {
+        self.budget.state.lock().unwrap().0
+    }
+
+    /// Returns the number of batches that were decoded so far.
+    pub fn produced(&self) -> usize {
+        self.budget.produced.load(Ordering::Relaxed)
+    }
+}
+
+impl Iterator for InflightBatches {
+    type Item = PolarsResult<InflightBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl Drop for InflightBatches {
+    fn drop(&mut self) {
+        self.budget.close();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Decodes the record batches of `reader` on a background thread, which stalls while the
+/// batches that were decoded but not yet dropped by the consumer hold `max_inflight_bytes` or
+/// more.
+///
+/// The size of a record batch is estimated from its buffers before it is decoded, see
+/// [`FileReader::estimate_batch_size`]; the output batches of a split record batch share its
+/// size. A record batch larger than `max_inflight_bytes` is still decoded once all previous
+/// batches were dropped.
+pub fn read_with_inflight_cap<R: Read + Seek + Send + 'static>(
+    mut reader: FileReader<R>,
+    max_inflight_bytes: usize,
+) -> InflightBatches {
+    let budget = Arc::new(InflightBudget {
+        max_bytes: max_inflight_bytes,
+        state: Mutex::new((0, false)),
+        freed: Condvar::new(),
+        produced: AtomicUsize::new(0),
+    });
+    let (sender, receiver) = channel();
+
+    let producer_budget = budget.clone();
+    let handle = std::thread::spawn(move || {
+        let budget = producer_budget;
+        while reader.get_current_block() < reader.metadata().blocks.len() {
+            if !budget.wait_below_cap() {
+                return;
+            }
+            let index = reader.block_index(reader.get_current_block());
+            let bytes = match reader.estimate_batch_size(index) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    let _ = sender.send(Err(e));
+                    return;
+                },
+            };
+
+            // all output batches of the record batch are decoded at once
+            let mut batches = vec![];
+            // whether the reader stopped, e.g. at `ReadOptions::max_batches`, before its last block
+            let mut exhausted = false;
+            loop {
+                match reader.next() {
+                    Some(Ok(batch)) => batches.push(batch),
+                    Some(Err(e)) => {
+                        let _ = sender.send(Err(e));
+                        return;
+                    },
+                    None => {
+                        exhausted = true;
+                        break;
+                    },
+                }
+                if !reader.has_pending() {
+                    break;
+                }
+            }
+
+            let n = batches.len().max(1);
+            for (i, batch) in batches.into_iter().enumerate() {
+                let bytes = bytes / n + if i == 0 { bytes % n } else { 0 };
+                budget.acquire(bytes);
+                let batch = InflightBatch {
+                    batch,
+                    bytes,
+                    budget: budget.clone(),
+                };
+                if sender.send(Ok(batch)).is_err() {
+                    return;
+                }
+            }
+            if exhausted {
+                return;
+            }
+        }
+    });
+
+    InflightBatches {
+        receiver,
+        budget,
+        handle: Some(handle),
+    }
+}
@@ -0,0 +1,148 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+
+use arrow_format::ipc::planus::ReadAsRoot;
+use polars_error::{PolarsError, PolarsResult, polars_bail, polars_err};
+
+use super::super::{ARROW_MAGIC_V2, CONTINUATION_MARKER};
+use super::OutOfSpecKind;
+
+/// A message of an Arrow IPC file or stream, with its body left undecoded.
+#[derive(Debug, Clone)]
+pub struct IpcMessage {
+    /// The metadata of the message, including its header.
+    pub message: arrow_format::ipc::Message,
+    /// The byte range of the message's body in the reader.
+    pub body: Range<u64>,
+}
+
+/// An iterator over the [`IpcMessage`]s of an Arrow IPC file or stream.
+///
+/// Only the metadata of every message is read and parsed; the body is skipped and its byte
+/// range is returned such that consumers can decide which bodies to read.
+pub struct MessageCursor<R: Read + Seek> {
+    reader: R,
+    scratch: Vec<u8>,
+    finished: bool,
+}
+
+impl<R: Read + Seek> MessageCursor<R> {
+    /// Creates a new [`MessageCursor`] over a reader positioned at the start of a message, e.g.
+    /// at the start of a stream.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            scratch: vec![],
+            finished: false,
+        }
+    }
+
+    /// Creates a new [`MessageCursor`] over the messages of an Arrow IPC file, whose reader is
+    /// positioned at the start of the file.
+    pub fn try_new_file(mut reader: R) -> PolarsResult<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if magic[..6] != ARROW_MAGIC_V2 {
+            polars_bail!(oos = OutOfSpecKind::InvalidHeader)
+        }
+        Ok(Self::new(reader))
+    }
+
+    /// Consumes this cursor, returning the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_message(&mut self) -> PolarsResult<Option<IpcMessage>> {
+        let mut meta_length: [u8; 4] = [0; 4];
+        match self.reader.read_exact(&mut meta_length) {
+            Ok(()) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(PolarsError::from(e)),
+        }
+        if meta_length == CONTINUATION_MARKER {
+            self.reader.read_exact(&mut meta_length)?;
+        }
+        let meta_length: usize = i32::from_le_bytes(meta_length)
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+        if meta_length == 0 {
+            // end of stream marker
+            return Ok(None);
+        }
+
+        self.scratch.clear();
+        self.scratch.try_reserve(meta_length)?;
+        self.reader
+            .by_ref()
+            .take(meta_length as u64)
+            .read_to_end(&mut self.scratch)?;
+
+        let message = arrow_format::ipc::MessageRef::read_as_root(&self.scratch)
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferMessage(err)))?;
+        let body_length: u64 = message
+            .body_length()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferBodyLength(err)))?
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+        let message: arrow_format::ipc::Message = message
+            .try_into()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferMessage(err)))?;
+
+        let start = self.reader.stream_position()?;
+        self.reader.seek(SeekFrom::Start(start + body_length))?;
+
+        Ok(Some(IpcMessage {
+            message,
+            body: start..start + body_length,
+        }))
+    }
+}
+
+impl<R: Read + Seek> Iterator for MessageCursor<R> {
+    type Item = PolarsResult<IpcMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let message = self.read_message().transpose();
+        self.finished = !matches!(message, Some(Ok(_)));
+        message
+    }
+}
+
+/// The multiple of bytes that the body of a message is padded to by this crate's writers.
+const BODY_ALIGNMENT: u64 = 64;
+
+/// Returns the length in bytes of the body of the message of `batch`, as declared by the
+/// `body_length` of its block: the end of its last buffer, padded to a multiple of 64 bytes as
+/// this crate's writers do.
+pub fn record_batch_body_length(batch: arrow_format::ipc::RecordBatchRef) -> PolarsResult<u64> {
+    let buffers = batch
+        .buffers()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferBuffers(err)))?
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageBuffers))?;
+    let end = buffers.iter().try_fold(0u64, |end, buffer| {
+        let buffer_end = buffer
+            .offset()
+            .checked_add(buffer.length())
+            .and_then(|buffer_end| u64::try_from(buffer_end).ok())
+            .ok_or_else(|| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+        PolarsResult::Ok(end.max(buffer_end))
+    })?;
+    Ok(end.next_multiple_of(BODY_ALIGNMENT))
+}
+
+/// Returns the total size in bytes of the message of `batch` in a file or stream, without
+/// decoding it: the continuation marker and length prefix, its metadata of `metadata_length`
+/// bytes padded to a multiple of 8 bytes, and its body. See [`record_batch_body_length`].
+///
+/// `metadata_length` is the length of the serialized flatbuffer `Message` of `batch`.
+pub fn record_batch_message_size(
+    batch: arrow_format::ipc::RecordBatchRef,
+    metadata_length: usize,
+) -> PolarsResult<u64> {
+    let prefixed_length = (CONTINUATION_MARKER.len() + 4 + metadata_length).next_multiple_of(8);
+    Ok(prefixed_length as u64 + record_batch_body_length(batch)?)
+}
@@ -6,29 +6,97 @@
 //! data in the order it was written in.
 use crate::array::Array;
 
+mod arena;
 mod array;
+#[cfg(feature = "arrow_rs")]
+mod arrow_rs;
+mod budget;
+mod c_data;
 mod common;
 mod deserialize;
+mod dictionary_cache;
+mod diff;
 mod error;
+mod evolution;
 pub(crate) mod file;
+#[cfg(feature = "io_ipc_read_async")]
+mod file_async;
 #[cfg(feature = "io_flight")]
 mod flight;
+mod inflight;
+mod message;
+mod multi_file;
+mod open;
+mod parallel;
+mod pool;
+mod prefetch;
 mod read_basic;
 mod reader;
+mod scan;
 mod schema;
+mod shared_dictionary;
+#[cfg(feature = "io_ipc_spill")]
+mod spill;
+mod statistics;
 mod stream;
+#[cfg(feature = "io_ipc_read_async")]
+mod stream_async;
+mod string_cache;
+mod structure;
+mod timezone;
+mod window;
+mod writer_info;
 
-pub(crate) use common::first_dict_field;
-pub use common::{ProjectionInfo, prepare_projection};
+pub use arena::{ArenaBatches, read_into_arena};
+#[cfg(feature = "arrow_rs")]
+pub use arrow_rs::{read_record_batch_arrow_rs, to_arrow_rs};
+pub use budget::{ColumnBudget, ComplexityBudget};
+pub use c_data::{CDataBatch, read_record_batch_c_data, to_c_data};
+#[allow(deprecated)]
+pub use common::apply_projection;
+pub use common::{
+    BufferAllocator, ColumnError, ColumnErrorPolicy, DEFAULT_MAX_DECOMPRESSED_SIZE,
+    DEFAULT_ZSTD_MAX_WINDOW_SIZE, DictionaryEvent, DictionaryEventKind, DictionaryHistory,
+    DictionaryResolver, FIELD_ID_KEYS, MissingDictionaryPolicy, OrderedProjection, ProjectionInfo,
+    ReadDiagnostics, ReadOptions, compression_ratios, concatenate_batches, estimate_batch_size,
+    prepare_projection, prepare_projection_by_field_id, prepare_projection_by_type,
+    prepare_projection_mask,
+};
+pub(crate) use common::{append_delta_dictionary, first_dict_field, read_variadic_buffer_counts};
+pub use dictionary_cache::DictionaryCache;
+pub use diff::{DictionaryChange, FieldChange, FileDiff, diff_files};
 pub use error::OutOfSpecKind;
 pub use file::{
-    FileMetadata, deserialize_footer, get_row_count, get_row_count_from_blocks, read_batch,
-    read_file_dictionaries, read_file_metadata,
+    BlockDescriptor, BlockKind, FileMetadata, deserialize_footer, get_row_count,
+    get_row_count_from_blocks, read_all_dictionaries, read_batch, read_batch_into,
+    read_dictionary_by_id, read_file_dictionaries, read_file_metadata,
+    read_file_metadata_with_budget, read_prefixed_file_metadata,
 };
+#[cfg(feature = "io_ipc_read_async")]
+pub use file_async::{AsyncFileReader, read_file_metadata_async};
+pub use inflight::{InflightBatch, InflightBatches, read_with_inflight_cap};
+pub use message::{IpcMessage, MessageCursor, record_batch_body_length, record_batch_message_size};
+pub use multi_file::{MetadataPolicy, MultiFileReader};
+pub use open::{IpcSourceReader, open};
+pub use parallel::Parallelism;
 use polars_utils::aliases::PlHashMap;
-pub use reader::FileReader;
-pub use schema::deserialize_schema;
-pub use stream::{StreamMetadata, StreamReader, StreamState, read_stream_metadata};
+pub use pool::BufferPool;
+pub use prefetch::BlockPrefetch;
+pub use reader::{FileReader, column_iter, dictionary_keys_iter, sample_first_rows};
+pub use scan::{IpcScanSource, ScanPushdown, ScanSource, StatisticsPredicate};
+pub use schema::{MAX_NESTING_DEPTH, SchemaCheckOptions, check_schema, deserialize_schema};
+#[cfg(feature = "io_ipc_spill")]
+pub use spill::{Spilled, read_spilled, spill};
+pub use statistics::{BatchStatistics, ColumnStatistics, STATISTICS_PREFIX};
+pub use stream::{
+    BlockAction, BlockInfo, StreamMetadata, StreamReader, StreamState, Truncation,
+    read_stream_metadata, read_stream_metadata_with_budget, read_unfinished_file_metadata,
+};
+#[cfg(feature = "io_ipc_read_async")]
+pub use stream_async::{AsyncStreamReader, read_stream_metadata_async};
+pub use string_cache::StringCache;
+pub use window::{Window, read_window};
+pub use writer_info::{COMPRESSION_LEVEL_KEY, WriterInfo};
 
 /// how dictionaries are tracked in this crate
 pub type Dictionaries = PlHashMap<i64, Box<dyn Array>>;
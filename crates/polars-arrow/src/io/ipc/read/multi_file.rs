@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use std::io::{Read, Seek};
+
+use polars_error::{PolarsResult, polars_bail, polars_err};
+use polars_utils::IdxSize;
+
+use super::common::{ReadOptions, try_prepare_projection};
+use super::{FileMetadata, FileReader, read_file_metadata};
+use crate::array::Array;
+use crate::datatypes::{ArrowDataType, ArrowSchema};
+use crate::record_batch::RecordBatchT;
+
+/// How a [`MultiFileReader`] treats differences in the metadata of the files' schemas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MetadataPolicy {
+    /// Metadata differences are allowed; the metadata of the first file is reported.
+    #[default]
+    Ignore,
+    /// The schema and field metadata of every file must equal those of the first file.
+    Strict,
+}
+
+/// An iterator of [`RecordBatchT`]s over several Arrow IPC files with the same schema, read
+/// one after the other as if they were a single file.
+///
+/// Every file is read by its own [`FileReader`], so the dictionaries of one file are never
+/// used to decode another file, even when their dictionary ids collide. The
+/// [`ReadOptions::row_index`], [`ReadOptions::max_batches`] and [`ReadOptions::reverse`] of the
+/// reader apply to the files as a whole: the row index counts the rows of all files, the
+/// record batches of all files count towards the maximum, and the files are read from the last
+/// to the first.
+pub struct MultiFileReader<R: Read + Seek> {
+    // the readers of the files that remain to be read, in file order
+    readers: VecDeque<FileReader<R>>,
+    metadata: FileMetadata,
+    schema: ArrowSchema,
+    options: ReadOptions,
+    // whether the options of the reader of the current file continue from the files before it
+    started: bool,
+    // the number of rows of the files that were read, in reading order
+    row_count: usize,
+    // the number of record batches that were decoded from the files that were read
+    batch_count: usize,
+}
+
+impl<R: Read + Seek> MultiFileReader<R> {
+    /// Creates a new [`MultiFileReader`] over `readers`, in order.
+    ///
+    /// The footer of every file is read upfront to validate that all schemas match the schema
+    /// of the first file by name and type, with metadata compared according to `policy`. The
+    /// columns of `projection` may be in any order, in which they are returned. Errors if
+    /// `projection` selects a column more than once or a column that is not in the schema.
+    pub fn try_new(
+        readers: Vec<R>,
+        projection: Option<Vec<usize>>,
+        policy: MetadataPolicy,
+    ) -> PolarsResult<Self> {
+        let readers = readers
+            .into_iter()
+            .map(|mut reader| {
+                let metadata = read_file_metadata(&mut reader)?;
+                Ok((reader, metadata))
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+        if readers.is_empty() {
+            polars_bail!(ComputeError: "IPC: a MultiFileReader requires at least one file")
+        }
+
+        let metadata = readers[0].1.clone();
+        for (i, (_, other)) in readers.iter().enumerate().skip(1) {
+            check_schemas_match(&metadata, other, i, policy)?;
+        }
+
+        let projection = projection
+            .map(|projection| try_prepare_projection(&metadata.schema, projection))
+            .transpose()?;
+        let readers: VecDeque<_> = readers
+            .into_iter()
+            .map(|(reader, metadata)| {
+                FileReader::new_with_projection_info(reader, metadata, projection.clone(), None)
+            })
+            .collect();
+        let schema = readers[0].schema().clone();
+        Ok(Self {
+            readers,
+            metadata,
+            schema,
+            options: Default::default(),
+            started: false,
+            row_count: 0,
+            batch_count: 0,
+        })
+    }
+
+    /// Sets the [`ReadOptions`] of every file's reader.
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.readers = self
+            .readers
+            .into_iter()
+            .map(|reader| reader.with_options(options.clone()))
+            .collect();
+        if let Some(reader) = self.readers.front() {
+            self.schema = reader.schema().clone();
+        }
+        self.options = options;
+        self
+    }
+
+    /// Returns the [`FileMetadata`] of the first file.
+    pub fn metadata(&self) -> &FileMetadata {
+        &self.metadata
+    }
+
+    /// Return the schema of the batches read.
+    pub fn schema(&self) -> &ArrowSchema {
+        &self.schema
+    }
+}
+
+impl<R: Read + Seek> MultiFileReader<R> {
+    /// Returns the reader of the file that is read next.
+    fn current(&mut self) -> Option<&mut FileReader<R>> {
+        if self.options.reverse {
+            self.readers.back_mut()
+        } else {
+            self.readers.front_mut()
+        }
+    }
+
+    /// Continues the row index and the count of record batches of the files that were read in
+    /// the reader of the file that is read next.
+    fn start_file(&mut self) -> PolarsResult<()> {
+        if let Some((name, offset)) = self.options.row_index.clone() {
+            let row = if self.options.reverse {
+                // the files that precede the file are read after it
+                let mut row = 0usize;
+                let preceding = self.readers.len().saturating_sub(1);
+                for reader in self.readers.iter_mut().take(preceding) {
+                    row += reader.num_rows()?;
+                }
+                row
+            } else {
+                self.row_count
+            };
+            let offset = (offset as usize)
+                .checked_add(row)
+                .and_then(|offset| IdxSize::try_from(offset).ok())
+                .ok_or_else(|| {
+                    polars_err!(ComputeError: "IPC: the row index \"{name}\" overflows its type {:?}", ArrowDataType::IDX_DTYPE)
+                })?;
+            if let Some(reader) = self.current() {
+                reader.options_mut().row_index = Some((name, offset));
+            }
+        }
+        if let Some(max_batches) = self.options.max_batches {
+            let remaining = max_batches.saturating_sub(self.batch_count);
+            if let Some(reader) = self.current() {
+                reader.options_mut().max_batches = Some(remaining);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_schemas_match(
+    expected: &FileMetadata,
+    other: &FileMetadata,
+    file: usize,
+    policy: MetadataPolicy,
+) -> PolarsResult<()> {
+    if expected.schema.len() != other.schema.len() {
+        polars_bail!(SchemaMismatch:
+            "IPC: expected {} columns in file {file}, found {}",
+            expected.schema.len(), other.schema.len()
+        )
+    }
+    for (expected, other) in expected
+        .schema
+        .iter_values()
+        .zip(other.schema.iter_values())
+    {
+        if expected.name != other.name || expected.dtype != other.dtype {
+            polars_bail!(SchemaMismatch:
+                "IPC: expected column {:?} of type {:?} in file {file}, found column {:?} of type {:?}",
+                expected.name, expected.dtype, other.name, other.dtype
+            )
+        }
+        if policy == MetadataPolicy::Strict && expected.metadata != other.metadata {
+            polars_bail!(SchemaMismatch:
+                "IPC: the metadata of column {:?} differs in file {file}", expected.name
+            )
+        }
+    }
+    if policy == MetadataPolicy::Strict
+        && expected.custom_schema_metadata != other.custom_schema_metadata
+    {
+        polars_bail!(SchemaMismatch: "IPC: the schema metadata differs in file {file}")
+    }
+    Ok(())
+}
+
+impl<R: Read + Seek> Iterator for MultiFileReader<R> {
+    type Item = PolarsResult<RecordBatchT<Box<dyn Array>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.options.reached_max_batches(self.batch_count) {
+                return None;
+            }
+            if !self.started {
+                if let Err(e) = self.start_file() {
+                    // the offsets of the files that follow are unknown
+                    self.readers.clear();
+                    return Some(Err(e));
+                }
+                self.started = true;
+            }
+            let reader = self.current()?;
+            match reader.next() {
+                Some(chunk) => return Some(chunk),
+                None => {
+                    let (rows, batches) = reader.progress();
+                    self.row_count += rows;
+                    self.batch_count += batches;
+                    if self.options.reverse {
+                        self.readers.pop_back();
+                    } else {
+                        self.readers.pop_front();
+                    }
+                    self.started = false;
+                },
+            }
+        }
+    }
+}
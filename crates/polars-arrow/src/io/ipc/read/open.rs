@@ -0,0 +1,105 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use polars_error::PolarsResult;
+
+use super::file::{find_leading_magic, read_footer_len};
+use super::{
+    FileReader, ReadOptions, StreamReader, StreamState, read_file_metadata,
+    read_prefixed_file_metadata, read_stream_metadata,
+};
+use crate::array::Array;
+use crate::datatypes::ArrowSchema;
+use crate::io::ipc::IpcSchema;
+use crate::record_batch::RecordBatchT;
+
+/// A reader of the record batches of an Arrow IPC file or stream, whichever [`open`] detected.
+pub enum IpcSourceReader<R: Read + Seek> {
+    File(FileReader<R>),
+    Stream(StreamReader<R>),
+}
+
+impl<R: Read + Seek> IpcSourceReader<R> {
+    /// Whether the reader reads the file format, i.e. with a footer.
+    pub fn is_file(&self) -> bool {
+        matches!(self, Self::File(_))
+    }
+
+    /// Returns the schema of the record batches.
+    pub fn schema(&self) -> &ArrowSchema {
+        match self {
+            Self::File(reader) => reader.schema(),
+            Self::Stream(reader) => reader.schema(),
+        }
+    }
+
+    /// Returns the [`IpcSchema`] of the file or stream.
+    pub fn ipc_schema(&self) -> &IpcSchema {
+        match self {
+            Self::File(reader) => reader.ipc_schema(),
+            Self::Stream(reader) => reader.ipc_schema(),
+        }
+    }
+
+    /// Sets the options of the reader.
+    pub fn with_options(self, options: ReadOptions) -> Self {
+        match self {
+            Self::File(reader) => Self::File(reader.with_options(options)),
+            Self::Stream(reader) => Self::Stream(reader.with_options(options)),
+        }
+    }
+}
+
+impl<R: Read + Seek> Iterator for IpcSourceReader<R> {
+    type Item = PolarsResult<RecordBatchT<Box<dyn Array>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::File(reader) => reader.next(),
+            // the reader is not live: a stream that waits for more data has ended
+            Self::Stream(reader) => match reader.next()? {
+                Ok(StreamState::Some(batch)) => Some(Ok(batch)),
+                Ok(StreamState::Waiting) => None,
+                Err(err) => Some(Err(err)),
+            },
+        }
+    }
+}
+
+/// Returns the position of the leading `ARROW1` magic of the IPC file in `reader` from `start`
+/// on, or `None` if its data is not in the file format. Data that starts with the magic is a
+/// file, as is data that ends with a footer and has the magic after a small header block.
+fn find_file_start<R: Read + Seek>(reader: &mut R, start: u64) -> PolarsResult<Option<u64>> {
+    if let Some(position) = find_leading_magic(reader, start, start)? {
+        return Ok(Some(position));
+    }
+    // a stream has no footer
+    let Ok((end, footer_len)) = read_footer_len(reader) else {
+        return Ok(None);
+    };
+    find_leading_magic(reader, start, end.saturating_sub(10 + footer_len as u64))
+}
+
+/// Opens the Arrow IPC data of `reader`, positioned at its start, without knowing whether it is
+/// in the file or the stream format: data that starts with the `ARROW1` magic, or that has it
+/// after a small header block, see [`read_prefixed_file_metadata`], is read as a file, and
+/// otherwise as a stream.
+pub fn open<R: Read + Seek>(mut reader: R) -> PolarsResult<IpcSourceReader<R>> {
+    let start = reader.stream_position()?;
+    let file_start = find_file_start(&mut reader, start)?;
+    reader.seek(SeekFrom::Start(start))?;
+
+    let Some(file_start) = file_start else {
+        let metadata = read_stream_metadata(&mut reader)?;
+        return Ok(IpcSourceReader::Stream(StreamReader::new(
+            reader, metadata, None,
+        )));
+    };
+    let metadata = if file_start == start {
+        read_file_metadata(&mut reader)?
+    } else {
+        read_prefixed_file_metadata(&mut reader, &Default::default())?
+    };
+    Ok(IpcSourceReader::File(FileReader::new(
+        reader, metadata, None, None,
+    )))
+}
@@ -0,0 +1,334 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use arrow_format::ipc::{BodyCompressionRef, MetadataVersion};
+use polars_error::{PolarsResult, polars_err};
+#[cfg(feature = "io_ipc_rayon")]
+use rayon::prelude::*;
+
+use super::common::{ColumnError, ReadOptions, read_column};
+use super::deserialize::skip;
+use super::{Dictionaries, IpcBuffer, Node, OutOfSpecKind};
+use crate::array::Array;
+use crate::datatypes::{ArrowSchema, Field};
+use crate::io::ipc::{IpcField, IpcSchema};
+
+/// How the columns of a record batch are decoded.
+///
+/// The decoded columns are always in schema (or projection) order, independently of the
+/// parallelism: every column is decoded into its own slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Parallelism {
+    /// Decode the columns one after the other on the calling thread.
+    #[default]
+    Serial,
+    /// Decode the columns on up to `n` threads. The threads are spawned on first use and then
+    /// shared by all reads, instead of being spawned for every record batch.
+    Threads(usize),
+    /// Decode the columns on rayon's global thread pool.
+    #[cfg(feature = "io_ipc_rayon")]
+    Rayon,
+}
+
+/// The nodes and buffers of a single column of a record batch, and the bytes of the body that
+/// they span.
+struct ColumnTask<'a, 'b> {
+    field: &'a Field,
+    ipc_field: &'a IpcField,
+    field_nodes: VecDeque<Node<'b>>,
+    buffers: VecDeque<IpcBuffer<'b>>,
+    variadic_buffer_counts: VecDeque<usize>,
+    body: BodyRange,
+}
+
+/// The bytes of the body of a record batch that start at `start`, which reads as the whole
+/// body: seeking to an offset of the body seeks to its byte in `bytes`.
+struct BodyRange {
+    start: u64,
+    bytes: Cursor<Vec<u8>>,
+}
+
+impl BodyRange {
+    /// Reads the bytes of the body that `buffers` span.
+    fn read<R: Read + Seek>(
+        reader: &mut R,
+        block_offset: u64,
+        buffers: &VecDeque<IpcBuffer>,
+    ) -> PolarsResult<Self> {
+        let mut start = u64::MAX;
+        let mut end = 0;
+        for buffer in buffers {
+            let offset = u64::try_from(buffer.offset())
+                .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+            let buffer_end = u64::try_from(buffer.length())
+                .ok()
+                .and_then(|length| offset.checked_add(length))
+                .ok_or_else(|| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+            start = start.min(offset);
+            end = end.max(buffer_end);
+        }
+        let start = start.min(end);
+
+        let mut bytes = vec![];
+        bytes.try_reserve((end - start) as usize)?;
+        reader.seek(SeekFrom::Start(block_offset + start))?;
+        reader.by_ref().take(end - start).read_to_end(&mut bytes)?;
+        Ok(Self {
+            start,
+            bytes: Cursor::new(bytes),
+        })
+    }
+}
+
+impl Read for BodyRange {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.bytes.read(buf)
+    }
+}
+
+impl Seek for BodyRange {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => {
+                SeekFrom::Start(offset.checked_sub(self.start).ok_or_else(|| {
+                    std::io::Error::other("IPC: seek before the bytes of the column")
+                })?)
+            },
+            pos => pos,
+        };
+        Ok(self.start + self.bytes.seek(pos)?)
+    }
+}
+
+/// Decodes the selected columns of a record batch according to the [`Parallelism`] of `options`.
+///
+/// The bytes of the body that every selected column spans are read into memory first, such
+/// that every column can be decoded from its own cursor, and the bytes of the columns that
+/// are not selected are never read. The errors of the columns that are replaced by nulls are
+/// appended to `column_errors` in schema order.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn read_columns_parallel<R: Read + Seek>(
+    fields: &ArrowSchema,
+    ipc_schema: &IpcSchema,
+    projection: Option<&[usize]>,
+    mut field_nodes: VecDeque<Node>,
+    mut buffers: VecDeque<IpcBuffer>,
+    mut variadic_buffer_counts: VecDeque<usize>,
+    reader: &mut R,
+    dictionaries: &Dictionaries,
+    block_offset: u64,
+    compression: Option<BodyCompressionRef>,
+    limit: Option<usize>,
+    version: MetadataVersion,
+    options: &ReadOptions,
+    column_errors: &mut Vec<ColumnError>,
+) -> PolarsResult<Vec<Box<dyn Array>>> {
+    // split the nodes and buffers of the batch over its columns: skipping a column tells how
+    // many of each it occupies
+    let last = projection.map_or(fields.len(), |p| p.last().map_or(0, |last| last + 1));
+    let (mut nodes_source, mut buffers_source, mut variadic_source) = (
+        field_nodes.clone(),
+        buffers.clone(),
+        variadic_buffer_counts.clone(),
+    );
+    let mut tasks = vec![];
+    for (i, (field, ipc_field)) in fields
+        .iter_values()
+        .zip(ipc_schema.fields.iter())
+        .enumerate()
+        .take(last)
+    {
+        skip(
+            &mut field_nodes,
+            &field.dtype,
+            &mut buffers,
+            &mut variadic_buffer_counts,
+            version,
+        )?;
+        let column_nodes = nodes_source.drain(..nodes_source.len() - field_nodes.len());
+        let column_buffers = buffers_source.drain(..buffers_source.len() - buffers.len());
+        let column_variadic_buffer_counts =
+            variadic_source.drain(..variadic_source.len() - variadic_buffer_counts.len());
+        if projection.is_none_or(|p| p.binary_search(&i).is_ok()) {
+            let buffers = column_buffers.collect();
+            tasks.push(ColumnTask {
+                field,
+                ipc_field,
+                field_nodes: column_nodes.collect(),
+                body: BodyRange::read(reader, block_offset, &buffers)?,
+                buffers,
+                variadic_buffer_counts: column_variadic_buffer_counts.collect(),
+            });
+        }
+    }
+
+    let decode = |task: ColumnTask| {
+        let ColumnTask {
+            field,
+            ipc_field,
+            mut field_nodes,
+            mut buffers,
+            mut variadic_buffer_counts,
+            mut body,
+        } = task;
+        options.check_cancelled()?;
+        let mut column_errors = vec![];
+        read_column(
+            &mut field_nodes,
+            &mut variadic_buffer_counts,
+            field,
+            ipc_field,
+            &mut buffers,
+            &mut body,
+            dictionaries,
+            0,
+            ipc_schema.is_little_endian,
+            compression,
+            limit,
+            version,
+            &mut vec![],
+            options,
+            &mut column_errors,
+        )
+        .map(|array| (array, column_errors))
+    };
+
+    let columns: PolarsResult<Vec<_>> = map_tasks(options.parallelism, tasks, decode)
+        .into_iter()
+        .collect();
+    Ok(columns?
+        .into_iter()
+        .map(|(array, errors)| {
+            column_errors.extend(errors);
+            array
+        })
+        .collect())
+}
+
+/// Maps `tasks` with `f` according to `parallelism`, returning the results in the order of
+/// `tasks`.
+pub(super) fn map_tasks<T: Send, U: Send>(
+    parallelism: Parallelism,
+    tasks: Vec<T>,
+    f: impl Fn(T) -> U + Send + Sync,
+) -> Vec<U> {
+    match parallelism {
+        Parallelism::Serial => tasks.into_iter().map(f).collect(),
+        Parallelism::Threads(n_threads) => {
+            let chunk_size = tasks.len().div_ceil(n_threads.max(1)).max(1);
+            let mut slots = tasks.iter().map(|_| None).collect::<Vec<_>>();
+            let mut tasks = tasks.into_iter();
+            let f = &f;
+            let jobs = slots
+                .chunks_mut(chunk_size)
+                .map(|slots| {
+                    let tasks = tasks.by_ref().take(slots.len()).collect::<Vec<_>>();
+                    Box::new(move || {
+                        for (task, slot) in tasks.into_iter().zip(slots) {
+                            *slot = Some(f(task));
+                        }
+                    }) as ScopedJob
+                })
+                .collect();
+            THREAD_POOL.run(jobs);
+            slots.into_iter().map(|slot| slot.unwrap()).collect()
+        },
+        #[cfg(feature = "io_ipc_rayon")]
+        Parallelism::Rayon => tasks.into_par_iter().map(f).collect(),
+    }
+}
+
+type ScopedJob<'a> = Box<dyn FnOnce() + Send + 'a>;
+type Job = ScopedJob<'static>;
+
+/// The threads of [`Parallelism::Threads`], which are shared by all reads. The pool grows to
+/// the largest number of threads that a read asks for.
+static THREAD_POOL: ThreadPool = ThreadPool {
+    state: Mutex::new(PoolState {
+        jobs: VecDeque::new(),
+        n_threads: 0,
+    }),
+    changed: Condvar::new(),
+};
+
+struct ThreadPool {
+    state: Mutex<PoolState>,
+    // notified when a job is queued or finished
+    changed: Condvar,
+}
+
+struct PoolState {
+    jobs: VecDeque<Job>,
+    n_threads: usize,
+}
+
+impl ThreadPool {
+    /// Runs `jobs` on their own threads and returns once all of them finished, resuming the
+    /// panic of a job that panicked.
+    ///
+    /// The calling thread runs queued jobs while it waits, such that jobs that run jobs
+    /// themselves, e.g. the record batches of a prefetch that decode their columns in
+    /// parallel, do not wait on each other.
+    fn run(&'static self, jobs: Vec<ScopedJob>) {
+        let remaining = Arc::new(AtomicUsize::new(jobs.len()));
+        let panic = Arc::new(Mutex::new(None::<Box<dyn Any + Send>>));
+
+        let mut state = self.state.lock().unwrap();
+        while state.n_threads < jobs.len() {
+            std::thread::spawn(|| self.work());
+            state.n_threads += 1;
+        }
+        for job in jobs {
+            let remaining = remaining.clone();
+            let panic = panic.clone();
+            let job = Box::new(move || {
+                if let Err(payload) = catch_unwind(AssertUnwindSafe(job)) {
+                    *panic.lock().unwrap() = Some(payload);
+                }
+                remaining.fetch_sub(1, Ordering::AcqRel);
+                // take the lock such that a waiting thread can not miss the notification
+                drop(self.state.lock().unwrap());
+                self.changed.notify_all();
+            }) as ScopedJob;
+            // SAFETY: this function does not return before every job finished, so the borrows
+            // of the jobs outlive them.
+            let job = unsafe { std::mem::transmute::<ScopedJob, Job>(job) };
+            state.jobs.push_back(job);
+        }
+        self.changed.notify_all();
+
+        while remaining.load(Ordering::Acquire) > 0 {
+            state = match state.jobs.pop_front() {
+                Some(job) => {
+                    drop(state);
+                    job();
+                    self.state.lock().unwrap()
+                },
+                None => self.changed.wait(state).unwrap(),
+            };
+        }
+        drop(state);
+
+        if let Some(payload) = panic.lock().unwrap().take() {
+            resume_unwind(payload);
+        }
+    }
+
+    fn work(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            state = match state.jobs.pop_front() {
+                Some(job) => {
+                    drop(state);
+                    job();
+                    self.state.lock().unwrap()
+                },
+                None => self.changed.wait(state).unwrap(),
+            };
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+use polars_utils::aliases::PlHashSet;
+
+use super::BufferAllocator;
+use crate::array::{Array, PrimitiveArray};
+use crate::datatypes::PhysicalType;
+use crate::with_match_primitive_type_full;
+
+/// A pool of buffers that are reused to decode the record batches of an IPC file or stream.
+///
+/// Decode with the [`BufferAllocator`] of [`BufferPool::allocator`] and return every batch
+/// that is no longer used to the pool with [`BufferPool::recycle`]: the values of its
+/// primitive columns are then decoded into the same allocations by the next batches. A batch
+/// that does not fit in the pooled buffers falls back to fresh allocations.
+#[derive(Debug, Clone, Default)]
+pub struct BufferPool {
+    state: Arc<Mutex<PoolState>>,
+    max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    buffers: Vec<Vec<u8>>,
+    // the sum of the capacities of `buffers`
+    bytes: usize,
+    // the addresses of the buffers that were handed out and not returned yet
+    handed_out: PlHashSet<usize>,
+    // the number of buffers that were allocated because no buffer of the pool fit
+    allocations: usize,
+}
+
+impl BufferPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`BufferPool`] that holds buffers of at most `max_bytes` bytes of capacity
+    /// in total. The buffers that are returned to a full pool are dropped.
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self {
+            max_bytes: Some(max_bytes),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a [`BufferAllocator`] that takes its allocations from this pool.
+    pub fn allocator(&self) -> BufferAllocator {
+        let state = self.state.clone();
+        BufferAllocator::new(move |size| {
+            let mut state = state.lock().unwrap();
+            // take the smallest buffer that fits, without wasting more than half of it
+            let position = state
+                .buffers
+                .iter()
+                .enumerate()
+                .filter(|(_, buffer)| (size..=size.saturating_mul(2)).contains(&buffer.capacity()))
+                .min_by_key(|(_, buffer)| buffer.capacity())
+                .map(|(i, _)| i);
+            let buffer = match position {
+                Some(i) => {
+                    let buffer = state.buffers.swap_remove(i);
+                    state.bytes -= buffer.capacity();
+                    buffer
+                },
+                None => {
+                    state.allocations += 1;
+                    Vec::with_capacity(size)
+                },
+            };
+            // empty buffers own no allocation to return
+            if buffer.capacity() > 0 {
+                state.handed_out.insert(buffer.as_ptr() as usize);
+            }
+            buffer
+        })
+    }
+
+    /// Returns the buffers of the primitive columns of `arrays` to this pool.
+    ///
+    /// Only the buffers that the [`BufferPool::allocator`] of this pool handed out are pooled;
+    /// buffers that are still shared, that were allocated otherwise, or that do not fit in the
+    /// `max_bytes` of [`BufferPool::with_max_bytes`], are dropped.
+    pub fn recycle<I: IntoIterator<Item = Box<dyn Array>>>(&self, arrays: I) {
+        let mut state = self.state.lock().unwrap();
+        for mut array in arrays {
+            let PhysicalType::Primitive(primitive) = array.dtype().to_physical_type() else {
+                continue;
+            };
+            let dtype = array.dtype().clone();
+            with_match_primitive_type_full!(primitive, |$T| {
+                let array = array
+                    .as_any_mut()
+                    .downcast_mut::<PrimitiveArray<$T>>()
+                    .unwrap();
+                let (_, values, _) =
+                    std::mem::replace(array, PrimitiveArray::new_empty(dtype)).into_inner();
+                let storage = values.into_storage().try_transmute::<u8>();
+                if let Ok(Ok(buffer)) = storage.map(|storage| storage.try_into_vec()) {
+                    let fits = self
+                        .max_bytes
+                        .is_none_or(|max_bytes| state.bytes + buffer.capacity() <= max_bytes);
+                    if state.handed_out.remove(&(buffer.as_ptr() as usize)) && fits {
+                        state.bytes += buffer.capacity();
+                        state.buffers.push(buffer);
+                    }
+                }
+            })
+        }
+    }
+
+    /// Returns the number of buffers in this pool.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().buffers.len()
+    }
+
+    /// Returns the number of buffers that the [`BufferPool::allocator`] of this pool allocated
+    /// because no buffer of the pool fit, e.g. to measure how much of a read it serves.
+    pub fn allocations(&self) -> usize {
+        self.state.lock().unwrap().allocations
+    }
+
+    /// Returns whether this pool has no buffers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
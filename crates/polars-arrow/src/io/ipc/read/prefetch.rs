@@ -0,0 +1,122 @@
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use arrow_format::ipc::Block;
+use polars_error::{PolarsResult, polars_err};
+
+use super::common::{
+    ReadDiagnostics, ReadOptions, batch_compression, read_record_batch_with_diagnostics,
+};
+use super::file::{get_message_from_block, get_record_batch};
+use super::parallel::{Parallelism, map_tasks};
+use super::{Dictionaries, FileMetadata, OutOfSpecKind};
+use crate::array::Array;
+use crate::io::ipc::write::Compression;
+use crate::record_batch::RecordBatchT;
+
+/// How many record batches a [`FileReader`] reads ahead and decodes in parallel under
+/// [`ReadOptions::block_prefetch`]. The record batches are read one after the other, and then
+/// decoded, and decompressed, as a task each, according to [`BlockPrefetch::parallelism`].
+///
+/// [`FileReader`]: super::FileReader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockPrefetch {
+    /// The largest number of record batches that are read and decoded at once, including the
+    /// record batch that is read.
+    pub max_blocks: usize,
+    /// The largest estimated decoded size in bytes of the record batches that are read ahead
+    /// and decoded at once, see [`FileReader::estimate_batch_size`]. The record batch that is
+    /// read is always decoded, however large.
+    ///
+    /// [`FileReader::estimate_batch_size`]: super::FileReader::estimate_batch_size
+    pub max_bytes: Option<usize>,
+    /// How the record batches that are read at once are decoded. The columns of every record
+    /// batch are decoded according to [`ReadOptions::parallelism`].
+    pub parallelism: Parallelism,
+}
+
+/// A record batch that was decoded ahead of time, with the codec that its message declares and
+/// its diagnostics.
+pub(super) type Prefetched = (
+    PolarsResult<RecordBatchT<Box<dyn Array>>>,
+    Option<Compression>,
+    ReadDiagnostics,
+);
+
+/// Reads the message and the body of `block` of `reader` into memory, returning them and the
+/// block of the message in them.
+pub(super) fn read_block<R: Read + Seek>(
+    reader: &mut R,
+    block: &Block,
+) -> PolarsResult<(Vec<u8>, Block)> {
+    let offset: u64 = block
+        .offset
+        .try_into()
+        .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+    let length: u64 = (block.meta_data_length as i64)
+        .checked_add(block.body_length)
+        .and_then(|length| length.try_into().ok())
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut data = vec![];
+    data.try_reserve(length as usize)?;
+    reader.take(length).read_to_end(&mut data)?;
+    Ok((
+        data,
+        Block {
+            offset: 0,
+            ..*block
+        },
+    ))
+}
+
+/// Decodes the record batch of `block` of `data`, as read by [`read_block`].
+fn decode_block(
+    data: &[u8],
+    block: &Block,
+    metadata: &FileMetadata,
+    dictionaries: &Dictionaries,
+    projection: Option<&[usize]>,
+    options: &ReadOptions,
+) -> Prefetched {
+    let mut message_scratch = vec![];
+    let mut diagnostics = ReadDiagnostics::default();
+    let chunk = (|| {
+        let mut reader = Cursor::new(data);
+        let message = get_message_from_block(&mut reader, block, &mut message_scratch)?;
+        read_record_batch_with_diagnostics(
+            get_record_batch(message)?,
+            &metadata.schema,
+            &metadata.ipc_schema,
+            projection,
+            None,
+            dictionaries,
+            message
+                .version()
+                .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferVersion(err)))?,
+            &mut reader,
+            block.meta_data_length as u64,
+            data.len() as u64,
+            &mut vec![],
+            &mut vec![],
+            options,
+            &mut diagnostics,
+        )
+    })();
+    (chunk, batch_compression(&message_scratch), diagnostics)
+}
+
+/// Decodes the record batches of `blocks`, as read by [`read_block`], according to
+/// `parallelism`, returning them in the order of `blocks`.
+pub(super) fn decode_blocks(
+    blocks: &[(Vec<u8>, Block)],
+    metadata: &FileMetadata,
+    dictionaries: &Dictionaries,
+    projection: Option<&[usize]>,
+    options: &ReadOptions,
+    parallelism: Parallelism,
+) -> Vec<Prefetched> {
+    map_tasks(parallelism, blocks.iter().collect(), |(data, block)| {
+        decode_block(data, block, metadata, dictionaries, projection, options)
+    })
+}
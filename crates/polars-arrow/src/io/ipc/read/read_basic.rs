@@ -1,19 +1,22 @@
 use std::collections::VecDeque;
 use std::io::{Read, Seek, SeekFrom};
 
-use polars_error::{PolarsResult, polars_bail, polars_err};
+use polars_error::{PolarsResult, polars_bail, polars_ensure, polars_err};
 
 use super::super::compression;
 use super::super::endianness::is_native_little_endian;
-use super::{Compression, IpcBuffer, Node, OutOfSpecKind};
+use super::budget::check_column_deadline;
+use super::{BufferAllocator, Compression, IpcBuffer, Node, OutOfSpecKind, ReadOptions};
 use crate::bitmap::Bitmap;
 use crate::buffer::Buffer;
+use crate::offset::Offset;
+use crate::storage::SharedStorage;
 use crate::types::NativeType;
 
 fn read_swapped<T: NativeType, R: Read + Seek>(
     reader: &mut R,
     length: usize,
-    buffer: &mut Vec<T>,
+    buffer: &mut [T],
     is_little_endian: bool,
 ) -> PolarsResult<()> {
     // Slow case where we must reverse bits.
@@ -26,7 +29,6 @@ fn read_swapped<T: NativeType, R: Read + Seek>(
     if !is_little_endian {
         // machine is little endian, file is big endian
         buffer
-            .as_mut_slice()
             .iter_mut()
             .zip(chunks)
             .try_for_each(|(slot, chunk)| {
@@ -46,13 +48,45 @@ fn read_swapped<T: NativeType, R: Read + Seek>(
     Ok(())
 }
 
+/// Returns an empty [`Vec`] with at least `capacity` bytes, allocated by `allocator` if given.
+fn allocate_bytes(capacity: usize, allocator: Option<&BufferAllocator>) -> Vec<u8> {
+    match allocator {
+        Some(allocator) => {
+            let mut buffer = allocator.allocate(capacity);
+            buffer.clear();
+            buffer.reserve(capacity);
+            buffer
+        },
+        None => Vec::with_capacity(capacity),
+    }
+}
+
+/// Returns zeroed storage for `length` values of `T`, allocated by `allocator` if given.
+fn allocate_zeroed<T: NativeType>(
+    length: usize,
+    allocator: Option<&BufferAllocator>,
+) -> SharedStorage<T> {
+    if let Some(allocator) = allocator {
+        let mut bytes = allocate_bytes(length * size_of::<T>(), Some(allocator));
+        bytes.resize(length * size_of::<T>(), 0);
+        // the allocation can only be used if it is aligned for `T`
+        if let Ok(storage) = SharedStorage::from_vec(bytes).try_transmute::<T>() {
+            return storage;
+        }
+    }
+    // It is undefined behavior to call read_exact on un-initialized, https://doc.rust-lang.org/std/io/trait.Read.html#tymethod.read
+    // see also https://github.com/MaikKlein/ash/issues/354#issue-781730580
+    SharedStorage::from_vec(vec![T::default(); length])
+}
+
 fn read_uncompressed_bytes<R: Read + Seek>(
     reader: &mut R,
     buffer_length: usize,
     is_little_endian: bool,
+    allocator: Option<&BufferAllocator>,
 ) -> PolarsResult<Vec<u8>> {
     if is_native_little_endian() == is_little_endian {
-        let mut buffer = Vec::with_capacity(buffer_length);
+        let mut buffer = allocate_bytes(buffer_length, allocator);
         let _ = reader
             .take(buffer_length as u64)
             .read_to_end(&mut buffer)
@@ -68,7 +102,8 @@ fn read_uncompressed_buffer<T: NativeType, R: Read + Seek>(
     buffer_length: usize,
     length: usize,
     is_little_endian: bool,
-) -> PolarsResult<Vec<T>> {
+    allocator: Option<&BufferAllocator>,
+) -> PolarsResult<SharedStorage<T>> {
     let required_number_of_bytes = length.saturating_mul(size_of::<T>());
     if required_number_of_bytes > buffer_length {
         polars_bail!(
@@ -81,18 +116,17 @@ fn read_uncompressed_buffer<T: NativeType, R: Read + Seek>(
         );
     }
 
-    // it is undefined behavior to call read_exact on un-initialized, https://doc.rust-lang.org/std/io/trait.Read.html#tymethod.read
-    // see also https://github.com/MaikKlein/ash/issues/354#issue-781730580
-    let mut buffer = vec![T::default(); length];
+    let mut storage = allocate_zeroed::<T>(length, allocator);
+    let buffer = storage.try_as_mut_slice().unwrap();
 
     if is_native_little_endian() == is_little_endian {
         // fast case where we can just copy the contents
-        let slice = bytemuck::cast_slice_mut(&mut buffer);
+        let slice = bytemuck::cast_slice_mut(buffer);
         reader.read_exact(slice)?;
     } else {
-        read_swapped(reader, length, &mut buffer, is_little_endian)?;
+        read_swapped(reader, length, buffer, is_little_endian)?;
     }
-    Ok(buffer)
+    Ok(storage)
 }
 
 fn read_compressed_buffer<T: NativeType, R: Read + Seek>(
@@ -102,9 +136,10 @@ fn read_compressed_buffer<T: NativeType, R: Read + Seek>(
     is_little_endian: bool,
     compression: Compression,
     scratch: &mut Vec<u8>,
-) -> PolarsResult<Vec<T>> {
+    options: &ReadOptions,
+) -> PolarsResult<SharedStorage<T>> {
     if output_length == Some(0) {
-        return Ok(vec![]);
+        return Ok(SharedStorage::from_vec(vec![]));
     }
 
     if is_little_endian != is_native_little_endian() {
@@ -121,14 +156,60 @@ fn read_compressed_buffer<T: NativeType, R: Read + Seek>(
         .take(buffer_length as u64)
         .read_to_end(scratch)?;
 
+    let (uncompressed_length, data) = split_compressed(scratch)?;
     let length = output_length
-        .unwrap_or_else(|| i64::from_le_bytes(scratch[..8].try_into().unwrap()) as usize);
+        .or(uncompressed_length.map(|length| length / size_of::<T>()))
+        .unwrap_or(data.len() / size_of::<T>());
+    options.check_decompressed_size(length.saturating_mul(size_of::<T>()))?;
+
+    let mut storage = allocate_zeroed::<T>(length, options.allocator.as_ref());
+    let out_slice = bytemuck::cast_slice_mut(storage.try_as_mut_slice().unwrap());
+    decompress(uncompressed_length, data, out_slice, compression, options)?;
+    Ok(storage)
+}
 
-    // It is undefined behavior to call read_exact on un-initialized, https://doc.rust-lang.org/std/io/trait.Read.html#tymethod.read
-    // see also https://github.com/MaikKlein/ash/issues/354#issue-781730580
-    let mut buffer = vec![T::default(); length];
+/// Splits a compressed buffer into its uncompressed length and its data.
+///
+/// The length is `None` when the data is stored uncompressed: writers may opt out of
+/// compression for individual buffers of a batch (e.g. when compressing them does not save
+/// space) by declaring a length of `-1`.
+fn split_compressed(buffer: &[u8]) -> PolarsResult<(Option<usize>, &[u8])> {
+    polars_ensure!(
+        buffer.len() >= 8,
+        ComputeError: "IPC: a compressed buffer of {} bytes is missing its uncompressed length", buffer.len()
+    );
+    let (length, data) = buffer.split_at(8);
+    let length = i64::from_le_bytes(length.try_into().unwrap());
+    if length == -1 {
+        return Ok((None, data));
+    }
+    let length = length
+        .try_into()
+        .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+    Ok((Some(length), data))
+}
 
-    let out_slice = bytemuck::cast_slice_mut(&mut buffer);
+/// Decompresses `data` into `out_slice`, or copies it when it is stored uncompressed.
+fn decompress(
+    uncompressed_length: Option<usize>,
+    data: &[u8],
+    out_slice: &mut [u8],
+    compression: Compression,
+    options: &ReadOptions,
+) -> PolarsResult<()> {
+    if uncompressed_length.is_none() {
+        polars_ensure!(
+            data.len() >= out_slice.len(),
+            oos = OutOfSpecKind::InvalidBuffer {
+                length: out_slice.len(),
+                type_name: "u8",
+                required_number_of_bytes: out_slice.len(),
+                buffer_length: data.len(),
+            }
+        );
+        out_slice.copy_from_slice(&data[..out_slice.len()]);
+        return Ok(());
+    }
 
     let compression = compression
         .codec()
@@ -136,13 +217,12 @@ fn read_compressed_buffer<T: NativeType, R: Read + Seek>(
 
     match compression {
         arrow_format::ipc::CompressionType::Lz4Frame => {
-            compression::decompress_lz4(&scratch[8..], out_slice)?;
+            compression::decompress_lz4(data, out_slice)
         },
         arrow_format::ipc::CompressionType::Zstd => {
-            compression::decompress_zstd(&scratch[8..], out_slice)?;
+            compression::decompress_zstd(data, out_slice, options.zstd_max_window_size())
         },
     }
-    Ok(buffer)
 }
 
 fn read_compressed_bytes<R: Read + Seek>(
@@ -151,7 +231,8 @@ fn read_compressed_bytes<R: Read + Seek>(
     is_little_endian: bool,
     compression: Compression,
     scratch: &mut Vec<u8>,
-) -> PolarsResult<Vec<u8>> {
+    options: &ReadOptions,
+) -> PolarsResult<SharedStorage<u8>> {
     read_compressed_buffer::<u8, _>(
         reader,
         buffer_length,
@@ -159,6 +240,7 @@ fn read_compressed_bytes<R: Read + Seek>(
         is_little_endian,
         compression,
         scratch,
+        options,
     )
 }
 
@@ -169,7 +251,9 @@ pub fn read_bytes<R: Read + Seek>(
     is_little_endian: bool,
     compression: Option<Compression>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<Buffer<u8>> {
+    check_column_deadline()?;
     let buf = buf
         .pop_front()
         .ok_or_else(|| polars_err!(oos = OutOfSpecKind::ExpectedBuffer))?;
@@ -187,19 +271,21 @@ pub fn read_bytes<R: Read + Seek>(
     reader.seek(SeekFrom::Start(block_offset + offset))?;
 
     if let Some(compression) = compression {
-        Ok(read_compressed_bytes(
+        Ok(Buffer::from_storage(read_compressed_bytes(
             reader,
             buffer_length,
             is_little_endian,
             compression,
             scratch,
-        )?
-        .into())
+            options,
+        )?))
     } else {
-        Ok(read_uncompressed_bytes(reader, buffer_length, is_little_endian)?.into())
+        let allocator = options.allocator.as_ref();
+        Ok(read_uncompressed_bytes(reader, buffer_length, is_little_endian, allocator)?.into())
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn read_buffer<T: NativeType, R: Read + Seek>(
     buf: &mut VecDeque<IpcBuffer>,
     length: usize, // in slots
@@ -208,7 +294,9 @@ pub fn read_buffer<T: NativeType, R: Read + Seek>(
     is_little_endian: bool,
     compression: Option<Compression>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<Buffer<T>> {
+    check_column_deadline()?;
     let buf = buf
         .pop_front()
         .ok_or_else(|| polars_err!(oos = OutOfSpecKind::ExpectedBuffer))?;
@@ -225,27 +313,119 @@ pub fn read_buffer<T: NativeType, R: Read + Seek>(
 
     reader.seek(SeekFrom::Start(block_offset + offset))?;
 
-    if let Some(compression) = compression {
-        Ok(read_compressed_buffer(
+    let storage = if let Some(compression) = compression {
+        read_compressed_buffer(
             reader,
             buffer_length,
             Some(length),
             is_little_endian,
             compression,
             scratch,
+            options,
         )?
-        .into())
     } else {
-        Ok(read_uncompressed_buffer(reader, buffer_length, length, is_little_endian)?.into())
+        let allocator = options.allocator.as_ref();
+        read_uncompressed_buffer(reader, buffer_length, length, is_little_endian, allocator)?
+    };
+    align_buffer(Buffer::from_storage(storage), options.align)
+}
+
+/// Returns `buffer`, or a copy of it when its values do not start at a multiple of `align` bytes.
+fn align_buffer<T: NativeType>(buffer: Buffer<T>, align: usize) -> PolarsResult<Buffer<T>> {
+    if align == 0 {
+        return Ok(buffer);
+    }
+    polars_ensure!(
+        align.is_power_of_two(),
+        ComputeError: "IPC: the alignment of buffers must be a power of two, got {align}"
+    );
+    if (buffer.as_ptr() as usize).is_multiple_of(align) {
+        return Ok(buffer);
+    }
+
+    // over-allocate and start the values at the first aligned slot of the allocation
+    let padding = align.div_ceil(size_of::<T>());
+    let mut values = Vec::<T>::with_capacity(padding + buffer.len());
+    let offset = (0..=padding)
+        .find(|&offset| (values.as_ptr().wrapping_add(offset) as usize).is_multiple_of(align))
+        .ok_or_else(|| {
+            polars_err!(ComputeError:
+                "IPC: buffers of {} cannot be aligned to {align} bytes", std::any::type_name::<T>()
+            )
+        })?;
+    values.resize(offset, T::default());
+    values.extend_from_slice(&buffer);
+    Ok(Buffer::from(values).sliced(offset, buffer.len()))
+}
+
+/// Reads the `1 + length` offsets of a variable-sized array. Their width is given by `O`, i.e.
+/// by the declared type (`i32` for e.g. `List`, `i64` for e.g. `LargeList`).
+///
+/// Older versions of the IPC format sometimes do not report an offset: an empty offsets buffer
+/// is read as a single zero offset. Any other error is returned instead of silently replacing
+/// the offsets, e.g. when the buffer is too short for the width of the offsets.
+#[allow(clippy::too_many_arguments)]
+pub fn read_offsets<O: Offset, R: Read + Seek>(
+    buf: &mut VecDeque<IpcBuffer>,
+    length: usize,
+    reader: &mut R,
+    block_offset: u64,
+    is_little_endian: bool,
+    compression: Option<Compression>,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<Buffer<O>> {
+    if buf.front().is_some_and(|buffer| buffer.length() == 0) {
+        buf.pop_front();
+        return Ok(Buffer::from(vec![O::default()]));
+    }
+    read_buffer(
+        buf,
+        1 + length,
+        reader,
+        block_offset,
+        is_little_endian,
+        compression,
+        scratch,
+        options,
+    )
+}
+
+/// Errors when `offsets` are not monotonically increasing, or when their last offset is not
+/// `values_length`. See [`ReadOptions::validate_offsets`].
+pub(super) fn validate_offsets<O: Offset>(
+    name: &str,
+    offsets: &[O],
+    values_length: Option<usize>,
+) -> PolarsResult<()> {
+    polars_ensure!(
+        offsets.first().is_none_or(|first| *first >= O::zero()),
+        ComputeError: "IPC: offset 0 of column \"{name}\" ({:?}) is negative", offsets[0]
+    );
+    if let Some(index) = offsets.windows(2).position(|pair| pair[0] > pair[1]) {
+        polars_bail!(
+            ComputeError: "IPC: offset {} of column \"{name}\" ({:?}) is smaller than the offset before it ({:?})",
+            index + 1, offsets[index + 1], offsets[index]
+        );
     }
+    if let (Some(last), Some(values_length)) = (offsets.last(), values_length) {
+        polars_ensure!(
+            last.to_usize() == values_length,
+            ComputeError: "IPC: the last offset of column \"{name}\" ({}) does not equal the length of its values ({values_length})",
+            last.to_usize()
+        );
+    }
+    Ok(())
 }
 
 fn read_uncompressed_bitmap<R: Read + Seek>(
     length: usize,
     bytes: usize,
     reader: &mut R,
+    allocator: Option<&BufferAllocator>,
+    pad: bool,
 ) -> PolarsResult<Vec<u8>> {
-    if length > bytes * 8 {
+    if length > bytes * 8 && !pad {
         polars_bail!(
             oos = OutOfSpecKind::InvalidBitmap {
                 length,
@@ -254,12 +434,21 @@ fn read_uncompressed_bitmap<R: Read + Seek>(
         )
     }
 
-    let mut buffer = vec![];
-    buffer.try_reserve(bytes)?;
+    let mut buffer = match allocator {
+        Some(allocator) => allocate_bytes(bytes, Some(allocator)),
+        None => {
+            let mut buffer = vec![];
+            buffer.try_reserve(bytes)?;
+            buffer
+        },
+    };
     reader
         .by_ref()
         .take(bytes as u64)
         .read_to_end(&mut buffer)?;
+    if pad && buffer.len() < length.div_ceil(8) {
+        buffer.resize(length.div_ceil(8), u8::MAX);
+    }
 
     Ok(buffer)
 }
@@ -270,31 +459,64 @@ fn read_compressed_bitmap<R: Read + Seek>(
     compression: Compression,
     reader: &mut R,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    pad: bool,
 ) -> PolarsResult<Vec<u8>> {
-    #[expect(clippy::slow_vector_initialization)] // Avoid alloc_zeroed, leads to syscall.
-    let mut buffer = Vec::new();
+    options.check_decompressed_size(length.div_ceil(8))?;
+    let mut buffer = allocate_bytes(length.div_ceil(8), options.allocator.as_ref());
     buffer.resize(length.div_ceil(8), 0);
 
     scratch.clear();
     scratch.try_reserve(bytes)?;
     reader.by_ref().take(bytes as u64).read_to_end(scratch)?;
 
-    let compression = compression
-        .codec()
-        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferCompression(err)))?;
-
-    match compression {
-        arrow_format::ipc::CompressionType::Lz4Frame => {
-            compression::decompress_lz4(&scratch[8..], &mut buffer)?;
-        },
-        arrow_format::ipc::CompressionType::Zstd => {
-            compression::decompress_zstd(&scratch[8..], &mut buffer)?;
-        },
-    }
+    let (uncompressed_length, data) = split_compressed(scratch)?;
+    let available = uncompressed_length.unwrap_or(data.len());
+    let decoded = if pad {
+        available.min(buffer.len())
+    } else {
+        buffer.len()
+    };
+    decompress(
+        uncompressed_length,
+        data,
+        &mut buffer[..decoded],
+        compression,
+        options,
+    )?;
+    buffer[decoded..].fill(u8::MAX);
     Ok(buffer)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn read_bitmap<R: Read + Seek>(
+    buf: &mut VecDeque<IpcBuffer>,
+    length: usize,
+    reader: &mut R,
+    block_offset: u64,
+    is_little_endian: bool,
+    compression: Option<Compression>,
+    scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+) -> PolarsResult<Bitmap> {
+    read_bitmap_padded(
+        buf,
+        length,
+        reader,
+        block_offset,
+        is_little_endian,
+        compression,
+        scratch,
+        options,
+        false,
+    )
+}
+
+/// Reads a bitmap of `length` bits, whose missing trailing bits are set if `pad` is set and
+/// its buffer is shorter than `length` bits, e.g. a validity bitmap whose trailing valid bits
+/// were omitted.
+#[allow(clippy::too_many_arguments)]
+fn read_bitmap_padded<R: Read + Seek>(
     buf: &mut VecDeque<IpcBuffer>,
     length: usize,
     reader: &mut R,
@@ -302,7 +524,10 @@ pub fn read_bitmap<R: Read + Seek>(
     _: bool,
     compression: Option<Compression>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    pad: bool,
 ) -> PolarsResult<Bitmap> {
+    check_column_deadline()?;
     let buf = buf
         .pop_front()
         .ok_or_else(|| polars_err!(oos = OutOfSpecKind::ExpectedBuffer))?;
@@ -320,9 +545,9 @@ pub fn read_bitmap<R: Read + Seek>(
     reader.seek(SeekFrom::Start(block_offset + offset))?;
 
     let buffer = if let Some(compression) = compression {
-        read_compressed_bitmap(length, bytes, compression, reader, scratch)
+        read_compressed_bitmap(length, bytes, compression, reader, scratch, options, pad)
     } else {
-        read_uncompressed_bitmap(length, bytes, reader)
+        read_uncompressed_bitmap(length, bytes, reader, options.allocator.as_ref(), pad)
     }?;
 
     Bitmap::try_new(buffer, length)
@@ -338,6 +563,7 @@ pub fn read_validity<R: Read + Seek>(
     compression: Option<Compression>,
     limit: Option<usize>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
 ) -> PolarsResult<Option<Bitmap>> {
     let length: usize = field_node
         .length()
@@ -345,20 +571,27 @@ pub fn read_validity<R: Read + Seek>(
         .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
     let length = limit.map(|limit| limit.min(length)).unwrap_or(length);
 
-    Ok(if field_node.null_count() > 0 {
-        Some(read_bitmap(
-            buffers,
-            length,
-            reader,
-            block_offset,
-            is_little_endian,
-            compression,
-            scratch,
-        )?)
-    } else {
-        let _ = buffers
+    if field_node.null_count() == 0 {
+        let buffer = buffers
             .pop_front()
             .ok_or_else(|| polars_err!(oos = OutOfSpecKind::ExpectedBuffer))?;
-        None
-    })
+        polars_ensure!(
+            !options.skip_validity_when_no_nulls || buffer.length() == 0,
+            ComputeError: "IPC: an array that declares no nulls has a validity buffer, which \
+            `skip_validity_when_no_nulls` does not allow"
+        );
+        return Ok(None);
+    }
+    read_bitmap_padded(
+        buffers,
+        length,
+        reader,
+        block_offset,
+        is_little_endian,
+        compression,
+        scratch,
+        options,
+        !options.strict_validity_length,
+    )
+    .map(Some)
 }
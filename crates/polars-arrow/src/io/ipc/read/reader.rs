@@ -1,12 +1,25 @@
+use std::collections::VecDeque;
 use std::io::{Read, Seek};
+use std::sync::Arc;
 
-use polars_error::PolarsResult;
+use polars_error::{PolarsResult, polars_bail, polars_ensure, polars_err};
+use polars_utils::pl_str::PlSmallStr;
 
 use super::common::*;
-use super::file::{get_message_from_block, get_record_batch};
-use super::{Dictionaries, FileMetadata, read_batch, read_file_dictionaries};
-use crate::array::Array;
+use super::file::{
+    get_message_from_block, get_record_batch, read_batch_with_diagnostics,
+    read_column_dictionaries, read_column_nested_dictionaries, read_file_dictionaries,
+    read_file_dictionaries_with_history, read_nested_dictionaries,
+};
+use super::prefetch::{BlockPrefetch, Prefetched, decode_blocks, read_block};
+use super::statistics::read_batch_statistics;
+use super::{
+    BatchStatistics, Dictionaries, FileMetadata, OutOfSpecKind, read_batch, read_dictionary_by_id,
+};
+use crate::array::{Array, new_empty_array};
 use crate::datatypes::ArrowSchema;
+use crate::io::ipc::IpcSchema;
+use crate::io::ipc::write::Compression;
 use crate::record_batch::RecordBatchT;
 
 /// An iterator of [`RecordBatchT`]s from an Arrow IPC file.
@@ -15,17 +28,38 @@ pub struct FileReader<R: Read + Seek> {
     metadata: FileMetadata,
     // the dictionaries are going to be read
     dictionaries: Option<Dictionaries>,
+    dictionary_history: DictionaryHistory,
     current_block: usize,
     projection: Option<ProjectionInfo>,
     remaining: usize,
+    options: ReadOptions,
     data_scratch: Vec<u8>,
     message_scratch: Vec<u8>,
+    // the output batches that remain of a split record batch
+    pending: VecDeque<RecordBatchT<Box<dyn Array>>>,
+    block_filter: Option<Box<dyn Fn(&BatchStatistics) -> bool + Send + Sync>>,
+    // the number of rows of the record batches that were read or skipped
+    row_count: usize,
+    // the codec of the last record batch that was read
+    compression: Option<Compression>,
+    // the number of rows that precede each record batch, followed by the number of rows of the
+    // file, read once by the first batch of a reversed read with a row index
+    block_rows: Option<Vec<usize>>,
+    // the number of record batches that were decoded
+    batch_count: usize,
+    // the record batches that were decoded ahead of time, by block index, in reading order
+    prefetched: VecDeque<(usize, Prefetched)>,
+    // the results of `filter_block` of the blocks that were evaluated before they are read, by
+    // block index, in reading order
+    filtered: VecDeque<(usize, (bool, usize))>,
+    // the diagnostics of the dictionaries and record batches that were read
+    diagnostics: ReadDiagnostics,
 }
 
 impl<R: Read + Seek> FileReader<R> {
     /// Creates a new [`FileReader`]. Use `projection` to only take certain columns.
     /// # Panic
-    /// Panics iff the projection is not in increasing order (e.g. `[1, 0]` nor `[0, 1, 1]` are valid)
+    /// Panics iff the projection selects a column more than once (e.g. `[0, 1, 1]` is not valid)
     pub fn new(
         reader: R,
         metadata: FileMetadata,
@@ -38,17 +72,28 @@ impl<R: Read + Seek> FileReader<R> {
             reader,
             metadata,
             dictionaries: Default::default(),
+            dictionary_history: Default::default(),
             projection,
             remaining: limit.unwrap_or(usize::MAX),
+            options: Default::default(),
             current_block: 0,
             data_scratch: Default::default(),
             message_scratch: Default::default(),
+            pending: Default::default(),
+            block_filter: None,
+            row_count: 0,
+            compression: None,
+            block_rows: None,
+            batch_count: 0,
+            prefetched: Default::default(),
+            filtered: Default::default(),
+            diagnostics: Default::default(),
         }
     }
 
     /// Creates a new [`FileReader`]. Use `projection` to only take certain columns.
     /// # Panic
-    /// Panics iff the projection is not in increasing order (e.g. `[1, 0]` nor `[0, 1, 1]` are valid)
+    /// Panics iff the projection selects a column more than once (e.g. `[0, 1, 1]` is not valid)
     pub fn new_with_projection_info(
         reader: R,
         metadata: FileMetadata,
@@ -59,16 +104,54 @@ impl<R: Read + Seek> FileReader<R> {
             reader,
             metadata,
             dictionaries: Default::default(),
+            dictionary_history: Default::default(),
             projection,
             remaining: limit.unwrap_or(usize::MAX),
+            options: Default::default(),
             current_block: 0,
             data_scratch: Default::default(),
             message_scratch: Default::default(),
+            pending: Default::default(),
+            block_filter: None,
+            row_count: 0,
+            compression: None,
+            block_rows: None,
+            batch_count: 0,
+            prefetched: Default::default(),
+            filtered: Default::default(),
+            diagnostics: Default::default(),
         }
     }
 
+    /// Sets the [`ReadOptions`] of this reader.
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.projection =
+            options_projection(&self.metadata.schema, self.projection.take(), &options);
+        self.options = options;
+        self
+    }
+
+    /// Sets a filter on the [`BatchStatistics`] of the record batches of this reader. Record
+    /// batches for which `filter` returns `false` are skipped without being decoded; record
+    /// batches without statistics are always read.
+    pub fn with_block_filter(
+        mut self,
+        filter: impl Fn(&BatchStatistics) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.block_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Returns the [`ReadOptions`] of this reader.
+    pub fn options(&self) -> &ReadOptions {
+        &self.options
+    }
+
     /// Return the schema of the file
     pub fn schema(&self) -> &ArrowSchema {
+        if let Some(schema) = &self.options.reader_schema {
+            return schema;
+        }
         self.projection
             .as_ref()
             .map(|x| &x.schema)
@@ -80,6 +163,51 @@ impl<R: Read + Seek> FileReader<R> {
         &self.metadata
     }
 
+    /// Returns the [`IpcSchema`] of the file, whose [`IpcField`]s hold the dictionary ids of
+    /// the fields of the file, including of their nested fields. Its fields are the fields of
+    /// the file, regardless of any projection.
+    ///
+    /// [`IpcField`]: crate::io::ipc::IpcField
+    pub fn ipc_schema(&self) -> &IpcSchema {
+        &self.metadata.ipc_schema
+    }
+
+    /// Returns the dictionaries of the file, once the first record batch has been read.
+    pub fn dictionaries(&self) -> Option<&Dictionaries> {
+        self.dictionaries.as_ref()
+    }
+
+    /// Returns the number of values of the dictionary with id `id`, once the first record batch
+    /// has been read, including the values of its deltas. This is an upper bound of the number
+    /// of distinct values of the columns that are encoded with it, and their exact number when
+    /// the values of the dictionary are unique, e.g. to plan joins and group-bys.
+    pub fn dictionary_cardinality(&self, id: i64) -> Option<usize> {
+        self.dictionaries
+            .as_ref()?
+            .get(&id)
+            .map(|values| values.len())
+    }
+
+    /// Returns the codec that the last record batch that was read declares, e.g. to re-encode it
+    /// with the same codec; `None` if it is not compressed or no record batch was read yet.
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
+    /// Returns how the dictionary with id `id` was built from the dictionary batches of the
+    /// file, once the first record batch has been read.
+    pub fn dictionary_history(&self, id: i64) -> &[DictionaryEvent] {
+        self.dictionary_history
+            .get(&id)
+            .map_or(&[], |events| events)
+    }
+
+    /// Takes the [`ReadDiagnostics`] of the dictionaries and record batches read so far, e.g. the
+    /// errors of the columns that were replaced by nulls under [`ColumnErrorPolicy::NullFill`].
+    pub fn take_diagnostics(&mut self) -> ReadDiagnostics {
+        std::mem::take(&mut self.diagnostics)
+    }
+
     /// Consumes this FileReader, returning the underlying reader
     pub fn into_inner(self) -> R {
         self.reader
@@ -87,12 +215,37 @@ impl<R: Read + Seek> FileReader<R> {
 
     pub fn set_current_block(&mut self, idx: usize) {
         self.current_block = idx;
+        self.pending.clear();
+        self.prefetched.clear();
+        self.filtered.clear();
     }
 
     pub fn get_current_block(&self) -> usize {
         self.current_block
     }
 
+    /// Returns the [`ReadOptions`] of this reader to change the options that apply to the record
+    /// batches that are read next, e.g. the offset of [`ReadOptions::row_index`].
+    pub(super) fn options_mut(&mut self) -> &mut ReadOptions {
+        &mut self.options
+    }
+
+    /// Returns the number of rows of the record batches that were read or skipped, and the
+    /// number of record batches that were decoded.
+    pub(super) fn progress(&self) -> (usize, usize) {
+        (self.row_count, self.batch_count)
+    }
+
+    /// Returns the number of rows of the file.
+    pub(super) fn num_rows(&mut self) -> PolarsResult<usize> {
+        self.block_row(self.metadata.blocks.len())
+    }
+
+    /// Returns whether output batches of a split record batch remain to be returned.
+    pub(super) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
     /// Get the inner memory scratches so they can be reused in a new writer.
     /// This can be utilized to save memory allocations for performance reasons.
     pub fn take_projection_info(&mut self) -> Option<ProjectionInfo> {
@@ -116,15 +269,35 @@ impl<R: Read + Seek> FileReader<R> {
 
     fn read_dictionaries(&mut self) -> PolarsResult<()> {
         if self.dictionaries.is_none() {
-            self.dictionaries = Some(read_file_dictionaries(
+            let read = if self.options.skip_dictionary_resolution {
+                read_nested_dictionaries
+            } else {
+                read_file_dictionaries_with_history
+            };
+            self.dictionaries = Some(read(
                 &mut self.reader,
                 &self.metadata,
                 &mut self.data_scratch,
+                &self.options,
+                &mut self.dictionary_history,
+                &mut self.diagnostics,
             )?);
         };
         Ok(())
     }
 
+    /// Reads the dictionary with id `id`, e.g. of a column that is read with
+    /// [`ReadOptions::skip_dictionary_resolution`]. See [`read_dictionary_by_id`].
+    pub fn resolve_dictionary(&mut self, id: i64) -> PolarsResult<Box<dyn Array>> {
+        read_dictionary_by_id(
+            &mut self.reader,
+            &self.metadata,
+            id,
+            &mut self.data_scratch,
+            &self.options,
+        )
+    }
+
     /// Skip over blocks until we have seen at most `offset` rows, returning how many rows we are
     /// still too see.  
     ///
@@ -153,6 +326,214 @@ impl<R: Read + Seek> FileReader<R> {
         Ok(remaining_offset)
     }
 
+    /// Returns an estimate of the decoded size in bytes of the record batch at position `index`,
+    /// restricted to the projected columns. See [`estimate_batch_size`].
+    /// # Panics
+    /// This function panics iff `index >= metadata.blocks.len()`
+    pub fn estimate_batch_size(&mut self, index: usize) -> PolarsResult<usize> {
+        let block = self.metadata.blocks[index];
+        let offset: u64 = block
+            .offset
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+        let length: u64 = block
+            .meta_data_length
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+
+        let message = get_message_from_block(&mut self.reader, &block, &mut self.message_scratch)?;
+        estimate_batch_size(
+            get_record_batch(message)?,
+            &self.metadata.schema,
+            self.projection.as_ref().map(|x| x.columns.as_ref()),
+            message
+                .version()
+                .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferVersion(err)))?,
+            &mut self.reader,
+            offset + length,
+        )
+    }
+
+    /// Returns the compression ratio of every projected column of the record batch at position
+    /// `index`, by name, e.g. to decide which columns to store uncompressed. See
+    /// [`compression_ratios`].
+    /// # Panics
+    /// This function panics iff `index >= metadata.blocks.len()`
+    pub fn compression_ratios(&mut self, index: usize) -> PolarsResult<Vec<(PlSmallStr, f64)>> {
+        let block = self.metadata.blocks[index];
+        let offset: u64 = block
+            .offset
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+        let length: u64 = block
+            .meta_data_length
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+
+        let message = get_message_from_block(&mut self.reader, &block, &mut self.message_scratch)?;
+        compression_ratios(
+            get_record_batch(message)?,
+            &self.metadata.schema,
+            self.projection.as_ref().map(|x| x.columns.as_ref()),
+            message
+                .version()
+                .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferVersion(err)))?,
+            &mut self.reader,
+            offset + length,
+        )
+    }
+
+    /// Returns whether the block at position `index` passes the block filter of this reader.
+    /// The rows of a block that does not are counted as skipped.
+    fn keep_block(&mut self, index: usize) -> PolarsResult<bool> {
+        if self.block_filter.is_none() {
+            return Ok(true);
+        }
+        let (keep, length) = self.filter_block_once(index)?;
+        if keep {
+            // the block is read next, which may need its number of rows
+            self.filtered.push_front((index, (keep, length)));
+        } else {
+            self.row_count += length;
+        }
+        Ok(keep)
+    }
+
+    /// Returns [`Self::filter_block`] of the block at position `index` of the file, reusing
+    /// its result if the block was evaluated before it is read.
+    fn filter_block_once(&mut self, index: usize) -> PolarsResult<(bool, usize)> {
+        // the blocks that were evaluated are read in order
+        while let Some((i, result)) = self.filtered.pop_front() {
+            if i == index {
+                return Ok(result);
+            }
+        }
+        self.filter_block(index)
+    }
+
+    /// Returns whether the block at position `index` of the file passes the block filter, and
+    /// its number of rows.
+    fn filter_block(&mut self, index: usize) -> PolarsResult<(bool, usize)> {
+        let block = &self.metadata.blocks[index];
+        let message = get_message_from_block(&mut self.reader, block, &mut self.message_scratch)?;
+        let length: usize = get_record_batch(message)?
+            .length()?
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+        let keep = match &self.block_filter {
+            Some(filter) => {
+                read_batch_statistics(message, index, length)?.is_none_or(|stats| filter(&stats))
+            },
+            None => true,
+        };
+        Ok((keep, length))
+    }
+
+    /// Returns the record batch at position `index` of the file, which is read at position
+    /// `position` of this reader. Unless it was decoded ahead of time, decodes it together with
+    /// the record batches that follow it, as bounded by `prefetch`.
+    fn read_prefetched(
+        &mut self,
+        index: usize,
+        position: usize,
+        prefetch: BlockPrefetch,
+    ) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+        if self.prefetched.front().is_none_or(|(i, _)| *i != index) {
+            self.prefetched.clear();
+            self.prefetch(position, prefetch)?;
+        }
+        let (_, (chunk, compression, diagnostics)) = self.prefetched.pop_front().unwrap();
+        self.diagnostics.append(diagnostics);
+        if chunk.is_ok() {
+            self.compression = compression;
+        }
+        chunk
+    }
+
+    /// Reads the record batch at position `position` of this reader and the record batches
+    /// that are read after it, as bounded by `prefetch`, and decodes them in parallel.
+    fn prefetch(&mut self, position: usize, prefetch: BlockPrefetch) -> PolarsResult<()> {
+        // the record batches beyond the limit or `max_batches` are not read
+        let max_blocks = match self.options.max_batches {
+            Some(max_batches) => prefetch
+                .max_blocks
+                .min(max_batches.saturating_sub(self.batch_count) + 1),
+            None => prefetch.max_blocks,
+        };
+        let mut indices = vec![self.block_index(position)];
+        let (_, mut rows) = self.filter_block_once(indices[0])?;
+        let mut bytes = match prefetch.max_bytes {
+            Some(_) => self.estimate_batch_size(indices[0])?,
+            None => 0,
+        };
+        for position in position + 1..self.metadata.blocks.len() {
+            if indices.len() >= max_blocks || (!self.options.reverse_rows && rows >= self.remaining)
+            {
+                break;
+            }
+            let index = self.block_index(position);
+            let (keep, length) = self.filter_block(index)?;
+            self.filtered.push_back((index, (keep, length)));
+            if !keep {
+                continue;
+            }
+            if let Some(max_bytes) = prefetch.max_bytes {
+                bytes += self.estimate_batch_size(index)?;
+                if bytes > max_bytes {
+                    break;
+                }
+            }
+            indices.push(index);
+            rows += length;
+        }
+
+        let blocks = indices
+            .iter()
+            .map(|&index| read_block(&mut self.reader, &self.metadata.blocks[index]))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let chunks = decode_blocks(
+            &blocks,
+            &self.metadata,
+            self.dictionaries.as_ref().unwrap(),
+            self.projection.as_ref().map(|x| x.columns.as_ref()),
+            &self.options,
+            prefetch.parallelism,
+        );
+        self.prefetched = indices.into_iter().zip(chunks).collect();
+        Ok(())
+    }
+
+    /// Returns the index of the block that is read at position `position` of this reader.
+    pub(super) fn block_index(&self, position: usize) -> usize {
+        if self.options.reverse {
+            self.metadata.blocks.len() - 1 - position
+        } else {
+            position
+        }
+    }
+
+    /// Returns the number of rows that precede the block at position `index` in the file, or the
+    /// number of rows of the file if `index` is the number of blocks.
+    fn block_row(&mut self, index: usize) -> PolarsResult<usize> {
+        if self.block_rows.is_none() {
+            let mut row = 0;
+            let mut rows = Vec::with_capacity(self.metadata.blocks.len() + 1);
+            for block in &self.metadata.blocks {
+                rows.push(row);
+                let message =
+                    get_message_from_block(&mut self.reader, block, &mut self.message_scratch)?;
+                let length: usize = get_record_batch(message)?
+                    .length()?
+                    .try_into()
+                    .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+                row += length;
+            }
+            rows.push(row);
+            self.block_rows = Some(rows);
+        }
+        Ok(self.block_rows.as_ref().unwrap()[index])
+    }
+
     pub fn next_record_batch(
         &mut self,
     ) -> Option<PolarsResult<arrow_format::ipc::RecordBatchRef<'_>>> {
@@ -163,41 +544,263 @@ impl<R: Read + Seek> FileReader<R> {
     }
 }
 
+/// Returns an iterator over the column at `field_index` of every record batch of an Arrow IPC
+/// file, in file order.
+///
+/// Only the dictionaries that this column refers to are read; all other columns are skipped.
+pub fn column_iter<R: Read + Seek>(
+    mut reader: R,
+    metadata: FileMetadata,
+    field_index: usize,
+    options: ReadOptions,
+) -> PolarsResult<impl Iterator<Item = PolarsResult<Box<dyn Array>>>> {
+    polars_ensure!(
+        field_index < metadata.schema.len(),
+        OutOfBounds: "field index {field_index} is out of bounds for a schema of {} fields",
+        metadata.schema.len()
+    );
+    let mut data_scratch = vec![];
+    let mut message_scratch = vec![];
+    let dictionaries = read_column_dictionaries(
+        &mut reader,
+        &metadata,
+        field_index,
+        &mut data_scratch,
+        &options,
+    )?;
+
+    Ok((0..metadata.blocks.len()).map(move |index| {
+        let chunk = read_batch(
+            &mut reader,
+            &dictionaries,
+            &metadata,
+            Some(&[field_index]),
+            None,
+            index,
+            &mut message_scratch,
+            &mut data_scratch,
+            &options,
+        )?;
+        let chunk = apply_options(chunk, &options)?;
+        Ok(chunk.into_arrays().pop().unwrap())
+    }))
+}
+
+/// Returns the dictionary of the dictionary-encoded column at `field_index` of an Arrow IPC
+/// file, and an iterator over every record batch of the file, in file order, of the keys of
+/// that column followed by the columns `columns`, e.g. of the measures of a categorical
+/// group-by.
+///
+/// This is the fast path of reading a dictionary-encoded column: only its own dictionary and
+/// the dictionaries nested in `columns` are read, all other columns are skipped, and the column
+/// is read as its keys into the returned dictionary, without materializing its values. As with
+/// [`ReadOptions::dictionary_keys`], the dictionary-encoded columns of `columns` are read as
+/// their keys too.
+pub fn dictionary_keys_iter<R: Read + Seek>(
+    mut reader: R,
+    metadata: FileMetadata,
+    field_index: usize,
+    columns: &[usize],
+    mut options: ReadOptions,
+) -> PolarsResult<(
+    Box<dyn Array>,
+    impl Iterator<Item = PolarsResult<RecordBatchT<Box<dyn Array>>>>,
+)> {
+    let fields = metadata.schema.len();
+    if let Some(index) = std::iter::once(&field_index)
+        .chain(columns)
+        .find(|&&index| index >= fields)
+    {
+        polars_bail!(
+            OutOfBounds: "field index {index} is out of bounds for a schema of {fields} fields"
+        );
+    }
+    let Some(id) = metadata.ipc_schema.fields[field_index].dictionary_id else {
+        polars_bail!(
+            InvalidOperation: "column {field_index} of type {:?} is not dictionary-encoded",
+            metadata.schema.get_at_index(field_index).unwrap().1.dtype
+        );
+    };
+    options.dictionary_keys = true;
+
+    let mut data_scratch = vec![];
+    let mut message_scratch = vec![];
+    let values = read_dictionary_by_id(&mut reader, &metadata, id, &mut data_scratch, &options)?;
+    let dictionaries = read_column_nested_dictionaries(
+        &mut reader,
+        &metadata,
+        columns,
+        &mut data_scratch,
+        &options,
+    )?;
+
+    let projection = std::iter::once(field_index)
+        .chain(columns.iter().copied())
+        .collect::<Vec<_>>();
+    let batches = (0..metadata.blocks.len()).map(move |index| {
+        let chunk = read_batch(
+            &mut reader,
+            &dictionaries,
+            &metadata,
+            Some(&projection),
+            None,
+            index,
+            &mut message_scratch,
+            &mut data_scratch,
+            &options,
+        )?;
+        apply_options(chunk, &options)
+    });
+    Ok((values, batches))
+}
+
+/// Reads a sample of an Arrow IPC file that consists of the first `per_block` rows of each of
+/// its record batches, e.g. to inspect the values of a large file. Only these rows are decoded
+/// (the rows of compressed buffers are decompressed whole); the dictionaries of the file are
+/// all read.
+pub fn sample_first_rows<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetadata,
+    per_block: usize,
+    options: &ReadOptions,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let mut data_scratch = vec![];
+    let mut message_scratch = vec![];
+    let dictionaries = read_file_dictionaries(reader, metadata, &mut data_scratch, options)?;
+
+    let samples = (0..metadata.blocks.len())
+        .map(|index| {
+            let chunk = read_batch(
+                reader,
+                &dictionaries,
+                metadata,
+                None,
+                Some(per_block),
+                index,
+                &mut message_scratch,
+                &mut data_scratch,
+                options,
+            )?;
+            apply_options(chunk, options)
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    if samples.is_empty() {
+        let schema = metadata
+            .schema
+            .iter_values()
+            .map(|field| decoded_field(field, options).into_owned())
+            .collect::<ArrowSchema>();
+        let arrays = schema
+            .iter_values()
+            .map(|field| new_empty_array(field.dtype.clone()))
+            .collect();
+        return apply_options(RecordBatchT::new(0, Arc::new(schema), arrays), options);
+    }
+    concatenate_batches(&samples)
+}
+
 impl<R: Read + Seek> Iterator for FileReader<R> {
     type Item = PolarsResult<RecordBatchT<Box<dyn Array>>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // get current block
-        if self.current_block == self.metadata.blocks.len() {
+        if let Some(chunk) = self.pending.pop_front() {
+            return Some(Ok(chunk));
+        }
+
+        if self.options.reached_max_batches(self.batch_count) {
             return None;
         }
 
+        // get current block, skipping the blocks that do not pass the block filter
+        loop {
+            if self.current_block == self.metadata.blocks.len() {
+                return None;
+            }
+            match self.keep_block(self.block_index(self.current_block)) {
+                Ok(true) => break,
+                Ok(false) => self.current_block += 1,
+                Err(e) => {
+                    // the block is skipped so that the error is not returned again
+                    self.current_block += 1;
+                    return Some(Err(e));
+                },
+            }
+        }
+
         match self.read_dictionaries() {
             Ok(_) => {},
             Err(e) => return Some(Err(e)),
         };
 
-        let block = self.current_block;
+        let block = self.block_index(self.current_block);
         self.current_block += 1;
+        self.batch_count += 1;
 
-        let chunk = read_batch(
-            &mut self.reader,
-            self.dictionaries.as_ref().unwrap(),
-            &self.metadata,
-            self.projection.as_ref().map(|x| x.columns.as_ref()),
-            Some(self.remaining),
-            block,
-            &mut self.message_scratch,
-            &mut self.data_scratch,
-        );
-        self.remaining -= chunk.as_ref().map(|x| x.len()).unwrap_or_default();
+        // in reverse, the rows that precede the block are not the rows that were read
+        let row = if self.options.reverse && self.options.row_index.is_some() {
+            match self.block_row(block) {
+                Ok(row) => row,
+                Err(e) => return Some(Err(e)),
+            }
+        } else {
+            self.row_count
+        };
 
-        let chunk = if let Some(ProjectionInfo { map, .. }) = &self.projection {
-            // re-order according to projection
-            chunk.map(|chunk| apply_projection(chunk, map))
+        // reversed rows are limited to the last rows of the block
+        let limit = (!self.options.reverse_rows).then_some(self.remaining);
+        let chunk = if let Some(prefetch) = self.options.block_prefetch {
+            // record batches that are decoded ahead of time are decoded whole
+            self.read_prefetched(block, self.current_block - 1, prefetch)
+                .map(|chunk| match limit {
+                    Some(limit) if chunk.len() > limit => {
+                        let (schema, arrays) = chunk.into_schema_and_arrays();
+                        RecordBatchT::new(limit, schema, slice_arrays(&arrays, 0, limit, false))
+                    },
+                    _ => chunk,
+                })
         } else {
+            let chunk = read_batch_with_diagnostics(
+                &mut self.reader,
+                self.dictionaries.as_ref().unwrap(),
+                &self.metadata,
+                self.projection.as_ref().map(|x| x.columns.as_ref()),
+                limit,
+                block,
+                &mut self.message_scratch,
+                &mut self.data_scratch,
+                &mut vec![],
+                &self.options,
+                &mut self.diagnostics,
+            );
+            if chunk.is_ok() {
+                self.compression = batch_compression(&self.message_scratch);
+            }
             chunk
         };
-        Some(chunk)
+        let length = chunk
+            .as_ref()
+            .map(|x| x.len().min(self.remaining))
+            .unwrap_or_default();
+        self.remaining -= length;
+        self.row_count += length;
+
+        let chunk = chunk
+            .and_then(|chunk| prepend_row_index(chunk, row, &self.options))
+            .and_then(|chunk| {
+                if self.options.reverse_rows {
+                    reverse_rows(chunk, length)
+                } else {
+                    Ok(chunk)
+                }
+            })
+            .and_then(|chunk| apply_options(chunk, &self.options));
+        match (chunk, self.options.max_rows_per_output_batch) {
+            (Ok(chunk), Some(max_rows)) => {
+                self.pending = split_batch(chunk, max_rows, self.options.contiguous);
+                self.pending.pop_front().map(Ok)
+            },
+            (chunk, _) => Some(chunk),
+        }
     }
 }
@@ -0,0 +1,105 @@
+use std::io::{Read, Seek};
+use std::sync::Arc;
+
+use polars_error::PolarsResult;
+
+use super::{
+    BatchStatistics, FileMetadata, FileReader, ProjectionInfo, ReadOptions,
+    get_row_count_from_blocks, read_file_metadata,
+};
+use crate::datatypes::ArrowSchema;
+
+/// A predicate on the [`BatchStatistics`] of a record batch, which is `false` when none of the
+/// rows of the record batch can match the predicate of the scan.
+pub type StatisticsPredicate = Arc<dyn Fn(&BatchStatistics) -> bool + Send + Sync>;
+
+/// What a scan pushes down into a [`ScanSource`].
+#[derive(Clone, Default)]
+pub struct ScanPushdown {
+    /// The columns to read, in their output order. All columns are read if `None`.
+    pub projection: Option<ProjectionInfo>,
+    /// The largest number of rows to read, across record batches.
+    pub limit: Option<usize>,
+    /// Prunes the record batches whose statistics can not match the predicate of the scan. The
+    /// rows of the record batches that are read still have to be filtered by the scan.
+    pub predicate: Option<StatisticsPredicate>,
+}
+
+/// A source of record batches for the scan of a lazy query, which describes the data before
+/// reading it and reads it with the projection, the limit and the predicate of the scan pushed
+/// down.
+pub trait ScanSource {
+    /// The record batches of a scan.
+    type Batches: Iterator;
+
+    /// Returns the schema of the source, before any projection.
+    fn schema(&self) -> &ArrowSchema;
+
+    /// Returns an estimate of the number of rows of the source, before any pushdown, or `None`
+    /// if it is unknown.
+    fn row_count_estimate(&self) -> Option<usize>;
+
+    /// Reads the source with `pushdown`.
+    fn scan(self, pushdown: ScanPushdown) -> PolarsResult<Self::Batches>;
+}
+
+/// A [`ScanSource`] of an Arrow IPC file.
+pub struct IpcScanSource<R: Read + Seek> {
+    reader: R,
+    metadata: FileMetadata,
+    row_count: usize,
+    options: ReadOptions,
+}
+
+impl<R: Read + Seek> IpcScanSource<R> {
+    /// Creates a [`IpcScanSource`] of the IPC file of `reader`, reading its footer and the
+    /// headers of its record batches, which count its rows.
+    pub fn try_new(mut reader: R) -> PolarsResult<Self> {
+        let metadata = read_file_metadata(&mut reader)?;
+        let row_count = get_row_count_from_blocks(&mut reader, &metadata.blocks)? as usize;
+        Ok(Self {
+            reader,
+            metadata,
+            row_count,
+            options: Default::default(),
+        })
+    }
+
+    /// Sets the [`ReadOptions`] of the scans of this source.
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns the metadata of the file.
+    pub fn metadata(&self) -> &FileMetadata {
+        &self.metadata
+    }
+}
+
+impl<R: Read + Seek> ScanSource for IpcScanSource<R> {
+    type Batches = FileReader<R>;
+
+    fn schema(&self) -> &ArrowSchema {
+        &self.metadata.schema
+    }
+
+    /// The number of rows of the file is exact.
+    fn row_count_estimate(&self) -> Option<usize> {
+        Some(self.row_count)
+    }
+
+    fn scan(self, pushdown: ScanPushdown) -> PolarsResult<Self::Batches> {
+        let mut reader = FileReader::new_with_projection_info(
+            self.reader,
+            self.metadata,
+            pushdown.projection,
+            pushdown.limit,
+        )
+        .with_options(self.options);
+        if let Some(predicate) = pushdown.predicate {
+            reader = reader.with_block_filter(move |statistics| predicate(statistics));
+        }
+        Ok(reader)
+    }
+}
@@ -2,11 +2,11 @@ use std::sync::Arc;
 
 use arrow_format::ipc::planus::ReadAsRoot;
 use arrow_format::ipc::{FieldRef, FixedSizeListRef, MapRef, TimeRef, TimestampRef, UnionRef};
-use polars_error::{PolarsResult, polars_bail, polars_err};
+use polars_error::{PolarsResult, polars_bail, polars_ensure, polars_err};
 use polars_utils::pl_str::PlSmallStr;
 
 use super::super::{IpcField, IpcSchema};
-use super::{OutOfSpecKind, StreamMetadata};
+use super::{ComplexityBudget, OutOfSpecKind, StreamMetadata};
 use crate::datatypes::{
     ArrowDataType, ArrowSchema, Extension, ExtensionType, Field, IntegerType, IntervalUnit,
     Metadata, TimeUnit, UnionMode, UnionType, get_extension,
@@ -380,16 +380,23 @@ pub fn deserialize_schema(
         _ => polars_bail!(ComputeError: "The message is expected to be a Schema message"),
     }?;
 
-    fb_to_schema(schema)
+    fb_to_schema(schema, &Default::default())
 }
 
+/// The deepest that the fields of a schema may be nested by default, as in the Arrow C++
+/// reader. Deserializing and decoding nested types recurses once per level, such that deeper
+/// schemas could overflow the stack. See [`ComplexityBudget::max_depth`].
+pub const MAX_NESTING_DEPTH: usize = 64;
+
 /// Deserialize the raw Schema table from IPC format to Schema data type
 pub(super) fn fb_to_schema(
     schema: arrow_format::ipc::SchemaRef,
+    budget: &ComplexityBudget,
 ) -> PolarsResult<(ArrowSchema, IpcSchema, Option<Metadata>)> {
     let fields = schema
         .fields()?
         .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingFields))?;
+    budget.check_fields(fields)?;
 
     let mut arrow_schema = ArrowSchema::with_capacity(fields.len());
     let mut ipc_fields = Vec::with_capacity(fields.len());
@@ -435,7 +442,10 @@ pub(super) fn fb_to_schema(
     ))
 }
 
-pub(super) fn deserialize_stream_metadata(meta: &[u8]) -> PolarsResult<StreamMetadata> {
+pub(super) fn deserialize_stream_metadata(
+    meta: &[u8],
+    budget: &ComplexityBudget,
+) -> PolarsResult<StreamMetadata> {
     let message = arrow_format::ipc::MessageRef::read_as_root(meta)
         .map_err(|err| polars_err!(oos = format!("Unable to get root as message: {err:?}")))?;
     let version = message.version()?;
@@ -448,7 +458,7 @@ pub(super) fn deserialize_stream_metadata(meta: &[u8]) -> PolarsResult<StreamMet
     } else {
         polars_bail!(oos = "The first IPC message of the stream must be a schema")
     };
-    let (schema, ipc_schema, custom_schema_metadata) = fb_to_schema(schema)?;
+    let (schema, ipc_schema, custom_schema_metadata) = fb_to_schema(schema, budget)?;
 
     Ok(StreamMetadata {
         schema,
@@ -457,3 +467,185 @@ pub(super) fn deserialize_stream_metadata(meta: &[u8]) -> PolarsResult<StreamMet
         custom_schema_metadata,
     })
 }
+
+/// Options of [`check_schema`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SchemaCheckOptions {
+    /// Whether fields that only differ in their nullability are a mismatch.
+    pub check_nullability: bool,
+    /// Whether fields that only differ in their metadata are a mismatch.
+    pub check_metadata: bool,
+}
+
+impl Default for SchemaCheckOptions {
+    fn default() -> Self {
+        Self {
+            check_nullability: true,
+            check_metadata: false,
+        }
+    }
+}
+
+/// Checks that the schema `found`, e.g. of a file, matches the schema `expected`.
+///
+/// Fields are compared by position, including the fields nested in their types. On mismatch,
+/// the error lists every field that differs and what differs about it.
+pub fn check_schema(
+    expected: &ArrowSchema,
+    found: &ArrowSchema,
+    options: SchemaCheckOptions,
+) -> PolarsResult<()> {
+    let mut differences = vec![];
+    diff_fields(
+        "",
+        expected.iter_values(),
+        found.iter_values(),
+        options,
+        &mut differences,
+    );
+    if differences.is_empty() {
+        return Ok(());
+    }
+    polars_bail!(SchemaMismatch: "schema does not match the expected schema:\n{}", differences.join("\n"))
+}
+
+fn diff_fields<'a>(
+    path: &str,
+    mut expected: impl ExactSizeIterator<Item = &'a Field>,
+    mut found: impl ExactSizeIterator<Item = &'a Field>,
+    options: SchemaCheckOptions,
+    differences: &mut Vec<String>,
+) {
+    for i in 0..expected.len().max(found.len()) {
+        match (expected.next(), found.next()) {
+            (Some(expected), Some(found)) => {
+                diff_field(&format!("{path}{i}"), expected, found, options, differences)
+            },
+            (Some(expected), None) => differences.push(format!(
+                "  field {path}{i}: expected field \"{}\", found none",
+                expected.name
+            )),
+            (None, Some(found)) => differences.push(format!(
+                "  field {path}{i}: found unexpected field \"{}\"",
+                found.name
+            )),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+fn diff_field(
+    path: &str,
+    expected: &Field,
+    found: &Field,
+    options: SchemaCheckOptions,
+    differences: &mut Vec<String>,
+) {
+    let name = &expected.name;
+    if expected.name != found.name {
+        differences.push(format!(
+            "  field {path}: expected name \"{name}\", found \"{}\"",
+            found.name
+        ));
+    }
+    if options.check_nullability && expected.is_nullable != found.is_nullable {
+        differences.push(format!(
+            "  field {path} (\"{name}\"): expected nullable {}, found {}",
+            expected.is_nullable, found.is_nullable
+        ));
+    }
+    if options.check_metadata && expected.metadata != found.metadata {
+        differences.push(format!(
+            "  field {path} (\"{name}\"): expected metadata {:?}, found {:?}",
+            expected.metadata, found.metadata
+        ));
+    }
+
+    use ArrowDataType::*;
+    let path = format!("{path}.");
+    match (&expected.dtype, &found.dtype) {
+        (Struct(expected), Struct(found)) => {
+            diff_fields(&path, expected.iter(), found.iter(), options, differences)
+        },
+        (List(expected), List(found)) | (LargeList(expected), LargeList(found)) => {
+            diff_field(&format!("{path}0"), expected, found, options, differences)
+        },
+        (FixedSizeList(expected, expected_size), FixedSizeList(found, found_size))
+            if expected_size == found_size =>
+        {
+            diff_field(&format!("{path}0"), expected, found, options, differences)
+        },
+        (Map(expected, expected_sorted), Map(found, found_sorted))
+            if expected_sorted == found_sorted =>
+        {
+            diff_field(&format!("{path}0"), expected, found, options, differences)
+        },
+        (expected_dtype, found_dtype) if expected_dtype != found_dtype => {
+            differences.push(format!(
+                "  field {} (\"{name}\"): expected type {expected_dtype:?}, found {found_dtype:?}",
+                path.trim_end_matches('.')
+            ))
+        },
+        _ => {},
+    }
+}
+
+/// Whether arrays of `a` and `b` are laid out in the same buffers, i.e. whether buffers that
+/// were written for one can be read as the other.
+fn same_layout(a: &ArrowDataType, b: &ArrowDataType) -> bool {
+    use ArrowDataType::*;
+    if a.to_physical_type() != b.to_physical_type() {
+        return false;
+    }
+    match (a.to_logical_type(), b.to_logical_type()) {
+        (List(a) | LargeList(a) | Map(a, _), List(b) | LargeList(b) | Map(b, _)) => {
+            same_layout(&a.dtype, &b.dtype)
+        },
+        (FixedSizeList(a, a_size), FixedSizeList(b, b_size)) => {
+            a_size == b_size && same_layout(&a.dtype, &b.dtype)
+        },
+        (FixedSizeBinary(a_size), FixedSizeBinary(b_size)) => a_size == b_size,
+        (Struct(a), Struct(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| same_layout(&a.dtype, &b.dtype))
+        },
+        (Union(a), Union(b)) => {
+            a.mode == b.mode
+                && a.fields.len() == b.fields.len()
+                && a.fields
+                    .iter()
+                    .zip(&b.fields)
+                    .all(|(a, b)| same_layout(&a.dtype, &b.dtype))
+        },
+        (Dictionary(_, a, _), Dictionary(_, b, _)) => same_layout(a, b),
+        _ => true,
+    }
+}
+
+/// Rewrites `schema` with `rewrite`, checking that the rewritten schema has the same number of
+/// fields as `schema` and that each is laid out in the same buffers as before. Fields may be
+/// renamed and their logical types changed, e.g. the timezone of a timestamp.
+pub(crate) fn rewrite_schema(
+    schema: &ArrowSchema,
+    rewrite: impl FnOnce(ArrowSchema) -> PolarsResult<ArrowSchema>,
+) -> PolarsResult<ArrowSchema> {
+    let rewritten = rewrite(schema.clone())?;
+    polars_ensure!(
+        rewritten.len() == schema.len(),
+        SchemaMismatch: "IPC: the rewritten schema has {} fields instead of {}",
+        rewritten.len(),
+        schema.len()
+    );
+    for (field, rewritten) in schema.iter_values().zip(rewritten.iter_values()) {
+        polars_ensure!(
+            same_layout(&field.dtype, &rewritten.dtype),
+            SchemaMismatch: "IPC: the rewritten type {:?} of field \"{}\" is not laid out as its type {:?}",
+            rewritten.dtype,
+            field.name,
+            field.dtype
+        );
+    }
+    Ok(rewritten)
+}
@@ -0,0 +1,249 @@
+use polars_error::{PolarsResult, polars_bail, polars_ensure, polars_err};
+use polars_utils::aliases::PlHashMap;
+use polars_utils::pl_str::PlSmallStr;
+
+use crate::array::*;
+use crate::compute::concatenate::concatenate;
+use crate::datatypes::PhysicalType;
+use crate::record_batch::RecordBatchT;
+use crate::types::NativeType;
+use crate::{match_integer_type, with_match_primitive_type_full};
+
+/// Returns the bytes of every value of `values`, by which values are compared, or `None` for
+/// the null values. Returns `None` if values of their type are not compared.
+fn value_bytes(values: &dyn Array) -> Option<Vec<Option<Vec<u8>>>> {
+    fn collect<'a, I: Iterator<Item = Option<&'a [u8]>>>(iter: I) -> Vec<Option<Vec<u8>>> {
+        iter.map(|value| value.map(<[u8]>::to_vec)).collect()
+    }
+
+    let any = values.as_any();
+    Some(match values.dtype().to_physical_type() {
+        PhysicalType::Primitive(primitive) => with_match_primitive_type_full!(primitive, |$T| {
+            any.downcast_ref::<PrimitiveArray<$T>>()
+                .unwrap()
+                .iter()
+                .map(|value| value.map(|value| value.to_le_bytes().as_ref().to_vec()))
+                .collect()
+        }),
+        PhysicalType::Utf8 => collect(
+            any.downcast_ref::<Utf8Array<i32>>()
+                .unwrap()
+                .iter()
+                .map(|value| value.map(str::as_bytes)),
+        ),
+        PhysicalType::LargeUtf8 => collect(
+            any.downcast_ref::<Utf8Array<i64>>()
+                .unwrap()
+                .iter()
+                .map(|value| value.map(str::as_bytes)),
+        ),
+        PhysicalType::Utf8View => collect(
+            any.downcast_ref::<Utf8ViewArray>()
+                .unwrap()
+                .iter()
+                .map(|value| value.map(str::as_bytes)),
+        ),
+        PhysicalType::Binary => collect(any.downcast_ref::<BinaryArray<i32>>().unwrap().iter()),
+        PhysicalType::LargeBinary => {
+            collect(any.downcast_ref::<BinaryArray<i64>>().unwrap().iter())
+        },
+        PhysicalType::BinaryView => collect(any.downcast_ref::<BinaryViewArray>().unwrap().iter()),
+        PhysicalType::FixedSizeBinary => {
+            collect(any.downcast_ref::<FixedSizeBinaryArray>().unwrap().iter())
+        },
+        _ => return None,
+    })
+}
+
+/// Returns the values of the dictionary array `array`.
+fn dictionary_values(array: &dyn Array) -> Box<dyn Array> {
+    let PhysicalType::Dictionary(key_type) = array.dtype().to_physical_type() else {
+        unreachable!()
+    };
+    match_integer_type!(key_type, |$T| {
+        array
+            .as_any()
+            .downcast_ref::<DictionaryArray<$T>>()
+            .unwrap()
+            .values()
+            .clone()
+    })
+}
+
+/// Merges `dictionaries`, of the same type, into the dictionary of their distinct values in the
+/// order that they first occur, returning it with the position in it of every value of each of
+/// `dictionaries`. Returns `None` if values of their type are not compared.
+fn merge_dictionaries(
+    dictionaries: &[Box<dyn Array>],
+) -> PolarsResult<Option<(Box<dyn Array>, Vec<Vec<usize>>)>> {
+    // the values of the merged dictionary, as 1-row slices of `dictionaries`
+    let mut distinct = vec![];
+    let mut positions = PlHashMap::default();
+    let mut remaps = vec![];
+    for values in dictionaries {
+        let Some(bytes) = value_bytes(values.as_ref()) else {
+            return Ok(None);
+        };
+        let remap = bytes
+            .into_iter()
+            .enumerate()
+            .map(|(position, value)| {
+                *positions.entry(value).or_insert_with(|| {
+                    distinct.push(values.sliced(position, 1));
+                    distinct.len() - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        remaps.push(remap);
+    }
+
+    let values = if distinct.is_empty() {
+        new_empty_array(dictionaries[0].dtype().clone())
+    } else {
+        concatenate(&distinct.iter().map(|row| row.as_ref()).collect::<Vec<_>>())?
+    };
+    Ok(Some((values, remaps)))
+}
+
+/// Remaps the keys of the dictionary array `array` by `remap` onto the dictionary `values`, or
+/// keeps them if `remap` is `None`. `name` names the array in errors.
+fn rebase(
+    array: &dyn Array,
+    remap: Option<&[usize]>,
+    values: &dyn Array,
+    name: &str,
+) -> PolarsResult<Box<dyn Array>> {
+    let PhysicalType::Dictionary(key_type) = array.dtype().to_physical_type() else {
+        unreachable!()
+    };
+    match_integer_type!(key_type, |$T| {
+        let array = array.as_any().downcast_ref::<DictionaryArray<$T>>().unwrap();
+        let Some(remap) = remap else {
+            let keys = array.keys().clone();
+            return Ok(
+                DictionaryArray::try_new(array.dtype().clone(), keys, values.to_boxed())?.boxed(),
+            );
+        };
+        let keys = array
+            .keys_iter()
+            .map(|key| {
+                key.map(|key| {
+                    <$T>::try_from(remap[key]).map_err(|_| {
+                        polars_err!(
+                            ComputeError: "IPC: the {} values of the shared dictionary overflow the keys of column '{name}'",
+                            values.len()
+                        )
+                    })
+                })
+                .transpose()
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let keys = PrimitiveArray::<$T>::from(keys);
+        Ok(DictionaryArray::try_new(array.dtype().clone(), keys, values.to_boxed())?.boxed())
+    })
+}
+
+/// Rebases the dictionary arrays `arrays` of the column `name`, of the same type, onto a single
+/// dictionary of the distinct values of their dictionaries, e.g. of the record batches of a
+/// stream that replaced the dictionary of the column. Arrays whose dictionaries are prefixes of
+/// the longest one, e.g. because they grew by deltas, keep their keys and share that dictionary.
+///
+/// Returns `None` if the arrays already share their dictionary, or if the values of their
+/// dictionaries are not compared, i.e. are not of a primitive, string or binary type.
+pub(crate) fn unify_dictionaries(
+    arrays: &[Box<dyn Array>],
+    name: &str,
+) -> PolarsResult<Option<Vec<Box<dyn Array>>>> {
+    let dictionaries = arrays
+        .iter()
+        .map(|array| dictionary_values(array.as_ref()))
+        .collect::<Vec<_>>();
+    if dictionaries[1..]
+        .iter()
+        .all(|values| values.as_ref() == dictionaries[0].as_ref())
+    {
+        return Ok(None);
+    }
+    let Some((values, remaps)) = merge_dictionaries(&dictionaries)? else {
+        return Ok(None);
+    };
+    let is_prefix = remaps.iter().all(|remap| {
+        remap
+            .iter()
+            .enumerate()
+            .all(|(key, &position)| key == position)
+    });
+    let values = if is_prefix {
+        dictionaries
+            .into_iter()
+            .max_by_key(|values| values.len())
+            .unwrap()
+    } else {
+        values
+    };
+    arrays
+        .iter()
+        .zip(remaps)
+        .map(|(array, remap)| {
+            let remap = (!is_prefix).then_some(remap.as_slice());
+            rebase(array.as_ref(), remap, values.as_ref(), name)
+        })
+        .collect::<PolarsResult<_>>()
+        .map(Some)
+}
+
+/// Merges the dictionaries of the dictionary-encoded columns `names` of `chunk` into a single
+/// dictionary of their distinct values, shared by these columns, whose keys are remapped to it.
+/// Columns of `names` that were not read are ignored. See
+/// [`ReadOptions::shared_dictionary_columns`].
+///
+/// [`ReadOptions::shared_dictionary_columns`]: super::ReadOptions::shared_dictionary_columns
+pub(crate) fn share_dictionaries(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    names: &[PlSmallStr],
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let indices = names
+        .iter()
+        .filter_map(|name| chunk.schema().index_of(name))
+        .collect::<Vec<_>>();
+    if indices.len() <= 1 {
+        return Ok(chunk);
+    }
+
+    let mut dictionaries = vec![];
+    for &index in &indices {
+        let (name, field) = chunk.schema().get_at_index(index).unwrap();
+        let array = &chunk.arrays()[index];
+        polars_ensure!(
+            matches!(array.dtype().to_physical_type(), PhysicalType::Dictionary(_)),
+            InvalidOperation: "IPC: column '{name}' of type {:?} is not dictionary-encoded and can not share a dictionary",
+            field.dtype
+        );
+        let values = dictionary_values(array.as_ref());
+        if let Some(first) = dictionaries
+            .first()
+            .map(|first: &Box<dyn Array>| first.dtype())
+        {
+            polars_ensure!(
+                values.dtype() == first,
+                SchemaMismatch: "IPC: column '{name}' has a dictionary of type {:?} that can not be shared with a dictionary of type {first:?}",
+                values.dtype()
+            );
+        }
+        dictionaries.push(values);
+    }
+    let Some((values, remaps)) = merge_dictionaries(&dictionaries)? else {
+        polars_bail!(
+            nyi = "IPC: sharing dictionaries of values of type {:?}",
+            dictionaries[0].dtype()
+        )
+    };
+
+    let length = chunk.len();
+    let (schema, mut arrays) = chunk.into_schema_and_arrays();
+    for (index, remap) in indices.into_iter().zip(remaps) {
+        let name = schema.get_at_index(index).unwrap().0;
+        arrays[index] = rebase(arrays[index].as_ref(), Some(&remap), values.as_ref(), name)?;
+    }
+    Ok(RecordBatchT::new(length, schema, arrays))
+}
@@ -0,0 +1,73 @@
+use std::fs::File;
+use std::io::{Cursor, Read, Result, Seek, SeekFrom, Write};
+
+use polars_error::PolarsResult;
+
+use super::{FileReader, read_file_metadata};
+
+#[derive(Debug)]
+enum Spool {
+    Memory(Cursor<Vec<u8>>),
+    File(File),
+}
+
+/// The data of a non-seekable reader, e.g. a pipe, spooled such that it can be read as an IPC
+/// file, which requires [`Seek`] to read its footer. See [`spill`].
+#[derive(Debug)]
+pub struct Spilled(Spool);
+
+impl Spilled {
+    /// Whether the data was spooled to a temporary file instead of to memory.
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.0, Spool::File(_))
+    }
+}
+
+impl Read for Spilled {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match &mut self.0 {
+            Spool::Memory(cursor) => cursor.read(buf),
+            Spool::File(file) => file.read(buf),
+        }
+    }
+}
+
+impl Seek for Spilled {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        match &mut self.0 {
+            Spool::Memory(cursor) => cursor.seek(pos),
+            Spool::File(file) => file.seek(pos),
+        }
+    }
+}
+
+/// Spools all data of `reader` such that it can be read with [`Seek`]: in memory if it has at
+/// most `threshold` bytes, and otherwise in a temporary file of [`std::env::temp_dir`] that is
+/// removed once the returned [`Spilled`] is dropped.
+pub fn spill<R: Read>(mut reader: R, threshold: usize) -> PolarsResult<Spilled> {
+    let mut data = vec![];
+    reader
+        .by_ref()
+        .take((threshold as u64).saturating_add(1))
+        .read_to_end(&mut data)?;
+    if data.len() <= threshold {
+        return Ok(Spilled(Spool::Memory(Cursor::new(data))));
+    }
+
+    let mut file = tempfile::tempfile()?;
+    file.write_all(&data)?;
+    drop(data);
+    std::io::copy(&mut reader, &mut file)?;
+    file.seek(SeekFrom::Start(0))?;
+    Ok(Spilled(Spool::File(file)))
+}
+
+/// Reads the IPC file of a non-seekable `reader`, e.g. of `stdin` or of a pipe, by spooling it
+/// first. See [`spill`].
+///
+/// Prefer the stream format for non-seekable input: it is read as it arrives.
+pub fn read_spilled<R: Read>(reader: R, threshold: usize) -> PolarsResult<FileReader<Spilled>> {
+    let mut reader = spill(reader, threshold)?;
+    let metadata = read_file_metadata(&mut reader)?;
+    Ok(FileReader::new(reader, metadata, None, None))
+}
@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use arrow_format::ipc::MessageRef;
+use polars_error::{PolarsResult, polars_err};
+use polars_utils::aliases::PlHashMap;
+use polars_utils::pl_str::PlSmallStr;
+
+use super::OutOfSpecKind;
+
+/// The prefix of the custom metadata keys of a record batch message that hold its statistics.
+///
+/// A statistic of a column is stored as `polars:statistics:<column>:<statistic>`, where
+/// `<statistic>` is one of `min`, `max` or `null_count`, and its value as a string.
+pub const STATISTICS_PREFIX: &str = "polars:statistics:";
+
+/// The statistics of a column of a record batch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnStatistics {
+    pub min: Option<PlSmallStr>,
+    pub max: Option<PlSmallStr>,
+    pub null_count: Option<usize>,
+}
+
+/// The statistics of a record batch, as stored in the custom metadata of its message.
+/// See [`STATISTICS_PREFIX`] for the format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchStatistics {
+    /// The position of the record batch in the file
+    pub index: usize,
+    /// The number of rows of the record batch
+    pub length: usize,
+    pub columns: PlHashMap<PlSmallStr, ColumnStatistics>,
+}
+
+impl BatchStatistics {
+    /// Returns the statistics of the column named `column`.
+    pub fn column(&self, column: &str) -> Option<&ColumnStatistics> {
+        self.columns.get(column)
+    }
+
+    /// Returns the minimum of the column named `column`, parsed as `T`.
+    pub fn min<T: FromStr>(&self, column: &str) -> Option<T> {
+        self.column(column)?.min.as_ref()?.parse().ok()
+    }
+
+    /// Returns the maximum of the column named `column`, parsed as `T`.
+    pub fn max<T: FromStr>(&self, column: &str) -> Option<T> {
+        self.column(column)?.max.as_ref()?.parse().ok()
+    }
+
+    /// Returns the custom metadata entries that encode these statistics.
+    pub fn to_metadata(&self) -> Vec<(String, String)> {
+        let mut entries = vec![];
+        for (name, column) in &self.columns {
+            let key = |statistic: &str| format!("{STATISTICS_PREFIX}{name}:{statistic}");
+            if let Some(min) = &column.min {
+                entries.push((key("min"), min.to_string()));
+            }
+            if let Some(max) = &column.max {
+                entries.push((key("max"), max.to_string()));
+            }
+            if let Some(null_count) = column.null_count {
+                entries.push((key("null_count"), null_count.to_string()));
+            }
+        }
+        entries
+    }
+}
+
+/// Reads the [`BatchStatistics`] of the record batch message at position `index`, or `None` when
+/// the message has no statistics.
+pub(super) fn read_batch_statistics(
+    message: MessageRef,
+    index: usize,
+    length: usize,
+) -> PolarsResult<Option<BatchStatistics>> {
+    let Some(metadata) = message
+        .custom_metadata()
+        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferMessage(err)))?
+    else {
+        return Ok(None);
+    };
+
+    let mut columns = PlHashMap::<PlSmallStr, ColumnStatistics>::default();
+    for kv in metadata {
+        let kv = kv?;
+        let (Some(key), Some(value)) = (kv.key()?, kv.value()?) else {
+            continue;
+        };
+        let Some((name, statistic)) = key
+            .strip_prefix(STATISTICS_PREFIX)
+            .and_then(|key| key.rsplit_once(':'))
+        else {
+            continue;
+        };
+        let column = columns.entry(name.into()).or_default();
+        match statistic {
+            "min" => column.min = Some(value.into()),
+            "max" => column.max = Some(value.into()),
+            "null_count" => column.null_count = value.parse().ok(),
+            _ => {},
+        }
+    }
+
+    Ok((!columns.is_empty()).then_some(BatchStatistics {
+        index,
+        length,
+        columns,
+    }))
+}
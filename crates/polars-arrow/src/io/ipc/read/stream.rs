@@ -1,15 +1,17 @@
+use std::collections::VecDeque;
 use std::io::Read;
 
 use arrow_format::ipc::planus::ReadAsRoot;
 use polars_error::{PolarsError, PolarsResult, polars_bail, polars_err};
 
-use super::super::CONTINUATION_MARKER;
+use super::super::{ARROW_MAGIC_V2, CONTINUATION_MARKER};
 use super::common::*;
-use super::schema::deserialize_stream_metadata;
-use super::{Dictionaries, OutOfSpecKind};
+use super::schema::{deserialize_stream_metadata, rewrite_schema};
+use super::{ComplexityBudget, Dictionaries, OutOfSpecKind, WriterInfo};
 use crate::array::Array;
 use crate::datatypes::{ArrowSchema, Metadata};
 use crate::io::ipc::IpcSchema;
+use crate::io::ipc::write::Compression;
 use crate::record_batch::RecordBatchT;
 
 /// Metadata of an Arrow IPC stream, written at the start of the stream
@@ -28,8 +30,35 @@ pub struct StreamMetadata {
     pub ipc_schema: IpcSchema,
 }
 
+impl StreamMetadata {
+    /// Rewrites the schema of the stream before any of it is decoded. See
+    /// [`FileMetadata::rewrite_schema`](super::FileMetadata::rewrite_schema).
+    pub fn rewrite_schema(
+        &mut self,
+        rewrite: impl FnOnce(ArrowSchema) -> PolarsResult<ArrowSchema>,
+    ) -> PolarsResult<()> {
+        self.schema = rewrite_schema(&self.schema, rewrite)?;
+        Ok(())
+    }
+
+    /// Returns what the writer of the stream recorded about how it was written. See
+    /// [`WriterInfo`].
+    pub fn writer_info(&self) -> PolarsResult<WriterInfo> {
+        WriterInfo::try_from_metadata(self.custom_schema_metadata.as_ref())
+    }
+}
+
 /// Reads the metadata of the stream
 pub fn read_stream_metadata(reader: &mut dyn std::io::Read) -> PolarsResult<StreamMetadata> {
+    read_stream_metadata_with_budget(reader, &Default::default())
+}
+
+/// Reads the metadata of the stream, erroring before its schema is deserialized if the schema
+/// exceeds `budget`.
+pub fn read_stream_metadata_with_budget(
+    reader: &mut dyn std::io::Read,
+    budget: &ComplexityBudget,
+) -> PolarsResult<StreamMetadata> {
     // determine metadata length
     let mut meta_size: [u8; 4] = [0; 4];
     reader.read_exact(&mut meta_size)?;
@@ -50,7 +79,21 @@ pub fn read_stream_metadata(reader: &mut dyn std::io::Read) -> PolarsResult<Stre
     buffer.try_reserve(length)?;
     reader.take(length as u64).read_to_end(&mut buffer)?;
 
-    deserialize_stream_metadata(&buffer)
+    deserialize_stream_metadata(&buffer, budget)
+}
+
+/// Reads the metadata of an Arrow IPC file whose footer is missing, e.g. because its writer
+/// crashed. The messages of the file can then be read with a [`StreamReader`], e.g. with
+/// [`ReadOptions::tolerate_truncation`] to read all record batches before a truncated one.
+pub fn read_unfinished_file_metadata(
+    reader: &mut dyn std::io::Read,
+) -> PolarsResult<StreamMetadata> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if magic[..6] != ARROW_MAGIC_V2 {
+        polars_bail!(oos = OutOfSpecKind::InvalidHeader)
+    }
+    read_stream_metadata(reader)
 }
 
 /// Encodes the stream's status after each read.
@@ -84,8 +127,46 @@ impl StreamState {
     }
 }
 
+/// Information about a record batch of a stream, once its header is read but before its
+/// columns are decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockInfo {
+    /// The position of the record batch among the record batches of the stream.
+    pub index: usize,
+    /// The number of rows of the record batch.
+    pub num_rows: usize,
+    /// The size in bytes of the body of the record batch.
+    pub body_length: usize,
+}
+
+/// What a [`StreamReader`] does with a record batch, as returned by its
+/// [`on_block`](StreamReader::with_on_block) callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlockAction {
+    /// Decode the record batch.
+    #[default]
+    Decode,
+    /// Skip over the body of the record batch without decoding it.
+    Skip,
+}
+
+type OnBlock = Box<dyn FnMut(BlockInfo) -> BlockAction + Send>;
+
+/// Describes the truncated tail of a stream that was read with
+/// [`ReadOptions::tolerate_truncation`], e.g. of a stream or file whose writer crashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Truncation {
+    /// The number of record batches of the stream before the truncated message.
+    pub block_index: usize,
+    /// The length in bytes that the truncated part of the message declares.
+    pub declared_length: usize,
+    /// The length in bytes of that part that was available.
+    pub available_length: usize,
+}
+
 /// Reads the next item, yielding `None` if the stream is done,
 /// and a [`StreamState`] otherwise.
+#[allow(clippy::too_many_arguments)]
 fn read_next<R: Read>(
     reader: &mut R,
     metadata: &StreamMetadata,
@@ -94,133 +175,179 @@ fn read_next<R: Read>(
     data_buffer: &mut Vec<u8>,
     projection: &Option<ProjectionInfo>,
     scratch: &mut Vec<u8>,
+    options: &ReadOptions,
+    block_index: &mut usize,
+    on_block: &mut Option<OnBlock>,
+    truncation: &mut Option<Truncation>,
+    dictionary_history: &mut DictionaryHistory,
+    row_count: &mut usize,
+    diagnostics: &mut ReadDiagnostics,
 ) -> PolarsResult<Option<StreamState>> {
-    // determine metadata length
-    let mut meta_length: [u8; 4] = [0; 4];
-
-    match reader.read_exact(&mut meta_length) {
-        Ok(()) => (),
-        Err(e) => {
-            return if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                // Handle EOF without the "0xFFFFFFFF 0x00000000"
-                // valid according to:
-                // https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format
-                Ok(Some(StreamState::Waiting))
-            } else {
-                Err(PolarsError::from(e))
-            };
-        },
-    }
-
-    let meta_length = {
-        // If a continuation marker is encountered, skip over it and read
-        // the size from the next four bytes.
-        if meta_length == CONTINUATION_MARKER {
-            reader.read_exact(&mut meta_length)?;
+    // whether `available` bytes of `declared` bytes end the stream as a truncated message
+    let mut is_truncated = |declared: usize, available: usize, block_index: usize| {
+        let is_truncated = options.tolerate_truncation && available < declared;
+        if is_truncated {
+            *truncation = Some(Truncation {
+                block_index,
+                declared_length: declared,
+                available_length: available,
+            });
         }
-        i32::from_le_bytes(meta_length)
+        is_truncated
     };
 
-    let meta_length: usize = meta_length
-        .try_into()
-        .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+    // read messages until we encounter a RecordBatch message that is decoded
+    loop {
+        // determine metadata length
+        let mut meta_length: [u8; 4] = [0; 4];
+
+        match reader.read_exact(&mut meta_length) {
+            Ok(()) => (),
+            Err(e) => {
+                return if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    // Handle EOF without the "0xFFFFFFFF 0x00000000"
+                    // valid according to:
+                    // https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format
+                    Ok(Some(StreamState::Waiting))
+                } else {
+                    Err(PolarsError::from(e))
+                };
+            },
+        }
 
-    if meta_length == 0 {
-        // the stream has ended, mark the reader as finished
-        return Ok(None);
-    }
+        let meta_length = {
+            // If a continuation marker is encountered, skip over it and read
+            // the size from the next four bytes.
+            if meta_length == CONTINUATION_MARKER {
+                reader.read_exact(&mut meta_length)?;
+            }
+            i32::from_le_bytes(meta_length)
+        };
 
-    message_buffer.clear();
-    message_buffer.try_reserve(meta_length)?;
-    reader
-        .by_ref()
-        .take(meta_length as u64)
-        .read_to_end(message_buffer)?;
+        let meta_length: usize = meta_length
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
 
-    let message = arrow_format::ipc::MessageRef::read_as_root(message_buffer.as_ref())
-        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferMessage(err)))?;
+        if meta_length == 0 {
+            // the stream has ended, mark the reader as finished
+            return Ok(None);
+        }
 
-    let header = message
-        .header()
-        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferHeader(err)))?
-        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageHeader))?;
+        message_buffer.clear();
+        message_buffer.try_reserve(meta_length)?;
+        reader
+            .by_ref()
+            .take(meta_length as u64)
+            .read_to_end(message_buffer)?;
+        if is_truncated(meta_length, message_buffer.len(), *block_index) {
+            return Ok(None);
+        }
 
-    let block_length: usize = message
-        .body_length()
-        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferBodyLength(err)))?
-        .try_into()
-        .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
-
-    match header {
-        arrow_format::ipc::MessageHeaderRef::RecordBatch(batch) => {
-            data_buffer.clear();
-            data_buffer.try_reserve(block_length)?;
-            reader
-                .by_ref()
-                .take(block_length as u64)
-                .read_to_end(data_buffer)?;
-
-            let file_size = data_buffer.len() as u64;
-
-            let mut reader = std::io::Cursor::new(data_buffer);
-
-            let chunk = read_record_batch(
-                batch,
-                &metadata.schema,
-                &metadata.ipc_schema,
-                projection.as_ref().map(|x| x.columns.as_ref()),
-                None,
-                dictionaries,
-                metadata.version,
-                &mut reader,
-                0,
-                file_size,
-                scratch,
-            );
-
-            if let Some(ProjectionInfo { map, .. }) = projection {
-                // re-order according to projection
-                chunk
-                    .map(|chunk| apply_projection(chunk, map))
-                    .map(|x| Some(StreamState::Some(x)))
-            } else {
-                chunk.map(|x| Some(StreamState::Some(x)))
-            }
-        },
-        arrow_format::ipc::MessageHeaderRef::DictionaryBatch(batch) => {
-            data_buffer.clear();
-            data_buffer.try_reserve(block_length)?;
-            reader
-                .by_ref()
-                .take(block_length as u64)
-                .read_to_end(data_buffer)?;
-
-            let file_size = data_buffer.len() as u64;
-            let mut dict_reader = std::io::Cursor::new(&data_buffer);
-
-            read_dictionary(
-                batch,
-                &metadata.schema,
-                &metadata.ipc_schema,
-                dictionaries,
-                &mut dict_reader,
-                0,
-                file_size,
-                scratch,
-            )?;
-
-            // read the next message until we encounter a RecordBatch message
-            read_next(
-                reader,
-                metadata,
-                dictionaries,
-                message_buffer,
-                data_buffer,
-                projection,
-                scratch,
-            )
-        },
-        _ => polars_bail!(oos = OutOfSpecKind::UnexpectedMessageType),
+        let message = arrow_format::ipc::MessageRef::read_as_root(message_buffer.as_ref())
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferMessage(err)))?;
+
+        let header = message
+            .header()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferHeader(err)))?
+            .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageHeader))?;
+
+        let block_length: usize = message
+            .body_length()
+            .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferBodyLength(err)))?
+            .try_into()
+            .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?;
+
+        match header {
+            arrow_format::ipc::MessageHeaderRef::RecordBatch(batch) => {
+                let info = BlockInfo {
+                    index: *block_index,
+                    num_rows: batch
+                        .length()
+                        .map_err(|_| polars_err!(oos = OutOfSpecKind::MissingData))?
+                        .try_into()
+                        .map_err(|_| polars_err!(oos = OutOfSpecKind::UnexpectedNegativeInteger))?,
+                    body_length: block_length,
+                };
+                if on_block.as_mut().map_or(BlockAction::Decode, |f| f(info)) == BlockAction::Skip {
+                    let skipped = std::io::copy(
+                        &mut reader.by_ref().take(block_length as u64),
+                        &mut std::io::sink(),
+                    )?;
+                    if is_truncated(block_length, skipped as usize, info.index) {
+                        return Ok(None);
+                    }
+                    *block_index += 1;
+                    *row_count += info.num_rows;
+                    continue;
+                }
+
+                data_buffer.clear();
+                data_buffer.try_reserve(block_length)?;
+                reader
+                    .by_ref()
+                    .take(block_length as u64)
+                    .read_to_end(data_buffer)?;
+                if is_truncated(block_length, data_buffer.len(), info.index) {
+                    return Ok(None);
+                }
+                *block_index += 1;
+
+                let file_size = data_buffer.len() as u64;
+
+                let mut reader = std::io::Cursor::new(data_buffer);
+
+                let chunk = read_record_batch_with_diagnostics(
+                    batch,
+                    &metadata.schema,
+                    &metadata.ipc_schema,
+                    projection.as_ref().map(|x| x.columns.as_ref()),
+                    None,
+                    dictionaries,
+                    metadata.version,
+                    &mut reader,
+                    0,
+                    file_size,
+                    scratch,
+                    &mut vec![],
+                    options,
+                    diagnostics,
+                );
+
+                return chunk.map(|x| Some(StreamState::Some(x)));
+            },
+            arrow_format::ipc::MessageHeaderRef::DictionaryBatch(batch) => {
+                data_buffer.clear();
+                data_buffer.try_reserve(block_length)?;
+                reader
+                    .by_ref()
+                    .take(block_length as u64)
+                    .read_to_end(data_buffer)?;
+                if is_truncated(block_length, data_buffer.len(), *block_index) {
+                    return Ok(None);
+                }
+
+                let dictionary_index = dictionary_history.values().map(Vec::len).sum();
+                record_dictionary_event(dictionary_history, batch, dictionary_index)?;
+
+                let file_size = data_buffer.len() as u64;
+                let mut dict_reader = std::io::Cursor::new(&data_buffer);
+
+                read_dictionary(
+                    batch,
+                    &metadata.schema,
+                    &metadata.ipc_schema,
+                    dictionaries,
+                    metadata.version,
+                    &mut dict_reader,
+                    0,
+                    file_size,
+                    scratch,
+                    options,
+                    diagnostics,
+                )?;
+            },
+            _ => polars_bail!(oos = OutOfSpecKind::UnexpectedMessageType),
+        }
     }
 }
 
@@ -234,11 +361,26 @@ pub struct StreamReader<R: Read> {
     reader: R,
     metadata: StreamMetadata,
     dictionaries: Dictionaries,
+    dictionary_history: DictionaryHistory,
     finished: bool,
     data_buffer: Vec<u8>,
     message_buffer: Vec<u8>,
     projection: Option<ProjectionInfo>,
+    options: ReadOptions,
     scratch: Vec<u8>,
+    block_index: usize,
+    on_block: Option<OnBlock>,
+    truncation: Option<Truncation>,
+    // the output batches that remain of a split record batch
+    pending: VecDeque<RecordBatchT<Box<dyn Array>>>,
+    // the number of rows of the record batches that were read or skipped
+    row_count: usize,
+    // the codec of the last record batch that was read
+    compression: Option<Compression>,
+    // the number of record batches that were decoded
+    batch_count: usize,
+    // the diagnostics of the messages that were read
+    diagnostics: ReadDiagnostics,
 }
 
 impl<R: Read> StreamReader<R> {
@@ -255,34 +397,125 @@ impl<R: Read> StreamReader<R> {
             reader,
             metadata,
             dictionaries: Default::default(),
+            dictionary_history: Default::default(),
             finished: false,
             data_buffer: Default::default(),
             message_buffer: Default::default(),
             projection,
+            options: Default::default(),
             scratch: Default::default(),
+            block_index: 0,
+            on_block: None,
+            truncation: None,
+            pending: Default::default(),
+            row_count: 0,
+            compression: None,
+            batch_count: 0,
+            diagnostics: Default::default(),
         }
     }
 
+    /// Sets the [`ReadOptions`] of this reader.
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.projection =
+            options_projection(&self.metadata.schema, self.projection.take(), &options);
+        self.options = options;
+        self
+    }
+
+    /// Sets a callback that is invoked for every record batch once its header is read, but
+    /// before its columns are decoded. Record batches for which it returns
+    /// [`BlockAction::Skip`] are skipped over without being decoded nor yielded.
+    pub fn with_on_block(
+        mut self,
+        on_block: impl FnMut(BlockInfo) -> BlockAction + Send + 'static,
+    ) -> Self {
+        self.on_block = Some(Box::new(on_block));
+        self
+    }
+
+    /// Returns the [`ReadOptions`] of this reader.
+    pub fn options(&self) -> &ReadOptions {
+        &self.options
+    }
+
     /// Return the schema of the stream
     pub fn metadata(&self) -> &StreamMetadata {
         &self.metadata
     }
 
+    /// Returns the [`IpcSchema`] of the stream, whose [`IpcField`]s hold the dictionary ids of
+    /// the fields of the stream, including of their nested fields. Its fields are the fields of
+    /// the stream, regardless of any projection.
+    ///
+    /// [`IpcField`]: crate::io::ipc::IpcField
+    pub fn ipc_schema(&self) -> &IpcSchema {
+        &self.metadata.ipc_schema
+    }
+
     /// Return the schema of the file
     pub fn schema(&self) -> &ArrowSchema {
+        if let Some(schema) = &self.options.reader_schema {
+            return schema;
+        }
         self.projection
             .as_ref()
             .map(|x| &x.schema)
             .unwrap_or(&self.metadata.schema)
     }
 
+    /// Returns the dictionaries read so far.
+    pub fn dictionaries(&self) -> &Dictionaries {
+        &self.dictionaries
+    }
+
+    /// Returns the number of values of the dictionary with id `id` read so far. See
+    /// [`FileReader::dictionary_cardinality`](super::FileReader::dictionary_cardinality).
+    pub fn dictionary_cardinality(&self, id: i64) -> Option<usize> {
+        self.dictionaries.get(&id).map(|values| values.len())
+    }
+
+    /// Returns how the dictionary with id `id` was built from the dictionary batches read so far.
+    pub fn dictionary_history(&self, id: i64) -> &[DictionaryEvent] {
+        self.dictionary_history
+            .get(&id)
+            .map_or(&[], |events| events)
+    }
+
+    /// Returns the truncated tail of the stream, once this reader stopped at it. See
+    /// [`ReadOptions::tolerate_truncation`].
+    pub fn truncation(&self) -> Option<&Truncation> {
+        self.truncation.as_ref()
+    }
+
+    /// Returns the codec that the last record batch that was read declares, e.g. to re-encode it
+    /// with the same codec; `None` if it is not compressed or no record batch was read yet.
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
+    /// Takes the [`ReadDiagnostics`] of the messages read so far. See
+    /// [`FileReader::take_diagnostics`](super::FileReader::take_diagnostics).
+    pub fn take_diagnostics(&mut self) -> ReadDiagnostics {
+        std::mem::take(&mut self.diagnostics)
+    }
+
     /// Check if the stream is finished
     pub fn is_finished(&self) -> bool {
         self.finished
     }
 
+    /// Returns a mutable reference to the underlying reader, e.g. to append the messages of a
+    /// stream that are read asynchronously.
+    pub(super) fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
     fn maybe_next(&mut self) -> PolarsResult<Option<StreamState>> {
-        if self.finished {
+        if let Some(chunk) = self.pending.pop_front() {
+            return Ok(Some(StreamState::Some(chunk)));
+        }
+        if self.finished || self.options.reached_max_batches(self.batch_count) {
             return Ok(None);
         }
         let batch = read_next(
@@ -293,11 +526,35 @@ impl<R: Read> StreamReader<R> {
             &mut self.data_buffer,
             &self.projection,
             &mut self.scratch,
+            &self.options,
+            &mut self.block_index,
+            &mut self.on_block,
+            &mut self.truncation,
+            &mut self.dictionary_history,
+            &mut self.row_count,
+            &mut self.diagnostics,
         )?;
-        if batch.is_none() {
-            self.finished = true;
+        match batch {
+            Some(StreamState::Some(chunk)) => {
+                self.compression = batch_compression(&self.message_buffer);
+                self.batch_count += 1;
+                let row = self.row_count;
+                self.row_count += chunk.len();
+                let chunk = prepend_row_index(chunk, row, &self.options)?;
+                let chunk = apply_options(chunk, &self.options)?;
+                if let Some(max_rows) = self.options.max_rows_per_output_batch {
+                    self.pending = split_batch(chunk, max_rows, self.options.contiguous);
+                    Ok(self.pending.pop_front().map(StreamState::Some))
+                } else {
+                    Ok(Some(StreamState::Some(chunk)))
+                }
+            },
+            None => {
+                self.finished = true;
+                Ok(None)
+            },
+            batch => Ok(batch),
         }
-        Ok(batch)
     }
 }
 
@@ -0,0 +1,236 @@
+//! Async reading of Arrow IPC streams, e.g. from a socket.
+use std::collections::VecDeque;
+
+use arrow_format::ipc::planus::ReadAsRoot;
+use futures::Stream;
+use polars_error::{PolarsResult, polars_err};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::super::CONTINUATION_MARKER;
+use super::schema::deserialize_stream_metadata;
+use super::{OutOfSpecKind, ReadOptions, StreamMetadata, StreamReader, StreamState};
+use crate::array::Array;
+use crate::datatypes::ArrowSchema;
+use crate::record_batch::RecordBatchT;
+
+/// Reads the length of the metadata of the next message of `reader`, skipping its continuation
+/// marker, and appends its bytes to `message`. Returns `None` at the end of `reader`.
+async fn read_message_length<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    message: &mut Vec<u8>,
+) -> PolarsResult<Option<i32>> {
+    let mut meta_length: [u8; 4] = [0; 4];
+    let start = message.len();
+    reader.take(4).read_to_end(message).await?;
+    if message.len() - start < 4 {
+        return Ok(None);
+    }
+    meta_length.copy_from_slice(&message[start..]);
+    if meta_length == CONTINUATION_MARKER {
+        reader.take(4).read_to_end(message).await?;
+        if message.len() - start < 8 {
+            return Ok(None);
+        }
+        meta_length.copy_from_slice(&message[start + 4..]);
+    }
+    Ok(Some(i32::from_le_bytes(meta_length)))
+}
+
+/// Reads the metadata of the stream of `reader`, without blocking. See
+/// [`read_stream_metadata`](super::read_stream_metadata).
+pub async fn read_stream_metadata_async<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> PolarsResult<StreamMetadata> {
+    let length = read_message_length(reader, &mut vec![])
+        .await?
+        .ok_or_else(|| polars_err!(oos = OutOfSpecKind::MissingMessageHeader))?;
+    let length: usize = length
+        .try_into()
+        .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+
+    let mut buffer = vec![];
+    buffer.try_reserve(length)?;
+    reader.take(length as u64).read_to_end(&mut buffer).await?;
+
+    deserialize_stream_metadata(&buffer, &Default::default())
+}
+
+/// Reads the next message of `reader`, as is, into `message`. Returns whether it was read
+/// completely, i.e. `false` if `reader` ended before or within it.
+async fn read_message<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    message: &mut Vec<u8>,
+) -> PolarsResult<bool> {
+    message.clear();
+    let Some(length) = read_message_length(reader, message).await? else {
+        return Ok(false);
+    };
+    // the end of the stream, or an invalid length that the decoding errors on
+    let Ok(length @ 1..) = usize::try_from(length) else {
+        return Ok(true);
+    };
+    let start = message.len();
+    message.try_reserve(length)?;
+    reader.take(length as u64).read_to_end(message).await?;
+    if message.len() - start < length {
+        return Ok(false);
+    }
+
+    // the body is only read if the message is valid, else the decoding errors on it
+    let Ok(body_length) = arrow_format::ipc::MessageRef::read_as_root(&message[start..])
+        .and_then(|message| message.body_length())
+    else {
+        return Ok(true);
+    };
+    let Ok(body_length) = usize::try_from(body_length) else {
+        return Ok(true);
+    };
+    let start = message.len();
+    message.try_reserve(body_length)?;
+    reader.take(body_length as u64).read_to_end(message).await?;
+    Ok(message.len() - start == body_length)
+}
+
+/// An async reader of the [`RecordBatchT`]s of an Arrow IPC stream.
+///
+/// It reads every message of the stream without blocking and decodes it with a
+/// [`StreamReader`], such that it reads a stream as a [`StreamReader`] does, with the same
+/// [`ReadOptions`].
+pub struct AsyncStreamReader<R: AsyncRead + Unpin> {
+    reader: R,
+    // decodes the messages that were read
+    inner: StreamReader<VecDeque<u8>>,
+    message: Vec<u8>,
+    // whether `reader` ended
+    exhausted: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncStreamReader<R> {
+    /// Creates a new [`AsyncStreamReader`] of the stream of `metadata`, e.g. as read by
+    /// [`read_stream_metadata_async`]. Use `projection` to only take certain columns.
+    pub fn new(reader: R, metadata: StreamMetadata, projection: Option<Vec<usize>>) -> Self {
+        Self {
+            reader,
+            inner: StreamReader::new(VecDeque::new(), metadata, projection),
+            message: vec![],
+            exhausted: false,
+        }
+    }
+
+    /// Sets the [`ReadOptions`] of this reader.
+    pub fn with_options(mut self, options: ReadOptions) -> Self {
+        self.inner = self.inner.with_options(options);
+        self
+    }
+
+    /// Returns the metadata of the stream.
+    pub fn metadata(&self) -> &StreamMetadata {
+        self.inner.metadata()
+    }
+
+    /// Returns the schema of the record batches of this reader.
+    pub fn schema(&self) -> &ArrowSchema {
+        self.inner.schema()
+    }
+
+    /// Whether the stream ended with its end-of-stream marker.
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    /// Consumes this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next record batch of the stream, or `None` once the stream ended.
+    pub async fn next_batch(&mut self) -> PolarsResult<Option<RecordBatchT<Box<dyn Array>>>> {
+        loop {
+            match self.inner.next().transpose()? {
+                Some(StreamState::Some(chunk)) => return Ok(Some(chunk)),
+                None => return Ok(None),
+                // the messages that were read are decoded, read the next one
+                Some(StreamState::Waiting) => {
+                    if self.exhausted {
+                        return Ok(None);
+                    }
+                    self.exhausted = !read_message(&mut self.reader, &mut self.message).await?;
+                    self.inner.get_mut().extend(&self.message);
+                },
+            }
+        }
+    }
+
+    /// Converts this reader into a [`Stream`] of its record batches.
+    pub fn into_stream(self) -> impl Stream<Item = PolarsResult<RecordBatchT<Box<dyn Array>>>> {
+        futures::stream::try_unfold(self, |mut reader| async move {
+            Ok(reader.next_batch().await?.map(|chunk| (chunk, reader)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::{DictionaryArray, PrimitiveArray, Utf8Array};
+    use crate::datatypes::Field;
+    use crate::io::ipc::write::{StreamWriter, WriteOptions};
+
+    #[tokio::test]
+    async fn test_async_stream_reader() -> PolarsResult<()> {
+        let dictionary = |keys: &[u32], values: &[&str]| -> PolarsResult<Box<dyn Array>> {
+            let keys = PrimitiveArray::<u32>::from_slice(keys);
+            let values = Utf8Array::<i64>::from_slice(values).boxed();
+            Ok(DictionaryArray::try_from_keys(keys, values)?.boxed())
+        };
+        // a dictionary, a delta of it and its replacement
+        let columns = [
+            dictionary(&[0, 1], &["a", "b"])?,
+            dictionary(&[2, 0], &["a", "b", "c"])?,
+            dictionary(&[0], &["d"])?,
+        ];
+        let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+            "c0".into(),
+            columns[0].dtype().clone(),
+            true,
+        )]));
+        let batches = columns
+            .into_iter()
+            .map(|column| RecordBatchT::try_new(column.len(), schema.clone(), vec![column]))
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+        writer.set_delta_dictionaries(true);
+        writer.start(&schema, None)?;
+        for batch in &batches {
+            writer.write(batch, None)?;
+        }
+        writer.finish()?;
+        let data = writer.into_inner();
+        // without the end-of-stream marker
+        let unfinished = &data[..data.len() - 8];
+
+        let mut reader = data.as_slice();
+        let metadata = read_stream_metadata_async(&mut reader).await?;
+        let mut reader = AsyncStreamReader::new(reader, metadata, None);
+        for batch in &batches {
+            assert_eq!(reader.next_batch().await?.as_ref(), Some(batch));
+        }
+        assert!(reader.next_batch().await?.is_none());
+        assert!(reader.is_finished());
+
+        // a stream without its end-of-stream marker ends with its data
+        let mut reader = unfinished;
+        let metadata = read_stream_metadata_async(&mut reader).await?;
+        let read = AsyncStreamReader::new(reader, metadata, None)
+            .into_stream()
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(read, batches);
+        Ok(())
+    }
+}
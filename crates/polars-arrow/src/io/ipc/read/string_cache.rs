@@ -0,0 +1,141 @@
+use std::sync::{Arc, Mutex};
+
+use polars_utils::aliases::PlHashMap;
+
+use crate::array::{Array, Utf8Array, Utf8ViewArray, View};
+use crate::buffer::Buffer;
+use crate::datatypes::{ArrowDataType, ArrowSchema};
+use crate::offset::Offset;
+use crate::record_batch::RecordBatchT;
+
+#[derive(Debug, Default)]
+struct InternedStrings {
+    // the views of the interned strings that are too long to be inlined in their views
+    views: PlHashMap<Box<str>, View>,
+    buffers: Vec<Buffer<u8>>,
+    // the buffers of `buffers`, shared by the arrays returned since the last interned string
+    shared: Option<Arc<[Buffer<u8>]>>,
+}
+
+/// A thread-safe cache of interned strings, shared across the batches of one or many readers.
+///
+/// Set as [`ReadOptions::string_cache`], the `Utf8` and `LargeUtf8` columns of every batch are
+/// read as `Utf8View` arrays whose values point into the buffers of this cache, such that a
+/// string that repeats across batches is only stored once. This is most valuable for
+/// low-cardinality string columns that are not dictionary-encoded.
+///
+/// The cache only grows: every distinct string that was read remains stored.
+///
+/// [`ReadOptions::string_cache`]: super::ReadOptions::string_cache
+#[derive(Debug, Default)]
+pub struct StringCache {
+    strings: Mutex<InternedStrings>,
+}
+
+impl StringCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of interned strings that are too long to be inlined in their views.
+    pub fn len(&self) -> usize {
+        self.strings.lock().unwrap().views.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total length in bytes of the interned strings.
+    pub fn total_bytes(&self) -> usize {
+        let strings = self.strings.lock().unwrap();
+        strings.buffers.iter().map(|buffer| buffer.len()).sum()
+    }
+
+    /// Returns `array` as a `Utf8View` array whose values are interned in this cache.
+    pub fn intern<O: Offset>(&self, array: &Utf8Array<O>) -> Utf8ViewArray {
+        let mut strings = self.strings.lock().unwrap();
+        let strings = &mut *strings;
+        let buffer_idx = strings.buffers.len() as u32;
+        let mut pending = vec![];
+
+        let mut total_bytes_len = 0;
+        let views = array
+            .iter()
+            .map(|value| {
+                let Some(value) = value else {
+                    return View::default();
+                };
+                total_bytes_len += value.len();
+                if value.len() <= View::MAX_INLINE_SIZE as usize {
+                    return View::new_inline(value.as_bytes());
+                }
+                if let Some(view) = strings.views.get(value) {
+                    return *view;
+                }
+                let view = View::new_from_bytes(value.as_bytes(), buffer_idx, pending.len() as u32);
+                pending.extend_from_slice(value.as_bytes());
+                strings.views.insert(value.into(), view);
+                view
+            })
+            .collect::<Vec<_>>();
+
+        if !pending.is_empty() {
+            strings.buffers.push(Buffer::from(pending));
+            strings.shared = None;
+        }
+        let buffers = strings
+            .shared
+            .get_or_insert_with(|| strings.buffers.clone().into())
+            .clone();
+        let total_buffer_len = buffers.iter().map(|buffer| buffer.len()).sum();
+
+        // SAFETY: the views point to valid utf8 values of the buffers
+        unsafe {
+            Utf8ViewArray::new_unchecked(
+                ArrowDataType::Utf8View,
+                views.into(),
+                buffers,
+                array.validity().cloned(),
+                total_bytes_len,
+                total_buffer_len,
+            )
+        }
+    }
+}
+
+/// Sets the type of the `Utf8` and `LargeUtf8` fields of `schema` to `Utf8View`, the type they
+/// are read as with a [`StringCache`].
+pub(crate) fn relabel_interned_schema(schema: &mut ArrowSchema) {
+    for field in schema.iter_values_mut() {
+        if matches!(field.dtype, ArrowDataType::Utf8 | ArrowDataType::LargeUtf8) {
+            field.dtype = ArrowDataType::Utf8View;
+        }
+    }
+}
+
+/// Interns the `Utf8` and `LargeUtf8` columns of `chunk` in `cache`.
+pub(crate) fn intern_strings(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    cache: &StringCache,
+) -> RecordBatchT<Box<dyn Array>> {
+    let length = chunk.len();
+    let (schema, arrays) = chunk.into_schema_and_arrays();
+    let arrays = arrays
+        .into_iter()
+        .map(|array| match array.dtype() {
+            ArrowDataType::Utf8 => {
+                let array = array.as_any().downcast_ref::<Utf8Array<i32>>().unwrap();
+                cache.intern(array).boxed()
+            },
+            ArrowDataType::LargeUtf8 => {
+                let array = array.as_any().downcast_ref::<Utf8Array<i64>>().unwrap();
+                cache.intern(array).boxed()
+            },
+            _ => array,
+        })
+        .collect();
+    let mut schema = Arc::unwrap_or_clone(schema);
+    relabel_interned_schema(&mut schema);
+    RecordBatchT::new(length, Arc::new(schema), arrays)
+}
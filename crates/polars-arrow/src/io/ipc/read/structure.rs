@@ -0,0 +1,91 @@
+use polars_error::{PolarsResult, polars_bail, polars_err};
+
+use super::{Node, OutOfSpecKind};
+use crate::datatypes::{ArrowDataType, ArrowSchema};
+
+/// Errors with the path of the field at which `reason` diverges the field nodes from the
+/// schema.
+fn diverged(path: &str, reason: impl std::fmt::Display) -> polars_error::PolarsError {
+    polars_err!(
+        oos = format!(
+            "IPC: the field nodes of the record batch diverge from the schema at field '{path}': {reason}"
+        )
+    )
+}
+
+/// Takes the field nodes of the field `path` of type `dtype` and of its nested fields from
+/// `nodes`, checking that there is one for each and that their lengths agree with the length
+/// of their parent, when it implies theirs.
+fn check_field<'a>(
+    dtype: &ArrowDataType,
+    path: &str,
+    expected_length: Option<usize>,
+    nodes: &mut impl Iterator<Item = Node<'a>>,
+) -> PolarsResult<()> {
+    let node = nodes
+        .next()
+        .ok_or_else(|| diverged(path, "the record batch has no field node for it"))?;
+    let length: usize = node
+        .length()
+        .try_into()
+        .map_err(|_| polars_err!(oos = OutOfSpecKind::NegativeFooterLength))?;
+    if let Some(expected_length) = expected_length.filter(|&expected| expected != length) {
+        return Err(diverged(
+            path,
+            format!("its field node has {length} rows but its parent implies {expected_length}"),
+        ));
+    }
+
+    let child = |name: &str| format!("{path}.{name}");
+    match dtype.to_logical_type() {
+        ArrowDataType::Struct(fields) => {
+            for field in fields {
+                check_field(&field.dtype, &child(&field.name), Some(length), nodes)?;
+            }
+        },
+        ArrowDataType::List(field)
+        | ArrowDataType::LargeList(field)
+        | ArrowDataType::Map(field, _) => {
+            check_field(&field.dtype, &child(&field.name), None, nodes)?;
+        },
+        ArrowDataType::FixedSizeList(field, size) => {
+            let length = length.checked_mul(*size);
+            check_field(&field.dtype, &child(&field.name), length, nodes)?;
+        },
+        ArrowDataType::Union(union) => {
+            let length = union.mode.is_sparse().then_some(length);
+            for field in &union.fields {
+                check_field(&field.dtype, &child(&field.name), length, nodes)?;
+            }
+        },
+        // the values of a dictionary are in dictionary batches
+        _ => {},
+    }
+    Ok(())
+}
+
+/// Checks that the field nodes of a record batch of `length` rows match the structure of the
+/// `fields`: that there is one node for every field, including nested fields, and that the
+/// lengths of the nodes of the fields of structs, sparse unions and fixed-size lists agree with
+/// the length of their parent. See [`ReadOptions::validate_structure`].
+///
+/// [`ReadOptions::validate_structure`]: super::ReadOptions::validate_structure
+pub(crate) fn check_field_nodes<'a>(
+    fields: &ArrowSchema,
+    length: usize,
+    nodes: impl IntoIterator<Item = Node<'a>>,
+) -> PolarsResult<()> {
+    let mut nodes = nodes.into_iter();
+    for field in fields.iter_values() {
+        check_field(&field.dtype, &field.name, Some(length), &mut nodes)?;
+    }
+    let remaining = nodes.count();
+    if remaining > 0 {
+        polars_bail!(
+            oos = format!(
+                "IPC: the record batch has {remaining} more field nodes than its schema has fields"
+            )
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use polars_error::PolarsResult;
+use polars_utils::pl_str::PlSmallStr;
+
+use crate::array::{Array, FixedSizeListArray, ListArray, PrimitiveArray, StructArray};
+use crate::datatypes::{ArrowDataType, ArrowSchema, TimeUnit};
+use crate::offset::Offset;
+use crate::record_batch::RecordBatchT;
+
+/// Sets the timezone of every `Timestamp` field of `schema` that has a timezone to `tz`,
+/// including the fields nested in lists and structs.
+pub(crate) fn relabel_schema(schema: &mut ArrowSchema, tz: &PlSmallStr) {
+    for field in schema.iter_values_mut() {
+        relabel_dtype(&mut field.dtype, tz);
+    }
+}
+
+/// Sets the timezone of the zoned timestamps of `dtype` to `tz`, and returns whether it
+/// contains any.
+///
+/// Naive timestamps have no instant to keep and are left as they are. Timestamps in
+/// dictionaries, maps and unions are not relabeled.
+fn relabel_dtype(dtype: &mut ArrowDataType, tz: &PlSmallStr) -> bool {
+    match dtype {
+        ArrowDataType::Timestamp(_, Some(from)) => {
+            *from = tz.clone();
+            true
+        },
+        ArrowDataType::List(field)
+        | ArrowDataType::LargeList(field)
+        | ArrowDataType::FixedSizeList(field, _) => relabel_dtype(&mut field.dtype, tz),
+        ArrowDataType::Struct(fields) => fields.iter_mut().fold(false, |relabeled, field| {
+            relabel_dtype(&mut field.dtype, tz) | relabeled
+        }),
+        _ => false,
+    }
+}
+
+/// Sets the timezone of every zoned `Timestamp` column of `chunk` to `tz`, including the
+/// columns nested in lists and structs.
+///
+/// Timestamps are stored relative to the UTC epoch, so relabeling keeps the instant of every
+/// value. When `shift` is set, the values are instead shifted to keep their wall-clock time.
+pub(crate) fn coerce_timestamp_tz(
+    chunk: RecordBatchT<Box<dyn Array>>,
+    tz: &PlSmallStr,
+    shift: bool,
+) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+    let length = chunk.len();
+    let (schema, arrays) = chunk.into_schema_and_arrays();
+    let mut schema = Arc::unwrap_or_clone(schema);
+
+    let arrays = schema
+        .iter_values_mut()
+        .zip(arrays)
+        .map(|(field, array)| {
+            let mut dtype = field.dtype.clone();
+            if !relabel_dtype(&mut dtype, tz) {
+                return Ok(array);
+            }
+            let array = coerce_array(array.as_ref(), dtype, tz, shift)?;
+            field.dtype = array.dtype().clone();
+            Ok(array)
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    RecordBatchT::try_new(length, Arc::new(schema), arrays)
+}
+
+/// Converts `array` to `dtype`, the relabeled data type of [`relabel_dtype`].
+fn coerce_array(
+    array: &dyn Array,
+    dtype: ArrowDataType,
+    tz: &str,
+    shift: bool,
+) -> PolarsResult<Box<dyn Array>> {
+    Ok(match (array.dtype(), &dtype) {
+        (ArrowDataType::Timestamp(unit, Some(from)), _) => {
+            let array = array
+                .as_any()
+                .downcast_ref::<PrimitiveArray<i64>>()
+                .unwrap();
+            let array = if shift {
+                shift_timestamps(array, *unit, from, tz)?
+            } else {
+                array.clone()
+            };
+            array.to(dtype).boxed()
+        },
+        (ArrowDataType::List(_), ArrowDataType::List(_)) => {
+            coerce_list::<i32>(array, dtype, tz, shift)?
+        },
+        (ArrowDataType::LargeList(_), ArrowDataType::LargeList(_)) => {
+            coerce_list::<i64>(array, dtype, tz, shift)?
+        },
+        (ArrowDataType::FixedSizeList(..), ArrowDataType::FixedSizeList(field, _)) => {
+            let array = array.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+            let values = coerce_array(array.values().as_ref(), field.dtype.clone(), tz, shift)?;
+            FixedSizeListArray::new(dtype, array.len(), values, array.validity().cloned()).boxed()
+        },
+        (ArrowDataType::Struct(_), ArrowDataType::Struct(fields)) => {
+            let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+            let values = array
+                .values()
+                .iter()
+                .zip(fields)
+                .map(|(values, field)| {
+                    coerce_array(values.as_ref(), field.dtype.clone(), tz, shift)
+                })
+                .collect::<PolarsResult<Vec<_>>>()?;
+            StructArray::new(dtype, array.len(), values, array.validity().cloned()).boxed()
+        },
+        _ => array.to_boxed(),
+    })
+}
+
+fn coerce_list<O: Offset>(
+    array: &dyn Array,
+    dtype: ArrowDataType,
+    tz: &str,
+    shift: bool,
+) -> PolarsResult<Box<dyn Array>> {
+    let array = array.as_any().downcast_ref::<ListArray<O>>().unwrap();
+    let child = ListArray::<O>::get_child_type(&dtype).clone();
+    let values = coerce_array(array.values().as_ref(), child, tz, shift)?;
+    let validity = array.validity().cloned();
+    Ok(ListArray::new(dtype, array.offsets().clone(), values, validity).boxed())
+}
+
+/// Shifts `array` from the timezone `from` to `to`, keeping the wall-clock time of every
+/// value.
+#[cfg(feature = "timezones")]
+fn shift_timestamps(
+    array: &PrimitiveArray<i64>,
+    unit: TimeUnit,
+    from: &str,
+    to: &str,
+) -> PolarsResult<PrimitiveArray<i64>> {
+    use polars_error::polars_err;
+
+    use crate::legacy::kernels::{Ambiguous, NonExistent, convert_to_naive_local};
+    use crate::temporal_conversions::{parse_offset_tz, timestamp_to_naive_datetime};
+
+    let from = parse_offset_tz(from)?;
+    let to_tz = parse_offset_tz(to)?;
+
+    let values = array
+        .iter()
+        .map(|value| {
+            value
+                .map(|&value| {
+                    let datetime = timestamp_to_naive_datetime(value, unit);
+                    let datetime = convert_to_naive_local(
+                        &from,
+                        &to_tz,
+                        datetime,
+                        Ambiguous::Earliest,
+                        NonExistent::Raise,
+                    )?
+                    .unwrap()
+                    .and_utc();
+                    Ok(match unit {
+                        TimeUnit::Second => datetime.timestamp(),
+                        TimeUnit::Millisecond => datetime.timestamp_millis(),
+                        TimeUnit::Microsecond => datetime.timestamp_micros(),
+                        TimeUnit::Nanosecond => datetime.timestamp_nanos_opt().ok_or_else(
+                            || polars_err!(ComputeError: "timestamp out of range for nanoseconds"),
+                        )?,
+                    })
+                })
+                .transpose()
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    Ok(PrimitiveArray::from(values))
+}
+
+#[cfg(not(feature = "timezones"))]
+fn shift_timestamps(
+    _array: &PrimitiveArray<i64>,
+    _unit: TimeUnit,
+    _from: &str,
+    _to: &str,
+) -> PolarsResult<PrimitiveArray<i64>> {
+    polars_error::polars_bail!(ComputeError:
+        "IPC: shifting timestamps to another timezone requires the `timezones` feature"
+    )
+}
@@ -0,0 +1,86 @@
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use polars_error::{PolarsResult, polars_ensure};
+
+use super::{FileReader, read_file_metadata};
+
+/// A reader of the `[start, start + len)` window of another reader, e.g. of an IPC file that is
+/// embedded in a larger archive. Positions are relative to the start of the window and its end
+/// is the end of the window, such that the window reads as a file of its own.
+#[derive(Debug)]
+pub struct Window<R> {
+    reader: R,
+    start: u64,
+    len: u64,
+    // the position relative to `start`
+    position: u64,
+}
+
+impl<R: Seek> Window<R> {
+    /// Creates a reader of the `[start, start + len)` window of `reader`, positioned at its start.
+    pub fn try_new(mut reader: R, start: u64, len: u64) -> PolarsResult<Self> {
+        let size = reader.seek(SeekFrom::End(0))?;
+        polars_ensure!(
+            start.checked_add(len).is_some_and(|end| end <= size),
+            ComputeError: "IPC: the window of {len} bytes at {start} exceeds the {size} bytes of the reader"
+        );
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(Self {
+            reader,
+            start,
+            len,
+            position: 0,
+        })
+    }
+}
+
+impl<R> Window<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Read for Window<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let remaining = self.len.saturating_sub(self.position);
+        let length = (buf.len() as u64).min(remaining) as usize;
+        let read = self.reader.read(&mut buf[..length])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Seek for Window<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+        };
+        let position = position.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "seek to a position before the start of the window",
+            )
+        })?;
+        self.reader.seek(SeekFrom::Start(self.start + position))?;
+        self.position = position;
+        Ok(position)
+    }
+}
+
+/// Reads the IPC file embedded in the `[start, start + len)` window of `reader`, without copying
+/// it out: its magic and footer are located relative to the end of the window and the offsets of
+/// its blocks relative to the start of the window.
+///
+/// Use [`Window`] directly to read with a projection.
+pub fn read_window<R: Read + Seek>(
+    reader: R,
+    start: u64,
+    len: u64,
+) -> PolarsResult<FileReader<Window<R>>> {
+    let mut reader = Window::try_new(reader, start, len)?;
+    let metadata = read_file_metadata(&mut reader)?;
+    Ok(FileReader::new(reader, metadata, None, None))
+}
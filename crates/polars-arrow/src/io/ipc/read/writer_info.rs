@@ -0,0 +1,39 @@
+use polars_error::{PolarsResult, polars_err};
+
+use crate::datatypes::Metadata;
+use crate::io::ipc::write::Compression;
+
+/// The key of the custom schema metadata that records the level that the writer of a file or
+/// stream compressed its buffers at. The IPC format itself does not record it.
+pub const COMPRESSION_LEVEL_KEY: &str = "polars.compression.level";
+
+/// What the writer of a file or stream recorded in its custom schema metadata about how it was
+/// written, e.g. to re-encode it with the same settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WriterInfo {
+    /// The compression level of [`COMPRESSION_LEVEL_KEY`], if recorded.
+    pub compression_level: Option<i32>,
+}
+
+impl WriterInfo {
+    /// Parses the [`WriterInfo`] of the custom schema metadata `metadata`.
+    pub fn try_from_metadata(metadata: Option<&Metadata>) -> PolarsResult<Self> {
+        let compression_level = metadata
+            .and_then(|metadata| metadata.get(COMPRESSION_LEVEL_KEY))
+            .map(|level| {
+                level.parse().map_err(|_| {
+                    polars_err!(ComputeError: "IPC: the compression level \"{level}\" of \"{COMPRESSION_LEVEL_KEY}\" is not an integer")
+                })
+            })
+            .transpose()?;
+        Ok(Self { compression_level })
+    }
+
+    /// Returns the recorded compression level, or else the default level of `codec`.
+    pub fn compression_level_or_default(&self, codec: Compression) -> i32 {
+        self.compression_level.unwrap_or(match codec {
+            Compression::LZ4 => 0,
+            Compression::ZSTD => 3,
+        })
+    }
+}
@@ -3,9 +3,10 @@ use std::borrow::{Borrow, Cow};
 use arrow_format::ipc;
 use arrow_format::ipc::planus::Builder;
 use polars_error::{PolarsResult, polars_bail, polars_err};
+use polars_utils::aliases::PlHashMap;
 
 use super::super::IpcField;
-use super::{write, write_dictionary};
+use super::{mark_uncompressed, write, write_dictionary};
 use crate::array::*;
 use crate::datatypes::*;
 use crate::io::ipc::endianness::is_native_little_endian;
@@ -32,12 +33,38 @@ pub struct WriteOptions {
     pub compression: Option<Compression>,
 }
 
-/// Find the dictionary that are new and need to be encoded.
+/// The compression of individual columns of a record batch, by column index, that overrides
+/// [`WriteOptions::compression`]: the codec of a column, or `None` to leave it uncompressed.
+pub type CompressionByColumn = PlHashMap<usize, Option<Compression>>;
+
+/// Returns the codec of the record batches whose columns are compressed as declared by
+/// `compression_by_column` and `options`, erroring if they declare different codecs: a record
+/// batch declares a single codec, by which all its compressed buffers are decompressed.
+pub fn check_compression_by_column(
+    options: &WriteOptions,
+    compression_by_column: &CompressionByColumn,
+) -> PolarsResult<Option<Compression>> {
+    let mut codecs = options
+        .compression
+        .into_iter()
+        .chain(compression_by_column.values().flatten().copied());
+    let compression = codecs.next();
+    if let Some(other) = codecs.find(|codec| Some(*codec) != compression) {
+        polars_bail!(InvalidOperation:
+            "the columns of an IPC record batch can only be compressed with a single codec, got {:?} and {:?}",
+            compression.unwrap(), other
+        );
+    }
+    Ok(compression)
+}
+
+/// Find the dictionary that are new and need to be encoded, with whether they are deltas of the
+/// dictionaries that were emitted with their ids, i.e. only hold the values appended to them.
 pub fn dictionaries_to_encode(
     field: &IpcField,
     array: &dyn Array,
     dictionary_tracker: &mut DictionaryTracker,
-    dicts_to_encode: &mut Vec<(i64, Box<dyn Array>)>,
+    dicts_to_encode: &mut Vec<(i64, Box<dyn Array>, bool)>,
 ) -> PolarsResult<()> {
     use PhysicalType::*;
     match array.dtype().to_physical_type() {
@@ -47,11 +74,23 @@ pub fn dictionaries_to_encode(
             let dict_id = field.dictionary_id
                 .ok_or_else(|| polars_err!(InvalidOperation: "Dictionaries must have an associated id"))?;
 
-            if dictionary_tracker.insert(dict_id, array)? {
-                dicts_to_encode.push((dict_id, array.to_boxed()));
+            let update = dictionary_tracker.update(dict_id, array)?;
+            let array = array.as_any().downcast_ref::<DictionaryArray<$T>>().unwrap();
+            match update {
+                DictionaryUpdate::Unchanged => {},
+                DictionaryUpdate::New => dicts_to_encode.push((dict_id, array.to_boxed(), false)),
+                DictionaryUpdate::Delta(start) => {
+                    // only the values of the delta are written, not the keys
+                    let values = array.values();
+                    let delta = DictionaryArray::<$T>::try_new(
+                        array.dtype().clone(),
+                        array.keys().clone().sliced(0, 0),
+                        values.sliced(start, values.len() - start),
+                    )?;
+                    dicts_to_encode.push((dict_id, delta.boxed(), true));
+                },
             }
 
-            let array = array.as_any().downcast_ref::<DictionaryArray<$T>>().unwrap();
             let values = array.values();
             // @Q? Should this not pick fields[0]?
             dictionaries_to_encode(field,
@@ -163,6 +202,37 @@ pub fn encode_dictionary(
             array,
             options,
             is_native_little_endian(),
+            false,
+        ));
+    });
+
+    Ok(())
+}
+
+/// Encode the values of a dictionary array as a delta of the dictionary with a certain id, i.e.
+/// as values that are appended to it.
+///
+/// # Panics
+///
+/// This will panic if the given array is not a [`DictionaryArray`].
+pub fn encode_delta_dictionary(
+    dict_id: i64,
+    array: &dyn Array,
+    options: &WriteOptions,
+    encoded_dictionaries: &mut Vec<EncodedData>,
+) -> PolarsResult<()> {
+    let PhysicalType::Dictionary(key_type) = array.dtype().to_physical_type() else {
+        panic!("Given array is not a DictionaryArray")
+    };
+
+    match_integer_type!(key_type, |$T| {
+        let array = array.as_any().downcast_ref::<DictionaryArray<$T>>().unwrap();
+        encoded_dictionaries.push(dictionary_batch_to_bytes::<$T>(
+            dict_id,
+            array,
+            options,
+            is_native_little_endian(),
+            true,
         ));
     });
 
@@ -178,8 +248,12 @@ pub fn encode_new_dictionaries(
 ) -> PolarsResult<()> {
     let mut dicts_to_encode = Vec::new();
     dictionaries_to_encode(field, array, dictionary_tracker, &mut dicts_to_encode)?;
-    for (dict_id, dict_array) in dicts_to_encode {
-        encode_dictionary(dict_id, dict_array.as_ref(), options, encoded_dictionaries)?;
+    for (dict_id, dict_array, is_delta) in dicts_to_encode {
+        if is_delta {
+            encode_delta_dictionary(dict_id, dict_array.as_ref(), options, encoded_dictionaries)?;
+        } else {
+            encode_dictionary(dict_id, dict_array.as_ref(), options, encoded_dictionaries)?;
+        }
     }
     Ok(())
 }
@@ -208,6 +282,27 @@ pub fn encode_chunk_amortized(
     dictionary_tracker: &mut DictionaryTracker,
     options: &WriteOptions,
     encoded_message: &mut EncodedData,
+) -> PolarsResult<Vec<EncodedData>> {
+    encode_chunk_with_compression_by_column(
+        chunk,
+        fields,
+        dictionary_tracker,
+        options,
+        &Default::default(),
+        encoded_message,
+    )
+}
+
+/// Encodes `chunk` like [`encode_chunk_amortized`], compressing its columns as declared by
+/// `compression_by_column` and `options`, see [`encode_record_batch_with_compression_by_column`].
+/// Its dictionaries are compressed as declared by `options`.
+pub fn encode_chunk_with_compression_by_column(
+    chunk: &RecordBatchT<Box<dyn Array>>,
+    fields: &[IpcField],
+    dictionary_tracker: &mut DictionaryTracker,
+    options: &WriteOptions,
+    compression_by_column: &CompressionByColumn,
+    encoded_message: &mut EncodedData,
 ) -> PolarsResult<Vec<EncodedData>> {
     let mut encoded_dictionaries = vec![];
 
@@ -220,7 +315,16 @@ pub fn encode_chunk_amortized(
             &mut encoded_dictionaries,
         )?;
     }
-    encode_record_batch(chunk, options, encoded_message);
+    if compression_by_column.is_empty() {
+        encode_record_batch(chunk, options, encoded_message);
+    } else {
+        encode_record_batch_with_compression_by_column(
+            chunk,
+            options,
+            compression_by_column,
+            encoded_message,
+        )?;
+    }
 
     Ok(encoded_dictionaries)
 }
@@ -368,6 +472,70 @@ pub fn encode_record_batch(
     );
 }
 
+/// Write [`RecordBatchT`] like [`encode_record_batch`], compressing the columns of
+/// `compression_by_column` with their own codec, or not at all, instead of with
+/// [`WriteOptions::compression`].
+///
+/// A record batch declares a single codec, so its columns can only be compressed with one codec,
+/// see [`check_compression_by_column`]; the columns that are not compressed are written as
+/// uncompressed buffers of the compressed record batch.
+pub fn encode_record_batch_with_compression_by_column(
+    chunk: &RecordBatchT<Box<dyn Array>>,
+    options: &WriteOptions,
+    compression_by_column: &CompressionByColumn,
+    encoded_message: &mut EncodedData,
+) -> PolarsResult<()> {
+    let compression = check_compression_by_column(options, compression_by_column)?;
+    let column_compression = |index: usize| {
+        compression_by_column
+            .get(&index)
+            .copied()
+            .unwrap_or(options.compression)
+    };
+    let options = WriteOptions { compression };
+
+    let mut nodes: Vec<arrow_format::ipc::FieldNode> = vec![];
+    let mut buffers: Vec<arrow_format::ipc::Buffer> = vec![];
+    encoded_message.arrow_data.clear();
+
+    let mut offset = 0;
+    let mut variadic_buffer_counts = vec![];
+    for (index, array) in chunk.arrays().iter().enumerate() {
+        let is_uncompressed = compression.is_some() && column_compression(index).is_none();
+        let column_options = WriteOptions {
+            compression: compression.filter(|_| !is_uncompressed),
+        };
+        let (start, first_buffer) = (encoded_message.arrow_data.len(), buffers.len());
+        encode_array(
+            array,
+            &column_options,
+            &mut variadic_buffer_counts,
+            &mut buffers,
+            &mut encoded_message.arrow_data,
+            &mut nodes,
+            &mut offset,
+        );
+        if is_uncompressed {
+            mark_uncompressed(
+                &mut buffers[first_buffer..],
+                &mut encoded_message.arrow_data,
+                start,
+                &mut offset,
+            );
+        }
+    }
+
+    commit_encoded_arrays(
+        chunk.len(),
+        &options,
+        variadic_buffer_counts,
+        buffers,
+        nodes,
+        encoded_message,
+    );
+    Ok(())
+}
+
 pub fn commit_encoded_arrays(
     array_len: usize,
     options: &WriteOptions,
@@ -411,6 +579,7 @@ fn dictionary_batch_to_bytes<K: DictionaryKey>(
     array: &DictionaryArray<K>,
     options: &WriteOptions,
     is_little_endian: bool,
+    is_delta: bool,
 ) -> EncodedData {
     let mut nodes: Vec<arrow_format::ipc::FieldNode> = vec![];
     let mut buffers: Vec<arrow_format::ipc::Buffer> = vec![];
@@ -449,7 +618,7 @@ fn dictionary_batch_to_bytes<K: DictionaryKey>(
                     compression,
                     variadic_buffer_counts,
                 })),
-                is_delta: false,
+                is_delta,
             },
         ))),
         body_length: arrow_data.len() as i64,
@@ -465,12 +634,30 @@ fn dictionary_batch_to_bytes<K: DictionaryKey>(
     }
 }
 
+/// How the dictionary of an id changed since it was last emitted. See
+/// [`DictionaryTracker::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DictionaryUpdate {
+    /// The dictionary was emitted with the same values, so it need not be emitted again.
+    Unchanged,
+    /// The dictionary was never emitted or replaces the dictionary that was emitted, so all of
+    /// its values must be emitted.
+    New,
+    /// The dictionary extends the dictionary that was emitted, so only its values from this
+    /// position must be emitted, as a delta.
+    Delta(usize),
+}
+
 /// Keeps track of dictionaries that have been written, to avoid emitting the same dictionary
 /// multiple times. Can optionally error if an update to an existing dictionary is attempted, which
-/// isn't allowed in the `FileWriter`.
+/// isn't allowed in the `FileWriter`, and emit the dictionaries that extend the dictionaries that
+/// were written as deltas.
 pub struct DictionaryTracker {
     pub dictionaries: Dictionaries,
     pub cannot_replace: bool,
+    /// Whether a dictionary whose values start with the values of the dictionary that was
+    /// written with its id is emitted as a delta of its new values instead of a replacement.
+    pub emit_deltas: bool,
 }
 
 impl DictionaryTracker {
@@ -484,6 +671,14 @@ impl DictionaryTracker {
     ///   has never been seen before, return `Ok(true)` to indicate that the dictionary was just
     ///   inserted.
     pub fn insert(&mut self, dict_id: i64, array: &dyn Array) -> PolarsResult<bool> {
+        Ok(self.update(dict_id, array)? != DictionaryUpdate::Unchanged)
+    }
+
+    /// Keep track of the dictionary with the given ID and values, like
+    /// [`DictionaryTracker::insert`], returning how it must be emitted. A dictionary that extends
+    /// the dictionary written with its ID is a [`DictionaryUpdate::Delta`] if the tracker is
+    /// configured to emit deltas, even when it is configured to error on replacement.
+    pub fn update(&mut self, dict_id: i64, array: &dyn Array) -> PolarsResult<DictionaryUpdate> {
         let values = match array.dtype() {
             ArrowDataType::Dictionary(key_type, _, _) => {
                 match_integer_type!(key_type, |$T| {
@@ -498,10 +693,16 @@ impl DictionaryTracker {
         };
 
         // If a dictionary with this id was already emitted, check if it was the same.
+        let mut update = DictionaryUpdate::New;
         if let Some(last) = self.dictionaries.get(&dict_id) {
             if last.as_ref() == values.as_ref() {
                 // Same dictionary values => no need to emit it again
-                return Ok(false);
+                return Ok(DictionaryUpdate::Unchanged);
+            } else if self.emit_deltas
+                && values.len() > last.len()
+                && values.sliced(0, last.len()).as_ref() == last.as_ref()
+            {
+                update = DictionaryUpdate::Delta(last.len());
             } else if self.cannot_replace {
                 polars_bail!(InvalidOperation:
                     "Dictionary replacement detected when writing IPC file format. \
@@ -512,7 +713,7 @@ impl DictionaryTracker {
         };
 
         self.dictionaries.insert(dict_id, values.clone());
-        Ok(true)
+        Ok(update)
     }
 }
 
@@ -6,13 +6,15 @@ mod stream;
 pub(crate) mod writer;
 
 pub use common::{
-    Compression, DictionaryTracker, EncodedData, Record, WriteOptions, commit_encoded_arrays,
-    dictionaries_to_encode, encode_array, encode_dictionary, encode_new_dictionaries,
-    encode_record_batch,
+    Compression, CompressionByColumn, DictionaryTracker, DictionaryUpdate, EncodedData, Record,
+    WriteOptions, check_compression_by_column, commit_encoded_arrays, dictionaries_to_encode,
+    encode_array, encode_chunk, encode_chunk_amortized, encode_chunk_with_compression_by_column,
+    encode_delta_dictionary, encode_dictionary, encode_new_dictionaries, encode_record_batch,
+    encode_record_batch_with_compression_by_column,
 };
 pub use schema::schema_to_bytes;
 pub use serialize::write;
-use serialize::write_dictionary;
+use serialize::{mark_uncompressed, write_dictionary};
 pub use stream::StreamWriter;
 pub use writer::FileWriter;
 
@@ -211,15 +211,7 @@ fn write_bytes(
 ) {
     let start = arrow_data.len();
     if let Some(compression) = compression {
-        arrow_data.extend_from_slice(&(bytes.len() as i64).to_le_bytes());
-        match compression {
-            Compression::LZ4 => {
-                compression::compress_lz4(bytes, arrow_data).unwrap();
-            },
-            Compression::ZSTD => {
-                compression::compress_zstd(bytes, arrow_data).unwrap();
-            },
-        }
+        write_compressed(bytes, arrow_data, compression);
     } else {
         arrow_data.extend_from_slice(bytes);
     };
@@ -227,6 +219,19 @@ fn write_bytes(
     buffers.push(finish_buffer(arrow_data, start, offset));
 }
 
+/// Writes `bytes` compressed to `arrow_data`, prefixed by their uncompressed length.
+fn write_compressed(bytes: &[u8], arrow_data: &mut Vec<u8>, compression: Compression) {
+    arrow_data.extend_from_slice(&(bytes.len() as i64).to_le_bytes());
+    match compression {
+        Compression::LZ4 => {
+            compression::compress_lz4(bytes, arrow_data).unwrap();
+        },
+        Compression::ZSTD => {
+            compression::compress_zstd(bytes, arrow_data).unwrap();
+        },
+    }
+}
+
 fn write_bitmap(
     bitmap: Option<&Bitmap>,
     length: usize,
@@ -313,15 +318,7 @@ fn _write_compressed_buffer_from_iter<T: NativeType, I: TrustedLen<Item = T>>(
             .map(|x| T::to_be_bytes(&x))
             .for_each(|x| swapped.extend_from_slice(x.as_ref()))
     };
-    arrow_data.extend_from_slice(&(swapped.len() as i64).to_le_bytes());
-    match compression {
-        Compression::LZ4 => {
-            compression::compress_lz4(&swapped, arrow_data).unwrap();
-        },
-        Compression::ZSTD => {
-            compression::compress_zstd(&swapped, arrow_data).unwrap();
-        },
-    }
+    write_compressed(&swapped, arrow_data, compression);
 }
 
 fn _write_buffer<T: NativeType>(buffer: &[T], arrow_data: &mut Vec<u8>, is_little_endian: bool) {
@@ -341,16 +338,7 @@ fn _write_compressed_buffer<T: NativeType>(
     compression: Compression,
 ) {
     if is_little_endian == is_native_little_endian() {
-        let bytes = bytemuck::cast_slice(buffer);
-        arrow_data.extend_from_slice(&(bytes.len() as i64).to_le_bytes());
-        match compression {
-            Compression::LZ4 => {
-                compression::compress_lz4(bytes, arrow_data).unwrap();
-            },
-            Compression::ZSTD => {
-                compression::compress_zstd(bytes, arrow_data).unwrap();
-            },
-        }
+        write_compressed(bytemuck::cast_slice(buffer), arrow_data, compression);
     } else {
         todo!()
     }
@@ -377,6 +365,30 @@ fn write_buffer_from_iter<T: NativeType, I: TrustedLen<Item = T>>(
     buffers.push(finish_buffer(arrow_data, start, offset));
 }
 
+/// Rewrites `buffers`, written uncompressed to `arrow_data` from `start` on, as the uncompressed
+/// buffers of a compressed message: prefixed by an uncompressed length of `-1`. Empty buffers,
+/// e.g. absent validities, remain empty.
+pub(super) fn mark_uncompressed(
+    buffers: &mut [ipc::Buffer],
+    arrow_data: &mut Vec<u8>,
+    start: usize,
+    offset: &mut i64,
+) {
+    let written = arrow_data.split_off(start);
+    *offset -= written.len() as i64;
+    let base = *offset;
+    for buffer in buffers {
+        let buffer_start = (buffer.offset - base) as usize;
+        let bytes = &written[buffer_start..buffer_start + buffer.length as usize];
+        let start = arrow_data.len();
+        if !bytes.is_empty() {
+            arrow_data.extend_from_slice(&(-1i64).to_le_bytes());
+            arrow_data.extend_from_slice(bytes);
+        }
+        *buffer = finish_buffer(arrow_data, start, offset);
+    }
+}
+
 fn finish_buffer(arrow_data: &mut Vec<u8>, start: usize, offset: &mut i64) -> ipc::Buffer {
     let buffer_len = (arrow_data.len() - start) as i64;
 
@@ -9,7 +9,10 @@ use std::sync::Arc;
 use polars_error::{PolarsError, PolarsResult};
 
 use super::super::IpcField;
-use super::common::{DictionaryTracker, EncodedData, WriteOptions, encode_chunk};
+use super::common::{
+    CompressionByColumn, DictionaryTracker, EncodedData, WriteOptions, check_compression_by_column,
+    encode_chunk_with_compression_by_column,
+};
 use super::common_sync::{write_continuation, write_message};
 use super::{default_ipc_fields, schema_to_bytes};
 use crate::array::Array;
@@ -33,6 +36,8 @@ pub struct StreamWriter<W: Write> {
     dictionary_tracker: DictionaryTracker,
     /// Custom schema-level metadata
     custom_schema_metadata: Option<Arc<Metadata>>,
+    /// The compression of the columns that differ from `write_options`
+    compression_by_column: CompressionByColumn,
 
     ipc_fields: Option<Vec<IpcField>>,
 }
@@ -47,9 +52,11 @@ impl<W: Write> StreamWriter<W> {
             dictionary_tracker: DictionaryTracker {
                 dictionaries: Default::default(),
                 cannot_replace: false,
+                emit_deltas: false,
             },
             ipc_fields: None,
             custom_schema_metadata: None,
+            compression_by_column: Default::default(),
         }
     }
 
@@ -58,6 +65,31 @@ impl<W: Write> StreamWriter<W> {
         self.custom_schema_metadata = Some(custom_metadata);
     }
 
+    /// Sets the compression of individual columns, e.g. to leave incompressible columns
+    /// uncompressed. See [`encode_record_batch_with_compression_by_column`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if the columns are compressed with a different codec than each other or than the
+    /// options of the writer, see [`check_compression_by_column`].
+    ///
+    /// [`encode_record_batch_with_compression_by_column`]: super::encode_record_batch_with_compression_by_column
+    pub fn set_compression_by_column(
+        &mut self,
+        compression_by_column: CompressionByColumn,
+    ) -> PolarsResult<()> {
+        check_compression_by_column(&self.write_options, &compression_by_column)?;
+        self.compression_by_column = compression_by_column;
+        Ok(())
+    }
+
+    /// Sets whether a dictionary that extends the dictionary written with its id, e.g. of a
+    /// categorical column whose categories grew, is written as a delta of its new values instead
+    /// of a replacement of all of its values. Deltas are not emitted by default.
+    pub fn set_delta_dictionaries(&mut self, emit_deltas: bool) {
+        self.dictionary_tracker.emit_deltas = emit_deltas;
+    }
+
     /// Starts the stream by writing a Schema message to it.
     /// Use `ipc_fields` to declare dictionary ids in the schema, for dictionary-reuse
     pub fn start(
@@ -101,11 +133,14 @@ impl<W: Write> StreamWriter<W> {
         #[allow(clippy::or_fun_call)]
         let fields = ipc_fields.unwrap_or(self.ipc_fields.as_ref().unwrap());
 
-        let (encoded_dictionaries, encoded_message) = encode_chunk(
+        let mut encoded_message = EncodedData::default();
+        let encoded_dictionaries = encode_chunk_with_compression_by_column(
             columns,
             fields,
             &mut self.dictionary_tracker,
             &self.write_options,
+            &self.compression_by_column,
+            &mut encoded_message,
         )?;
 
         for encoded_dictionary in encoded_dictionaries {
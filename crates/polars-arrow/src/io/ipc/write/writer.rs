@@ -5,12 +5,14 @@ use arrow_format::ipc::planus::Builder;
 use polars_error::{PolarsResult, polars_bail};
 
 use super::super::{ARROW_MAGIC_V2, IpcField};
-use super::common::{DictionaryTracker, EncodedData, WriteOptions};
+use super::common::{
+    CompressionByColumn, DictionaryTracker, EncodedData, WriteOptions, check_compression_by_column,
+    encode_chunk_with_compression_by_column,
+};
 use super::common_sync::{write_continuation, write_message};
 use super::{default_ipc_fields, schema, schema_to_bytes};
 use crate::array::Array;
 use crate::datatypes::*;
-use crate::io::ipc::write::common::encode_chunk_amortized;
 use crate::record_batch::RecordBatchT;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -43,6 +45,8 @@ pub struct FileWriter<W: Write> {
     pub(crate) encoded_message: EncodedData,
     /// Custom schema-level metadata
     pub(crate) custom_schema_metadata: Option<Arc<Metadata>>,
+    /// The compression of the columns that differ from `options`
+    pub(crate) compression_by_column: CompressionByColumn,
 }
 
 impl<W: Write> FileWriter<W> {
@@ -84,9 +88,11 @@ impl<W: Write> FileWriter<W> {
             dictionary_tracker: DictionaryTracker {
                 dictionaries: Default::default(),
                 cannot_replace: true,
+                emit_deltas: false,
             },
             encoded_message: Default::default(),
             custom_schema_metadata: None,
+            compression_by_column: Default::default(),
         }
     }
 
@@ -152,11 +158,12 @@ impl<W: Write> FileWriter<W> {
         } else {
             self.ipc_fields.as_ref()
         };
-        let encoded_dictionaries = encode_chunk_amortized(
+        let encoded_dictionaries = encode_chunk_with_compression_by_column(
             chunk,
             ipc_fields,
             &mut self.dictionary_tracker,
             &self.options,
+            &self.compression_by_column,
             &mut self.encoded_message,
         )?;
 
@@ -253,4 +260,22 @@ impl<W: Write> FileWriter<W> {
     pub fn set_custom_schema_metadata(&mut self, custom_metadata: Arc<Metadata>) {
         self.custom_schema_metadata = Some(custom_metadata);
     }
+
+    /// Sets the compression of individual columns, e.g. to leave incompressible columns
+    /// uncompressed. See [`encode_record_batch_with_compression_by_column`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if the columns are compressed with a different codec than each other or than the
+    /// options of the writer, see [`check_compression_by_column`].
+    ///
+    /// [`encode_record_batch_with_compression_by_column`]: super::encode_record_batch_with_compression_by_column
+    pub fn set_compression_by_column(
+        &mut self,
+        compression_by_column: CompressionByColumn,
+    ) -> PolarsResult<()> {
+        check_compression_by_column(&self.options, &compression_by_column)?;
+        self.compression_by_column = compression_by_column;
+        Ok(())
+    }
 }
@@ -189,11 +189,12 @@ fn mmap_fixed_size_binary<T: AsRef<[u8]>>(
     buffers: &mut VecDeque<IpcBuffer>,
     dtype: &ArrowDataType,
 ) -> PolarsResult<ArrowArray> {
-    let bytes_per_row = if let ArrowDataType::FixedSizeBinary(bytes_per_row) = dtype {
-        bytes_per_row
-    } else {
-        polars_bail!(ComputeError: "out-of-spec {:?}", OutOfSpecKind::InvalidDataType);
-    };
+    let bytes_per_row =
+        if let ArrowDataType::FixedSizeBinary(bytes_per_row) = dtype.to_logical_type() {
+            bytes_per_row
+        } else {
+            polars_bail!(ComputeError: "out-of-spec {:?}", OutOfSpecKind::InvalidDataType);
+        };
     let (num_rows, null_count) = get_num_rows_and_null_count(node)?;
 
     let data_ref = data.as_ref().as_ref();
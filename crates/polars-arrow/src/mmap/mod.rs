@@ -7,14 +7,15 @@ mod array;
 
 use arrow_format::ipc::planus::ReadAsRoot;
 use arrow_format::ipc::{Block, DictionaryBatchRef, MessageRef, RecordBatchRef};
-use polars_error::{PolarsResult, polars_bail, polars_err, to_compute_err};
+use polars_error::{PolarsResult, polars_bail, polars_ensure, polars_err, to_compute_err};
 use polars_utils::pl_str::PlSmallStr;
 
 use crate::array::Array;
 use crate::datatypes::{ArrowDataType, ArrowSchema, Field};
 use crate::io::ipc::read::file::{get_dictionary_batch, get_record_batch};
 use crate::io::ipc::read::{
-    Dictionaries, FileMetadata, IpcBuffer, Node, OutOfSpecKind, first_dict_field,
+    Dictionaries, FileMetadata, IpcBuffer, Node, OutOfSpecKind, append_delta_dictionary,
+    first_dict_field, read_file_metadata, read_variadic_buffer_counts,
 };
 use crate::io::ipc::{CONTINUATION_MARKER, IpcField};
 use crate::record_batch::RecordBatchT;
@@ -81,11 +82,7 @@ pub(crate) unsafe fn mmap_record<T: AsRef<[u8]>>(
     dictionaries: &Dictionaries,
 ) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
     let (mut buffers, mut field_nodes) = get_buffers_nodes(batch)?;
-    let mut variadic_buffer_counts = batch
-        .variadic_buffer_counts()
-        .map_err(|err| polars_err!(oos = OutOfSpecKind::InvalidFlatbufferRecordBatches(err)))?
-        .map(|v| v.iter().map(|v| v as usize).collect::<VecDeque<usize>>())
-        .unwrap_or_else(VecDeque::new);
+    let mut variadic_buffer_counts = read_variadic_buffer_counts(batch, buffers.len(), None)?;
 
     let length = batch
         .length()
@@ -175,6 +172,9 @@ pub(crate) unsafe fn mmap_dictionary_from_batch<T: AsRef<[u8]>>(
     let id = batch
         .id()
         .map_err(|err| polars_err!(ComputeError: "out-of-spec {:?}", OutOfSpecKind::InvalidFlatbufferId(err)))?;
+    let is_delta = batch
+        .is_delta()
+        .map_err(|err| polars_err!(ComputeError: "out-of-spec {:?}", OutOfSpecKind::InvalidFlatbufferIsDelta(err)))?;
     let (first_field, first_ipc_field) = first_dict_field(id, schema, ipc_fields)?;
 
     let batch = batch
@@ -202,7 +202,11 @@ pub(crate) unsafe fn mmap_dictionary_from_batch<T: AsRef<[u8]>>(
         dictionaries,
     )?;
 
-    dictionaries.insert(id, chunk.into_arrays().pop().unwrap());
+    let mut values = chunk.into_arrays().pop().unwrap();
+    if is_delta {
+        values = append_delta_dictionary(id, dictionaries, values.as_ref())?;
+    }
+    dictionaries.insert(id, values);
 
     Ok(())
 }
@@ -243,3 +247,71 @@ pub(crate) unsafe fn mmap_dictionaries_unchecked2<T: AsRef<[u8]>>(
     })?;
     Ok(dictionaries)
 }
+
+/// A reader of the record batches of an IPC file that is held in memory, e.g. memory mapped,
+/// which copies none of their buffers: the arrays of its record batches point into the file.
+///
+/// The record batches share the ownership of the file with the reader instead of borrowing it,
+/// such that the file is kept alive, e.g. mapped, for as long as the reader or any of its record
+/// batches is alive, and dropping the reader does not invalidate its record batches.
+pub struct MmapReader<T: AsRef<[u8]>> {
+    data: Arc<T>,
+    metadata: FileMetadata,
+    dictionaries: Dictionaries,
+}
+
+impl<T: AsRef<[u8]>> MmapReader<T> {
+    /// Creates a [`MmapReader`] of the IPC file `data`, reading its footer and memory mapping its
+    /// dictionaries.
+    /// # Errors
+    /// This function errors when:
+    /// * The IPC file is not valid
+    /// * The IPC file is compressed or is not of the native endianness
+    /// * The buffers of its dictionaries are un-aligned, see [`mmap_unchecked`]
+    /// # Safety
+    /// The caller must ensure that `data` contains valid buffers, as for [`mmap_unchecked`].
+    pub unsafe fn try_new(data: Arc<T>) -> PolarsResult<Self> {
+        let metadata = read_file_metadata(&mut std::io::Cursor::new(data.as_ref().as_ref()))?;
+        polars_ensure!(
+            metadata.ipc_schema.is_little_endian == cfg!(target_endian = "little"),
+            ComputeError: "memory_map can only be done on IPC files of the native endianness"
+        );
+        let dictionaries = mmap_dictionaries_unchecked(&metadata, data.clone())?;
+        Ok(Self {
+            data,
+            metadata,
+            dictionaries,
+        })
+    }
+
+    /// Returns the metadata of the file.
+    pub fn metadata(&self) -> &FileMetadata {
+        &self.metadata
+    }
+
+    /// Returns the file that the record batches point into.
+    pub fn data(&self) -> &Arc<T> {
+        &self.data
+    }
+
+    /// Returns the number of record batches of the file.
+    pub fn num_batches(&self) -> usize {
+        self.metadata.blocks.len()
+    }
+
+    /// Memory maps the record batch at position `index`.
+    /// # Errors
+    /// This function errors when the record batch is not valid or is compressed, or its buffers
+    /// are un-aligned, see [`mmap_unchecked`].
+    /// # Panics
+    /// This function panics iff `index >= self.num_batches()`
+    pub fn read(&self, index: usize) -> PolarsResult<RecordBatchT<Box<dyn Array>>> {
+        // SAFETY: the caller of `try_new` ensured that `data` contains valid buffers
+        unsafe { mmap_unchecked(&self.metadata, &self.dictionaries, self.data.clone(), index) }
+    }
+
+    /// Returns an iterator that memory maps the record batches of the file, in order.
+    pub fn iter(&self) -> impl Iterator<Item = PolarsResult<RecordBatchT<Box<dyn Array>>>> + '_ {
+        (0..self.num_batches()).map(|index| self.read(index))
+    }
+}
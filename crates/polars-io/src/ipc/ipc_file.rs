@@ -86,6 +86,7 @@ pub struct IpcReader<R: MmapBytesReader> {
     pub(super) memory_map: Option<PathBuf>,
     metadata: Option<read::FileMetadata>,
     schema: Option<ArrowSchemaRef>,
+    block_prefetch: Option<read::BlockPrefetch>,
 }
 
 fn check_mmap_err(err: PolarsError) -> PolarsResult<()> {
@@ -171,6 +172,24 @@ impl<R: MmapBytesReader> IpcReader<R> {
         self
     }
 
+    /// Decode the record batches that follow the record batch that is read ahead of time, in
+    /// parallel, e.g. to decompress a compressed file on several threads.
+    pub fn with_block_prefetch(mut self, block_prefetch: Option<read::BlockPrefetch>) -> Self {
+        self.block_prefetch = block_prefetch;
+        self
+    }
+
+    fn file_reader(self, metadata: read::FileMetadata) -> read::FileReader<R> {
+        let reader = read::FileReader::new(self.reader, metadata, self.projection, self.n_rows);
+        match self.block_prefetch {
+            Some(block_prefetch) => reader.with_options(read::ReadOptions {
+                block_prefetch: Some(block_prefetch),
+                ..Default::default()
+            }),
+            None => reader,
+        }
+    }
+
     // todo! hoist to lazy crate
     #[cfg(feature = "lazy")]
     pub fn finish_with_scan_ops(
@@ -203,12 +222,55 @@ impl<R: MmapBytesReader> IpcReader<R> {
             metadata.schema.clone()
         };
 
-        let reader = read::FileReader::new(self.reader, metadata, self.projection, self.n_rows);
+        let row_index = self.row_index.take();
+        let reader = self.file_reader(metadata);
 
-        finish_reader(reader, rechunk, None, predicate, &schema, self.row_index)
+        finish_reader(reader, rechunk, None, predicate, &schema, row_index)
     }
 }
 
+/// Reads the record batch at position `index` of an Arrow IPC file into one [`Series`] per
+/// column, named after its field. See [`read::read_batch`] for the arguments.
+///
+/// Dictionary-encoded string columns become `Categorical` (or `Enum`) series, which use the
+/// global string cache when it is enabled.
+#[allow(clippy::too_many_arguments)]
+pub fn read_batch_series<R: Read + Seek>(
+    reader: &mut R,
+    dictionaries: &read::Dictionaries,
+    metadata: &read::FileMetadata,
+    projection: Option<&[usize]>,
+    limit: Option<usize>,
+    index: usize,
+    message_scratch: &mut Vec<u8>,
+    data_scratch: &mut Vec<u8>,
+    options: &read::ReadOptions,
+) -> PolarsResult<Vec<Series>> {
+    let batch = read::read_batch(
+        reader,
+        dictionaries,
+        metadata,
+        projection,
+        limit,
+        index,
+        message_scratch,
+        data_scratch,
+        options,
+    )?;
+    record_batch_to_series(batch)
+}
+
+/// Converts a record batch decoded from Arrow IPC into one [`Series`] per column, named after
+/// its field and honoring the polars data type that its field metadata declares.
+pub fn record_batch_to_series(batch: RecordBatch) -> PolarsResult<Vec<Series>> {
+    let (schema, arrays) = batch.into_schema_and_arrays();
+    schema
+        .iter_values()
+        .zip(arrays)
+        .map(|(field, array)| Series::try_from((field, array)))
+        .collect()
+}
+
 impl<R: MmapBytesReader> ArrowReader for read::FileReader<R>
 where
     R: Read + Seek,
@@ -232,6 +294,7 @@ impl<R: MmapBytesReader> SerReader<R> for IpcReader<R> {
             memory_map: None,
             metadata: None,
             schema: None,
+            block_prefetch: None,
         }
     }
 
@@ -292,9 +355,9 @@ impl<R: MmapBytesReader> SerReader<R> for IpcReader<R> {
 
             let metadata = self.get_metadata()?.clone();
 
-            let ipc_reader =
-                read::FileReader::new(self.reader, metadata, self.projection, self.n_rows);
-            let df = finish_reader(ipc_reader, rechunk, None, None, &schema, self.row_index)?;
+            let row_index = self.row_index.take();
+            let ipc_reader = self.file_reader(metadata);
+            let df = finish_reader(ipc_reader, rechunk, None, None, &schema, row_index)?;
             Ok(df)
         })()?;
 
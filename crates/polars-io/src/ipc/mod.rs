@@ -8,7 +8,7 @@ mod ipc_stream;
 mod mmap;
 mod write;
 #[cfg(feature = "ipc")]
-pub use ipc_file::{IpcReader, IpcScanOptions};
+pub use ipc_file::{IpcReader, IpcScanOptions, read_batch_series, record_batch_to_series};
 #[cfg(feature = "cloud")]
 pub use ipc_reader_async::*;
 #[cfg(feature = "ipc_streaming")]
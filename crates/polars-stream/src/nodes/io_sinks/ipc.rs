@@ -171,6 +171,7 @@ impl SinkNode for IpcSinkNode {
             let mut dictionary_tracker = DictionaryTracker {
                 dictionaries: Default::default(),
                 cannot_replace: false,
+                emit_deltas: false,
             };
 
             struct CurrentColumn {
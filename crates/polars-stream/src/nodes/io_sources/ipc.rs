@@ -9,7 +9,8 @@ use polars_core::frame::DataFrame;
 use polars_core::prelude::DataType;
 use polars_core::schema::{Schema, SchemaExt};
 use polars_core::utils::arrow::io::ipc::read::{
-    FileMetadata, ProjectionInfo, get_row_count_from_blocks, prepare_projection, read_file_metadata,
+    BlockPrefetch, FileMetadata, Parallelism, ProjectionInfo, ReadOptions,
+    get_row_count_from_blocks, prepare_projection, read_file_metadata,
 };
 use polars_error::{ErrString, PolarsError, PolarsResult, polars_err};
 use polars_io::RowIndex;
@@ -40,7 +41,7 @@ pub mod builder {
     use polars_io::cloud::CloudOptions;
     use polars_plan::dsl::ScanSource;
 
-    use super::IpcFileReader;
+    use super::{IpcFileReader, get_block_prefetch};
     use crate::nodes::io_sources::multi_file_reader::reader_interface::FileReader;
     use crate::nodes::io_sources::multi_file_reader::reader_interface::builder::FileReaderBuilder;
     use crate::nodes::io_sources::multi_file_reader::reader_interface::capabilities::ReaderCapabilities;
@@ -87,6 +88,7 @@ pub mod builder {
                 cloud_options,
                 metadata,
                 verbose,
+                block_prefetch: get_block_prefetch(),
                 init_data: None,
             };
 
@@ -106,6 +108,7 @@ struct IpcFileReader {
     cloud_options: Option<Arc<CloudOptions>>,
     metadata: Option<Arc<FileMetadata>>,
     verbose: bool,
+    block_prefetch: Option<BlockPrefetch>,
 
     init_data: Option<InitializedState>,
 }
@@ -145,6 +148,21 @@ fn get_max_morsel_size() -> usize {
         .max(1)
 }
 
+/// Returns how many record batches every decoder task reads ahead and decodes in parallel on
+/// the rayon thread pool, e.g. to decompress compressed files on more threads than there are
+/// pipelines; `None` if they are decoded one after the other.
+fn get_block_prefetch() -> Option<BlockPrefetch> {
+    let max_blocks = std::env::var("POLARS_STREAMING_IPC_SOURCE_PREFETCH_BLOCKS")
+        .ok()?
+        .parse::<usize>()
+        .expect("POLARS_STREAMING_IPC_SOURCE_PREFETCH_BLOCKS does not contain valid number");
+    (max_blocks > 1).then_some(BlockPrefetch {
+        max_blocks,
+        max_bytes: None,
+        parallelism: Parallelism::Rayon,
+    })
+}
+
 #[async_trait]
 impl FileReader for IpcFileReader {
     async fn initialize(&mut self) -> PolarsResult<()> {
@@ -188,6 +206,7 @@ impl FileReader for IpcFileReader {
         args: BeginReadArgs,
     ) -> PolarsResult<(FileReaderOutputRecv, JoinHandle<PolarsResult<()>>)> {
         let verbose = self.verbose;
+        let block_prefetch = self.block_prefetch;
 
         let InitializedState {
             memslice,
@@ -407,6 +426,14 @@ impl FileReader for IpcFileReader {
                                 projection_info.clone(),
                                 None,
                             );
+                            if block_prefetch.is_some() {
+                                // Don't decode the blocks beyond the range ahead of time.
+                                reader = reader.with_options(ReadOptions {
+                                    block_prefetch,
+                                    max_batches: Some(block_range.len()),
+                                    ..Default::default()
+                                });
+                            }
 
                             reader.set_current_block(block_range.start);
                             reader.set_scratches((
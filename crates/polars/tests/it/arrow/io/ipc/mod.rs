@@ -1,13 +1,39 @@
-use std::io::Cursor;
-use std::sync::Arc;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 use arrow::array::*;
-use arrow::datatypes::{ArrowSchema, ArrowSchemaRef, Field};
+use arrow::bitmap::Bitmap;
+use arrow::datatypes::{
+    ArrowDataType, ArrowSchema, ArrowSchemaRef, ExtensionType, Field, IntegerType, TimeUnit,
+    UnionMode, UnionType,
+};
 use arrow::io::ipc::IpcField;
-use arrow::io::ipc::read::{FileReader, read_file_metadata};
+use arrow::io::ipc::format::ipc::planus::{Builder, ReadAsRoot};
+use arrow::io::ipc::format::ipc::{
+    Buffer, KeyValue, Message, MessageHeader, MessageHeaderRef, MessageRef, MetadataVersion,
+};
+use arrow::io::ipc::read::{
+    BatchStatistics, BlockAction, BlockKind, BlockPrefetch, BufferAllocator, BufferPool,
+    COMPRESSION_LEVEL_KEY, ColumnBudget, ColumnErrorPolicy, ColumnStatistics, ComplexityBudget,
+    DictionaryCache, DictionaryChange, DictionaryEvent, DictionaryEventKind, DictionaryResolver,
+    FieldChange, FileDiff, FileReader, IpcScanSource, MAX_NESTING_DEPTH, MessageCursor,
+    MetadataPolicy, MissingDictionaryPolicy, MultiFileReader, Parallelism, ReadOptions,
+    ScanPushdown, ScanSource, SchemaCheckOptions, StreamReader, StringCache, Window, WriterInfo,
+    check_schema, column_iter, concatenate_batches, deserialize_schema, dictionary_keys_iter,
+    diff_files, open, prepare_projection, prepare_projection_by_field_id,
+    prepare_projection_by_type, prepare_projection_mask, read_all_dictionaries, read_batch,
+    read_batch_into, read_file_metadata, read_file_metadata_with_budget, read_into_arena,
+    read_prefixed_file_metadata, read_record_batch_c_data, read_spilled, read_stream_metadata,
+    read_stream_metadata_with_budget, read_unfinished_file_metadata, read_window,
+    read_with_inflight_cap, record_batch_body_length, record_batch_message_size, sample_first_rows,
+    spill, to_c_data,
+};
 use arrow::io::ipc::write::*;
+use arrow::offset::Offset;
 use arrow::record_batch::RecordBatchT;
-use polars::prelude::PlSmallStr;
+use arrow::scalar::{PrimitiveScalar, Scalar};
+use polars::prelude::{IdxSize, PlSmallStr};
 use polars_error::*;
 
 pub(crate) fn write(
@@ -26,6 +52,17 @@ pub(crate) fn write(
     Ok(writer.into_inner())
 }
 
+/// Marks an encoded dictionary batch as a delta dictionary batch.
+fn into_delta(mut encoded: EncodedData) -> EncodedData {
+    let message = MessageRef::read_as_root(&encoded.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    if let Some(MessageHeader::DictionaryBatch(batch)) = &mut message.header {
+        batch.is_delta = true;
+    }
+    encoded.ipc_message = Builder::new().finish(&message, None).to_vec();
+    encoded
+}
+
 fn round_trip(
     columns: RecordBatchT<Box<dyn Array>>,
     schema: ArrowSchemaRef,
@@ -83,3 +120,5943 @@ fn write_binview() -> PolarsResult<()> {
     let columns = RecordBatchT::try_new(array.len(), schema.clone(), vec![array])?;
     round_trip(columns, schema, None, Some(Compression::ZSTD))
 }
+
+#[test]
+fn read_sparse_projection_of_wide_schema() -> PolarsResult<()> {
+    let int = Int32Array::from([Some(1), None, Some(3)]).boxed();
+    let utf8 = Utf8Array::<i32>::from([Some("a"), Some("bb"), None]).boxed();
+    let list = ListArray::<i32>::new(
+        ArrowDataType::List(Box::new(Field::new(
+            PlSmallStr::from_static("item"),
+            ArrowDataType::Int64,
+            true,
+        ))),
+        vec![0, 2, 2, 3].try_into()?,
+        Int64Array::from_slice([1, 2, 3]).boxed(),
+        None,
+    )
+    .boxed();
+    let struct_ = StructArray::new(
+        ArrowDataType::Struct(vec![
+            Field::new(PlSmallStr::from_static("x"), ArrowDataType::Int8, true),
+            Field::new(PlSmallStr::from_static("y"), ArrowDataType::Utf8, true),
+        ]),
+        3,
+        vec![
+            Int8Array::from_slice([1, 2, 3]).boxed(),
+            Utf8Array::<i32>::from_slice(["x", "y", "z"]).boxed(),
+        ],
+        None,
+    )
+    .boxed();
+    let view =
+        Utf8ViewArray::from_slice([Some("a string longer than twelve bytes"), None, Some("c")])
+            .boxed();
+    let candidates = [int, utf8, list, struct_, view];
+
+    let arrays = (0..200)
+        .map(|i| candidates[i % candidates.len()].clone())
+        .collect::<Vec<_>>();
+    let schema = Arc::new(ArrowSchema::from_iter(arrays.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), arrays.clone())?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    // reads `projection` of `data`, returning the batches and the bytes read of their bodies
+    let read = |data: &[u8], projection: Option<Vec<usize>>, parallelism| {
+        let metadata = read_file_metadata(&mut Cursor::new(data))?;
+        let block = metadata.blocks[0];
+        let body_start = (block.offset + block.meta_data_length as i64) as u64;
+        let body = body_start..body_start + block.body_length as u64;
+        let mut reader = FileReader::new(
+            TrackingReader {
+                inner: Cursor::new(data),
+                reads: vec![],
+            },
+            metadata,
+            projection,
+            None,
+        )
+        .with_options(ReadOptions {
+            parallelism,
+            ..Default::default()
+        });
+        let batches = reader.by_ref().collect::<PolarsResult<Vec<_>>>()?;
+        let body_bytes = reader
+            .into_inner()
+            .reads
+            .iter()
+            .filter(|read| read.start >= body.start && read.end <= body.end)
+            .map(|read| read.end - read.start)
+            .sum::<u64>();
+        PolarsResult::Ok((batches, body_bytes))
+    };
+
+    let projection = vec![3, 50, 101, 154, 199];
+    let expected = projection
+        .iter()
+        .map(|&i| arrays[i].clone())
+        .collect::<Vec<_>>();
+    let schema = Arc::new(schema.try_project_indices(&projection)?);
+    let batch = RecordBatchT::try_new(3, schema.clone(), expected.clone())?;
+    let selected = write(&[batch], &schema, None, None)?;
+
+    for parallelism in [Parallelism::Serial, Parallelism::Threads(2)] {
+        let (batches, body_bytes) = read(&data, Some(projection.clone()), parallelism)?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].arrays(), expected.as_slice());
+
+        // the buffers of the skipped columns are never read: only as many bytes are read as
+        // of a file of only the selected columns
+        let (_, selected_bytes) = read(&selected, None, parallelism)?;
+        assert!(selected_bytes > 0);
+        assert_eq!(body_bytes, selected_bytes);
+    }
+    Ok(())
+}
+
+#[test]
+fn read_nested_delta_dictionary() -> PolarsResult<()> {
+    let values = Utf8Array::<i32>::from_slice(["a", "b", "c", "d"]).boxed();
+    let keys = PrimitiveArray::<i32>::from_slice([0, 2, 3, 1, 3]);
+    let dictionary = DictionaryArray::try_from_keys(keys, values.clone())?;
+    let list = ListArray::<i32>::new(
+        ArrowDataType::List(Box::new(Field::new(
+            PlSmallStr::from_static("item"),
+            dictionary.dtype().clone(),
+            true,
+        ))),
+        vec![0, 2, 2, 5].try_into()?,
+        dictionary.boxed(),
+        None,
+    )
+    .boxed();
+    let schema = prep_schema(list.as_ref());
+    let batch = RecordBatchT::try_new(list.len(), schema.clone(), vec![list])?;
+
+    // The dictionary is split over a dictionary batch and a delta dictionary batch.
+    let options = WriteOptions { compression: None };
+    let mut dictionaries = vec![];
+    for (start, len) in [(0, 2), (2, 2)] {
+        let part = DictionaryArray::try_from_keys(
+            PrimitiveArray::<i32>::from_slice([]),
+            values.sliced(start, len),
+        )?;
+        encode_dictionary(0, &part, &options, &mut dictionaries)?;
+    }
+    let delta = into_delta(dictionaries.pop().unwrap());
+    dictionaries.push(delta);
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+
+    let mut writer = FileWriter::try_new(vec![], schema, None, options)?;
+    writer.write_encoded(&dictionaries, &record)?;
+    writer.finish()?;
+
+    let mut reader = Cursor::new(writer.into_inner());
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches =
+        FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 1);
+
+    let list = batches[0].arrays()[0]
+        .as_any()
+        .downcast_ref::<ListArray<i32>>()
+        .unwrap();
+    assert_eq!(list.offsets().as_slice(), &[0, 2, 2, 5]);
+    let dictionary = list
+        .values()
+        .as_any()
+        .downcast_ref::<DictionaryArray<i32>>()
+        .unwrap();
+    assert_eq!(
+        dictionary.keys(),
+        &PrimitiveArray::from_slice([0, 2, 3, 1, 3])
+    );
+    assert_eq!(dictionary.values(), &values);
+    Ok(())
+}
+
+#[test]
+fn read_dictionaries_only() -> PolarsResult<()> {
+    let a = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 1, 0]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?
+    .boxed();
+    let b = DictionaryArray::try_from_keys(
+        PrimitiveArray::<u8>::from_slice([2, 1, 0]),
+        Int64Array::from_slice([10, 20, 30]).boxed(),
+    )?
+    .boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new(PlSmallStr::from_static("a"), a.dtype().clone(), true),
+        Field::new(PlSmallStr::from_static("b"), b.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![a, b])?;
+    let data = write(&[batch.clone(), batch], &schema, None, None)?;
+
+    let dictionaries = read_all_dictionaries(&mut Cursor::new(data))?;
+    assert_eq!(dictionaries.len(), 2);
+    assert_eq!(
+        dictionaries[&0].as_ref(),
+        &Utf8Array::<i32>::from_slice(["x", "y"]) as &dyn Array
+    );
+    assert_eq!(
+        dictionaries[&1].as_ref(),
+        &Int64Array::from_slice([10, 20, 30]) as &dyn Array
+    );
+    Ok(())
+}
+
+fn int_columns(n: usize) -> PolarsResult<(ArrowSchemaRef, RecordBatchT<Box<dyn Array>>)> {
+    let arrays = (0..n as i32)
+        .map(|i| Int32Array::from_slice([i, i + 1]).boxed())
+        .collect::<Vec<_>>();
+    let schema =
+        Arc::new(ArrowSchema::from_iter((0..n).map(|i| {
+            Field::new(format!("c{i}").into(), ArrowDataType::Int32, true)
+        })));
+    let batch = RecordBatchT::try_new(2, schema.clone(), arrays)?;
+    Ok((schema, batch))
+}
+
+#[test]
+fn read_max_columns() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(10)?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+    let options = ReadOptions {
+        max_columns: Some(3),
+        ..Default::default()
+    };
+
+    let mut reader = Cursor::new(data.clone());
+    let metadata = read_file_metadata(&mut reader)?;
+    let reader = FileReader::new(reader, metadata, None, None).with_options(options.clone());
+    assert_eq!(
+        reader.schema(),
+        &schema.try_project_indices(&[0, 1, 2]).unwrap()
+    );
+    let batches = reader.collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].arrays(), &batch.arrays()[..3]);
+
+    // with a projection, the first columns of the projection are kept
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let reader =
+        FileReader::new(reader, metadata, Some(vec![7, 2, 9, 4]), None).with_options(options);
+    assert_eq!(
+        reader.schema(),
+        &schema.try_project_indices(&[7, 2, 9]).unwrap()
+    );
+    let batches = reader.collect::<PolarsResult<Vec<_>>>()?;
+    let expected = [7, 2, 9]
+        .iter()
+        .map(|&i| batch.arrays()[i].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(batches[0].arrays(), expected.as_slice());
+    Ok(())
+}
+
+#[test]
+fn read_zero_columns() -> PolarsResult<()> {
+    let schema = Arc::new(ArrowSchema::default());
+    let batch = RecordBatchT::try_new(5, schema.clone(), vec![])?;
+    let data = write(&[batch.clone(), batch], &schema, None, None)?;
+
+    for projection in [None, Some(vec![])] {
+        let mut reader = Cursor::new(data.clone());
+        let metadata = read_file_metadata(&mut reader)?;
+        assert!(metadata.schema.is_empty());
+        let batches = FileReader::new(reader, metadata, projection, None)
+            .collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(batches.len(), 2);
+        for batch in batches {
+            assert_eq!(batch.len(), 5);
+            assert!(batch.arrays().is_empty());
+            assert!(batch.schema().is_empty());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_empty_projection() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+
+    let mut reader = Cursor::new(data.clone());
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches =
+        FileReader::new(reader, metadata, Some(vec![]), None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].len(), 2);
+    assert!(batches[0].arrays().is_empty());
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let empty = read_batch(
+        &mut reader,
+        &Default::default(),
+        &metadata,
+        Some(&[]),
+        None,
+        0,
+        &mut vec![],
+        &mut vec![],
+        &Default::default(),
+    )?;
+    assert_eq!(empty.len(), 2);
+    assert!(empty.arrays().is_empty());
+    assert!(empty.schema().is_empty());
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    writer.write(&batch, None)?;
+    writer.finish()?;
+    let mut stream = Cursor::new(writer.into_inner());
+    let metadata = read_stream_metadata(&mut stream)?;
+    let batches = StreamReader::new(stream, metadata, Some(vec![]))
+        .map(|state| state.map(|state| state.unwrap()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].len(), 2);
+    assert!(batches[0].arrays().is_empty());
+
+    // the fields are still skipped, such that a batch that lacks their buffers is an error
+    let options = WriteOptions { compression: None };
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    let Some(MessageHeader::RecordBatch(ipc_batch)) = &mut message.header else {
+        unreachable!()
+    };
+    ipc_batch.buffers.as_mut().unwrap().pop();
+    record.ipc_message = Builder::new().finish(&message, None).to_vec();
+    let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+    writer.write_encoded(&[], &record)?;
+    writer.finish()?;
+    let mut reader = Cursor::new(writer.into_inner());
+    let metadata = read_file_metadata(&mut reader)?;
+    let result =
+        FileReader::new(reader, metadata, Some(vec![]), None).collect::<PolarsResult<Vec<_>>>();
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn read_multiple_files() -> PolarsResult<()> {
+    // both files use dictionary id 0, with different values
+    let files = [["x", "y"], ["y", "z"]].map(|values| {
+        let array = DictionaryArray::try_from_keys(
+            PrimitiveArray::<i32>::from_slice([1, 0, 1]),
+            Utf8Array::<i32>::from_slice(values).boxed(),
+        )
+        .unwrap()
+        .boxed();
+        let schema = prep_schema(array.as_ref());
+        let batch = RecordBatchT::try_new(3, schema.clone(), vec![array]).unwrap();
+        write(&[batch], &schema, None, None).unwrap()
+    });
+
+    let reader = MultiFileReader::try_new(
+        files.into_iter().map(Cursor::new).collect(),
+        None,
+        MetadataPolicy::Strict,
+    )?;
+    let batches = reader.collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 2);
+    let values = batches
+        .iter()
+        .map(|batch| {
+            let array = batch.arrays()[0]
+                .as_any()
+                .downcast_ref::<DictionaryArray<i32>>()
+                .unwrap();
+            array
+                .iter_typed::<Utf8Array<i32>>()
+                .unwrap()
+                .map(|x| x.unwrap().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, [["y", "x", "y"], ["z", "y", "z"]]);
+
+    // the row index, the maximum number of record batches and the reversal apply to the files
+    // as a whole
+    let schema = prep_schema(&Int32Array::from_slice([]));
+    // the first and last value of every record batch of every file
+    let files = [vec![(0, 2), (3, 4)], vec![(5, 7)], vec![(8, 8)]].map(|bounds| {
+        let batches = bounds
+            .into_iter()
+            .map(|(first, last)| {
+                let array = Int32Array::from_vec((first..=last).collect()).boxed();
+                RecordBatchT::try_new(array.len(), schema.clone(), vec![array]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        write(&batches, &schema, None, None).unwrap()
+    });
+    let read = |options: ReadOptions| {
+        let reader = MultiFileReader::try_new(
+            files.iter().cloned().map(Cursor::new).collect(),
+            None,
+            MetadataPolicy::Strict,
+        )?
+        .with_options(options);
+        reader
+            .map(|batch| {
+                let batch = batch?;
+                let index = batch.arrays()[0]
+                    .as_any()
+                    .downcast_ref::<PrimitiveArray<IdxSize>>()
+                    .unwrap();
+                let values = batch.arrays()[1]
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                // the row index counts the rows of the files before the file
+                for (index, value) in index.values_iter().zip(values.values_iter()) {
+                    assert_eq!(*index, 10 + *value as IdxSize);
+                }
+                Ok(values.values().to_vec())
+            })
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    let row_index = Some(("index".into(), 10));
+    let batches = read(ReadOptions {
+        row_index: row_index.clone(),
+        ..Default::default()
+    })?;
+    assert_eq!(batches, [vec![0, 1, 2], vec![3, 4], vec![5, 6, 7], vec![8]]);
+    let batches = read(ReadOptions {
+        row_index: row_index.clone(),
+        reverse: true,
+        ..Default::default()
+    })?;
+    assert_eq!(batches, [vec![8], vec![5, 6, 7], vec![3, 4], vec![0, 1, 2]]);
+    let batches = read(ReadOptions {
+        row_index: row_index.clone(),
+        max_batches: Some(3),
+        ..Default::default()
+    })?;
+    assert_eq!(batches, [vec![0, 1, 2], vec![3, 4], vec![5, 6, 7]]);
+    let batches = read(ReadOptions {
+        row_index,
+        reverse: true,
+        max_batches: Some(2),
+        ..Default::default()
+    })?;
+    assert_eq!(batches, [vec![8], vec![5, 6, 7]]);
+
+    // files with a different schema are rejected
+    let (schema, batch) = int_columns(1)?;
+    let other = write(&[batch], &schema, None, None)?;
+    let (schema, batch) = int_columns(2)?;
+    let files = vec![
+        Cursor::new(other),
+        Cursor::new(write(&[batch], &schema, None, None)?),
+    ];
+    assert!(matches!(
+        MultiFileReader::try_new(files, None, MetadataPolicy::Ignore),
+        Err(PolarsError::SchemaMismatch(_))
+    ));
+
+    // the projection may be in any order, but must select every column at most once
+    let (schema, batch) = int_columns(2)?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+    let read = |projection| {
+        let files = vec![Cursor::new(data.clone()), Cursor::new(data.clone())];
+        MultiFileReader::try_new(files, Some(projection), MetadataPolicy::Strict)?
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    let reversed = batch.arrays().iter().rev().cloned().collect::<Vec<_>>();
+    for read in read(vec![1, 0])? {
+        assert_eq!(read.arrays(), reversed);
+    }
+    assert!(matches!(read(vec![1, 1]), Err(PolarsError::Duplicate(_))));
+    assert!(matches!(read(vec![2]), Err(PolarsError::OutOfBounds(_))));
+    Ok(())
+}
+
+#[test]
+fn read_timestamp_tz() -> PolarsResult<()> {
+    let array = Int64Array::from([Some(1_700_000_000_000_000), None])
+        .to(ArrowDataType::Timestamp(
+            TimeUnit::Microsecond,
+            Some(PlSmallStr::from_static("America/New_York")),
+        ))
+        .boxed();
+    let schema = prep_schema(array.as_ref());
+    let batch = RecordBatchT::try_new(2, schema.clone(), vec![array])?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let options = ReadOptions {
+        timestamp_tz: Some(PlSmallStr::from_static("UTC")),
+        ..Default::default()
+    };
+    let reader = FileReader::new(reader, metadata, None, None).with_options(options);
+    let expected =
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(PlSmallStr::from_static("UTC")));
+    assert_eq!(reader.schema().get_at_index(0).unwrap().1.dtype, expected);
+
+    let batches = reader.collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(
+        batches[0].schema().get_at_index(0).unwrap().1.dtype,
+        expected
+    );
+    assert_eq!(
+        batches[0].arrays()[0].as_ref(),
+        &Int64Array::from([Some(1_700_000_000_000_000), None]).to(expected) as &dyn Array
+    );
+    Ok(())
+}
+
+#[test]
+fn read_nested_timestamp_tz() -> PolarsResult<()> {
+    let timestamp = |tz: Option<&'static str>| {
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, tz.map(PlSmallStr::from_static))
+    };
+    // a naive column, and zoned timestamps in `tz` nested in a list and a struct
+    let columns = |tz: &'static str| -> PolarsResult<_> {
+        let naive = Int64Array::from_slice([1, 2]).to(timestamp(None)).boxed();
+        let list = ListArray::<i32>::new(
+            ArrowDataType::List(Box::new(Field::new(
+                PlSmallStr::from_static("item"),
+                timestamp(Some(tz)),
+                true,
+            ))),
+            vec![0, 2, 3].try_into()?,
+            Int64Array::from([Some(1), None, Some(3)])
+                .to(timestamp(Some(tz)))
+                .boxed(),
+            None,
+        )
+        .boxed();
+        let struct_ = StructArray::new(
+            ArrowDataType::Struct(vec![
+                Field::new(PlSmallStr::from_static("x"), timestamp(Some(tz)), true),
+                Field::new(PlSmallStr::from_static("y"), timestamp(None), true),
+            ]),
+            2,
+            vec![
+                Int64Array::from_slice([1, 2])
+                    .to(timestamp(Some(tz)))
+                    .boxed(),
+                Int64Array::from_slice([3, 4]).to(timestamp(None)).boxed(),
+            ],
+            None,
+        )
+        .boxed();
+        let arrays = vec![naive, list, struct_];
+        let schema = Arc::new(ArrowSchema::from_iter(arrays.iter().enumerate().map(
+            |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+        )));
+        RecordBatchT::try_new(2, schema, arrays)
+    };
+    let batch = columns("America/New_York")?;
+    let data = write(
+        &[batch.clone()],
+        &Arc::new(batch.schema().clone()),
+        None,
+        None,
+    )?;
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let options = ReadOptions {
+        timestamp_tz: Some(PlSmallStr::from_static("UTC")),
+        ..Default::default()
+    };
+    let reader = FileReader::new(reader, metadata, None, None).with_options(options);
+    let expected = columns("UTC")?;
+    assert_eq!(reader.schema(), expected.schema());
+    let batches = reader.collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches, vec![expected]);
+    Ok(())
+}
+
+#[test]
+fn read_with_allocator() -> PolarsResult<()> {
+    let a = Int32Array::from([Some(1), None, Some(3)]).boxed();
+    let b = Utf8Array::<i32>::from_slice(["a", "bb", "ccc"]).boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new(PlSmallStr::from_static("a"), a.dtype().clone(), true),
+        Field::new(PlSmallStr::from_static("b"), b.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![a, b])?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+
+    let sizes = Arc::new(Mutex::new(vec![]));
+    let allocator = {
+        let sizes = sizes.clone();
+        BufferAllocator::new(move |size| {
+            sizes.lock().unwrap().push(size);
+            Vec::with_capacity(size)
+        })
+    };
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let options = ReadOptions {
+        allocator: Some(allocator),
+        ..Default::default()
+    };
+    let batches = FileReader::new(reader, metadata, None, None)
+        .with_options(options)
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    assert_eq!(batches, [batch]);
+    // the validity and values of "a" and the offsets and values of "b", once each
+    assert_eq!(*sizes.lock().unwrap(), [1, 12, 16, 6]);
+    Ok(())
+}
+
+fn union_array(mode: UnionMode) -> PolarsResult<Box<dyn Array>> {
+    let dtype = ArrowDataType::Union(Box::new(UnionType {
+        fields: vec![
+            Field::new(PlSmallStr::from_static("a"), ArrowDataType::Int32, true),
+            Field::new(PlSmallStr::from_static("b"), ArrowDataType::Utf8, true),
+        ],
+        ids: None,
+        mode,
+    }));
+    let types = vec![0i8, 1, 0, 1].into();
+    let (fields, offsets) = if mode.is_sparse() {
+        let fields = vec![
+            Int32Array::from([Some(1), None, Some(3), None]).boxed(),
+            Utf8Array::<i32>::from([None, Some("x"), None, Some("y")]).boxed(),
+        ];
+        (fields, None)
+    } else {
+        let fields = vec![
+            Int32Array::from_slice([1, 3]).boxed(),
+            Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+        ];
+        (fields, Some(vec![0, 0, 1, 1].into()))
+    };
+    Ok(UnionArray::try_new(dtype, types, fields, offsets)?.boxed())
+}
+
+#[test]
+fn write_union() -> PolarsResult<()> {
+    for mode in [UnionMode::Dense, UnionMode::Sparse] {
+        let array = union_array(mode)?;
+        let schema = prep_schema(array.as_ref());
+        let batch = RecordBatchT::try_new(array.len(), schema.clone(), vec![array.clone()])?;
+        let data = write(&[batch], &schema, None, None)?;
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let batches =
+            FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+
+        // equality of union scalars is not implemented, compare the union's components
+        let expected = array.as_any().downcast_ref::<UnionArray>().unwrap();
+        let result = batches[0].arrays()[0]
+            .as_any()
+            .downcast_ref::<UnionArray>()
+            .unwrap();
+        assert_eq!(result.dtype(), expected.dtype());
+        assert_eq!(result.types(), expected.types());
+        assert_eq!(
+            result.offsets().map(|x| x.as_slice()),
+            expected.offsets().map(|x| x.as_slice())
+        );
+        assert_eq!(result.fields(), expected.fields());
+    }
+    Ok(())
+}
+
+#[test]
+fn read_projection_skips_union() -> PolarsResult<()> {
+    for mode in [UnionMode::Dense, UnionMode::Sparse] {
+        let union = union_array(mode)?;
+        let after = Int64Array::from([Some(1), None, Some(3), Some(4)]).boxed();
+        let schema = Arc::new(ArrowSchema::from_iter([
+            Field::new(PlSmallStr::from_static("u"), union.dtype().clone(), true),
+            Field::new(
+                PlSmallStr::from_static("after"),
+                after.dtype().clone(),
+                true,
+            ),
+        ]));
+        let batch = RecordBatchT::try_new(4, schema.clone(), vec![union, after.clone()])?;
+        let data = write(&[batch], &schema, None, None)?;
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let batches = FileReader::new(reader, metadata, Some(vec![1]), None)
+            .collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(batches[0].arrays(), &[after]);
+    }
+    Ok(())
+}
+
+#[test]
+fn read_message_sequence() -> PolarsResult<()> {
+    let array = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 1, 0]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?
+    .boxed();
+    let schema = prep_schema(array.as_ref());
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![array])?;
+    let data = write(&[batch.clone(), batch], &schema, None, None)?;
+    let size = data.len() as u64;
+
+    let messages =
+        MessageCursor::try_new_file(Cursor::new(data))?.collect::<PolarsResult<Vec<_>>>()?;
+    let headers = messages
+        .iter()
+        .map(|message| match message.message.header {
+            Some(MessageHeader::Schema(_)) => "schema",
+            Some(MessageHeader::DictionaryBatch(_)) => "dictionary",
+            Some(MessageHeader::RecordBatch(_)) => "record batch",
+            _ => "other",
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        headers,
+        ["schema", "dictionary", "record batch", "record batch"]
+    );
+    for message in &messages {
+        assert_eq!(
+            message.body.end - message.body.start,
+            message.message.body_length as u64
+        );
+        assert!(message.body.end <= size);
+    }
+    Ok(())
+}
+
+#[test]
+fn read_parallel_is_deterministic() -> PolarsResult<()> {
+    let list = ListArray::<i64>::new(
+        ArrowDataType::LargeList(Box::new(Field::new(
+            PlSmallStr::from_static("item"),
+            ArrowDataType::Utf8View,
+            true,
+        ))),
+        vec![0, 2, 2, 3].try_into()?,
+        Utf8ViewArray::from_slice([Some("a string longer than twelve bytes"), None, Some("c")])
+            .boxed(),
+        None,
+    )
+    .boxed();
+    let arrays = (0..12)
+        .map(|i| match i % 3 {
+            0 => Int32Array::from([Some(i), None, Some(i + 1)]).boxed(),
+            1 => Utf8Array::<i32>::from([Some("a"), None, Some("ccc")]).boxed(),
+            _ => list.clone(),
+        })
+        .collect::<Vec<_>>();
+    let schema = Arc::new(ArrowSchema::from_iter(arrays.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), arrays)?;
+    let data = write(
+        &[batch.clone(), batch],
+        &schema,
+        None,
+        Some(Compression::LZ4),
+    )?;
+
+    for projection in [None, Some(vec![1, 2, 7, 11])] {
+        let read = |parallelism| {
+            let mut reader = Cursor::new(data.clone());
+            let metadata = read_file_metadata(&mut reader)?;
+            let options = ReadOptions {
+                parallelism,
+                ..Default::default()
+            };
+            FileReader::new(reader, metadata, projection.clone(), None)
+                .with_options(options)
+                .collect::<PolarsResult<Vec<_>>>()
+        };
+        let expected = read(Parallelism::Serial)?;
+        assert_eq!(read(Parallelism::Threads(4))?, expected);
+        #[cfg(feature = "ipc_rayon")]
+        assert_eq!(read(Parallelism::Rayon)?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn estimate_batch_size() -> PolarsResult<()> {
+    let ints = Int32Array::from_iter((0..1000).map(|i| (i % 3 != 0).then_some(i)));
+    let strings = Utf8Array::<i32>::from_iter_values((0..1000).map(|i| format!("value {i}")));
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("a".into(), ints.dtype().clone(), true),
+        Field::new("b".into(), strings.dtype().clone(), false),
+    ]));
+    let batch = RecordBatchT::try_new(
+        1000,
+        schema.clone(),
+        vec![ints.clone().boxed(), strings.clone().boxed()],
+    )?;
+
+    let ints_size = ints.values().len() * 4 + ints.validity().unwrap().as_slice().0.len();
+    let strings_size = strings.offsets().len_proxy() * 4 + 4 + strings.values().len();
+    for compression in [None, Some(Compression::LZ4), Some(Compression::ZSTD)] {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        for (projection, actual) in [
+            (None, ints_size + strings_size),
+            (Some(vec![1]), strings_size),
+        ] {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let estimate =
+                FileReader::new(reader, metadata, projection, None).estimate_batch_size(0)?;
+            assert!(
+                estimate.abs_diff(actual) <= actual / 20,
+                "{estimate} vs {actual}"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Moves every buffer of an encoded record batch to the next multiple of `alignment` after a
+/// gap of `alignment` bytes, declaring lengths padded to `alignment`.
+fn align_buffers(mut encoded: EncodedData, alignment: usize) -> EncodedData {
+    let message = MessageRef::read_as_root(&encoded.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    let Some(MessageHeader::RecordBatch(batch)) = &mut message.header else {
+        unreachable!()
+    };
+    let mut body = vec![];
+    for buffer in batch.buffers.iter_mut().flatten() {
+        let (start, length) = (buffer.offset as usize, buffer.length as usize);
+        body.resize(body.len().next_multiple_of(alignment) + alignment, 0);
+        buffer.offset = body.len() as i64;
+        buffer.length = length.next_multiple_of(alignment) as i64;
+        body.extend_from_slice(&encoded.arrow_data[start..start + length]);
+        body.resize(body.len().next_multiple_of(alignment), 0);
+    }
+    message.body_length = body.len() as i64;
+    encoded.ipc_message = Builder::new().finish(&message, None).to_vec();
+    encoded.arrow_data = body;
+    encoded
+}
+
+#[test]
+fn read_64_byte_aligned_buffers() -> PolarsResult<()> {
+    let columns = vec![
+        Int64Array::from([Some(1), None, Some(3)]).boxed(),
+        Utf8Array::<i32>::from([Some("a"), None, Some("ccc")]).boxed(),
+        BooleanArray::from([Some(true), Some(false), None]).boxed(),
+        Utf8ViewArray::from_slice([Some("a string longer than twelve bytes"), None, Some("c")])
+            .boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+    for compression in [None, Some(Compression::LZ4)] {
+        let options = WriteOptions { compression };
+        let mut record = EncodedData::default();
+        encode_record_batch(&batch, &options, &mut record);
+        let record = align_buffers(record, 64);
+
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&[], &record)?;
+        writer.finish()?;
+        let data = writer.into_inner();
+
+        for projection in [vec![0, 1, 2, 3], vec![1, 3]] {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let batches = FileReader::new(reader, metadata, Some(projection.clone()), None)
+                .collect::<PolarsResult<Vec<_>>>()?;
+            let expected = projection
+                .iter()
+                .map(|&i| batch.arrays()[i].clone())
+                .collect::<Vec<_>>();
+            assert_eq!(batches[0].arrays(), expected.as_slice());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_buffer_pool() -> PolarsResult<()> {
+    let batch = |length: i64| {
+        let schema = Arc::new(ArrowSchema::from_iter([
+            Field::new("a".into(), ArrowDataType::Int64, false),
+            Field::new("b".into(), ArrowDataType::Float32, false),
+        ]));
+        RecordBatchT::try_new(
+            length as usize,
+            schema,
+            vec![
+                Int64Array::from_vec((0..length).collect()).boxed(),
+                Float32Array::from_vec((0..length).map(|x| x as f32).collect()).boxed(),
+            ],
+        )
+    };
+    // the last batch does not fit in the pooled buffers
+    let mut batches = (0..1000)
+        .map(|_| batch(100))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    batches.push(batch(1000)?);
+    let schema = Arc::new(batches[0].schema().clone());
+    let data = write(&batches, &schema, None, None)?;
+
+    let read = |pool: &BufferPool| -> PolarsResult<Vec<*const i64>> {
+        let options = ReadOptions {
+            allocator: Some(pool.allocator()),
+            ..Default::default()
+        };
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let mut pointers = vec![];
+        for (read, expected) in FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .zip(&batches)
+        {
+            let read = read?;
+            assert_eq!(&read, expected);
+            let values = read.arrays()[0]
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap();
+            pointers.push(values.values().as_ptr());
+            pool.recycle(read.into_arrays());
+        }
+        Ok(pointers)
+    };
+
+    let pool = BufferPool::new();
+    let pointers = read(&pool)?;
+    // every batch but the last one is decoded into the allocations of the first one
+    assert!(pointers[..1000].iter().all(|&p| p == pointers[0]));
+    assert_ne!(pointers[1000], pointers[0]);
+    assert_eq!(pool.len(), 4);
+    // the 1001 batches are decoded with 4 allocations instead of 2002
+    assert_eq!(pool.allocations(), 4);
+
+    // buffers that the pool did not hand out are not pooled
+    pool.recycle([Int64Array::from_vec(vec![0; 100]).boxed()]);
+    assert_eq!(pool.len(), 4);
+
+    // a pool that only fits the buffers of one column of a small batch
+    let pool = BufferPool::with_max_bytes(800);
+    let pointers = read(&pool)?;
+    assert!(pointers[..1000].iter().all(|&p| p == pointers[0]));
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool.allocations(), 1001 + 2);
+    Ok(())
+}
+
+#[test]
+fn read_with_prepended_header() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let data = write(&[batch.clone(), batch.clone()], &schema, None, None)?;
+
+    let mut prefixed = b"custom header block\n".to_vec();
+    prefixed.extend_from_slice(&data);
+    let mut reader = Cursor::new(prefixed);
+    let metadata = read_prefixed_file_metadata(&mut reader, &Default::default())?;
+    let batches =
+        FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches, vec![batch.clone(), batch]);
+
+    // the offsets of a file without a header block are unchanged
+    let metadata = read_prefixed_file_metadata(&mut Cursor::new(&data), &Default::default())?;
+    assert_eq!(
+        metadata.blocks,
+        read_file_metadata(&mut Cursor::new(&data))?.blocks
+    );
+
+    // without the leading magic, the file is invalid
+    let mut invalid = b"custom header block\n".to_vec();
+    invalid.extend_from_slice(&data[6..]);
+    assert!(read_prefixed_file_metadata(&mut Cursor::new(invalid), &Default::default()).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_null_fills_failed_column() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 1, 0]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?
+    .boxed();
+    let ints = Int32Array::from([Some(1), None, Some(3)]).boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+        Field::new("ints".into(), ints.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![dictionary.clone(), ints.clone()])?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    // without its dictionary, the first column can't be decoded
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let read = |options: &ReadOptions, reader: &mut Cursor<Vec<u8>>| {
+        read_batch(
+            reader,
+            &Default::default(),
+            &metadata,
+            None,
+            None,
+            0,
+            &mut vec![],
+            &mut vec![],
+            options,
+        )
+    };
+    assert!(read(&ReadOptions::default(), &mut reader).is_err());
+
+    let options = ReadOptions {
+        column_error_policy: ColumnErrorPolicy::NullFill,
+        ..Default::default()
+    };
+    let batch = read(&options, &mut reader)?;
+    assert_eq!(batch.arrays()[0].dtype(), dictionary.dtype());
+    assert_eq!(batch.arrays()[0].null_count(), 3);
+    assert_eq!(batch.arrays()[1], ints);
+
+    // a reader reports the columns that it replaced by nulls, here a column that decompresses
+    // to more than its limit
+    let big = Int64Array::from_vec((0..1000).collect()).boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("ints".into(), ints.dtype().clone(), true),
+        Field::new("big".into(), big.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![ints.clone(), big.sliced(0, 3)])?;
+    let data = write(&[batch], &schema, None, Some(Compression::LZ4))?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(ReadOptions {
+        max_decompressed_size: Some(16),
+        ..options.clone()
+    });
+    let batch = reader.next().unwrap()?;
+    assert_eq!(batch.arrays()[0], ints);
+    assert_eq!(batch.arrays()[1].null_count(), 3);
+
+    let errors = reader.take_diagnostics().column_errors;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].name, "big");
+    assert!(reader.take_diagnostics().column_errors.is_empty());
+
+    // a corrupt dictionary is an error instead of nulls in every column that refers to it
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+        Field::new("ints".into(), ints.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![dictionary, ints])?;
+    let mut data = write(&[batch], &schema, None, Some(Compression::LZ4))?;
+    let metadata = read_file_metadata(&mut Cursor::new(&data))?;
+    let block = metadata.dictionary_blocks()[0];
+    let body = (block.offset + block.meta_data_length as i64) as usize;
+    // the uncompressed length of every buffer of the body exceeds any limit
+    data[body..body + block.body_length as usize].fill(0x7F);
+    let mut reader = FileReader::new(Cursor::new(data), metadata, None, None).with_options(options);
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.take_diagnostics().column_errors.is_empty());
+    Ok(())
+}
+
+#[test]
+fn read_zstd_window_limit() -> PolarsResult<()> {
+    let array = Int64Array::from_vec((0..100_000).collect()).boxed();
+    let schema = prep_schema(array.as_ref());
+    let batch = RecordBatchT::try_new(array.len(), schema.clone(), vec![array])?;
+    let data = write(&[batch.clone()], &schema, None, Some(Compression::ZSTD))?;
+
+    let read = |zstd_max_window_size| {
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            zstd_max_window_size,
+            ..Default::default()
+        };
+        FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    assert_eq!(read(None)?, vec![batch]);
+    // the buffer of 800 kB requires a larger window
+    assert!(read(Some(16 * 1024)).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_max_decompressed_size() -> PolarsResult<()> {
+    let array = Int64Array::from_vec((0..100_000).collect()).boxed();
+    let strings = Utf8ViewArray::from_slice_values(
+        (0..100_000)
+            .map(|i| format!("a string that is not inlined {i}"))
+            .collect::<Vec<_>>(),
+    )
+    .boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("a".into(), array.dtype().clone(), true),
+        Field::new("b".into(), strings.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(array.len(), schema.clone(), vec![array, strings.clone()])?;
+
+    for compression in [Compression::LZ4, Compression::ZSTD] {
+        let data = write(&[batch.clone()], &schema, None, Some(compression))?;
+        let read = |max_decompressed_size| {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let options = ReadOptions {
+                max_decompressed_size,
+                ..Default::default()
+            };
+            FileReader::new(reader, metadata, None, None)
+                .with_options(options)
+                .collect::<PolarsResult<Vec<_>>>()
+        };
+        assert_eq!(read(None)?, vec![batch.clone()]);
+        // the buffer of 800 kB exceeds the limit
+        assert!(read(Some(64 * 1024)).is_err());
+    }
+
+    // a forged uncompressed length of the data buffer of a string view column
+    let schema = prep_schema(strings.as_ref());
+    let batch = RecordBatchT::try_new(strings.len(), schema.clone(), vec![strings])?;
+    for compression in [Compression::LZ4, Compression::ZSTD] {
+        let options = WriteOptions {
+            compression: Some(compression),
+        };
+        let mut record = EncodedData::default();
+        encode_record_batch(&batch, &options, &mut record);
+        let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+        let Ok(Some(MessageHeaderRef::RecordBatch(header))) = message.header() else {
+            unreachable!()
+        };
+        let values = header.buffers().unwrap().unwrap().get(2).unwrap();
+        let offset = values.offset() as usize;
+        record.arrow_data[offset..offset + 8].copy_from_slice(&(1i64 << 40).to_le_bytes());
+
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&[], &record)?;
+        writer.finish()?;
+        let mut reader = Cursor::new(writer.into_inner());
+        let metadata = read_file_metadata(&mut reader)?;
+        let err = FileReader::new(reader, metadata, None, None)
+            .collect::<PolarsResult<Vec<_>>>()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("decompresses to 1099511627776 bytes"),
+            "{err}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn read_dictionary_keys() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from([Some(0), None, Some(1), Some(0)]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let ints = Int32Array::from_slice([1, 2, 3, 4]).boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("ints".into(), ints.dtype().clone(), true),
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(
+        4,
+        schema.clone(),
+        vec![ints.clone(), dictionary.clone().boxed()],
+    )?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    for projection in [None, Some(vec![1])] {
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let dictionary_id = metadata.ipc_schema.fields[1].dictionary_id.unwrap();
+        let options = ReadOptions {
+            dictionary_keys: true,
+            ..Default::default()
+        };
+        let mut reader =
+            FileReader::new(reader, metadata, projection.clone(), None).with_options(options);
+        assert_eq!(
+            reader.schema().get("dictionary").unwrap().dtype(),
+            &ArrowDataType::Int32
+        );
+
+        let batch = reader.next().unwrap()?;
+        let keys = batch.arrays()[batch.arrays().len() - 1].clone();
+        assert_eq!(keys, dictionary.keys().clone().boxed());
+        assert_eq!(
+            batch.schema().get("dictionary").unwrap().dtype(),
+            &ArrowDataType::Int32
+        );
+
+        let values = &reader.dictionaries().unwrap()[&dictionary_id];
+        assert_eq!(values, dictionary.values());
+    }
+    Ok(())
+}
+
+#[test]
+fn read_fixed_size_binary() -> PolarsResult<()> {
+    let uuid_dtype = ArrowDataType::FixedSizeBinary(16);
+    let uuids = FixedSizeBinaryArray::new(
+        uuid_dtype.clone(),
+        (0..64u8).collect::<Vec<_>>().into(),
+        Some([true, false, true, true].into()),
+    );
+    let list = ListArray::<i64>::new(
+        ArrowDataType::LargeList(Box::new(Field::new(
+            PlSmallStr::from_static("item"),
+            uuid_dtype.clone(),
+            true,
+        ))),
+        vec![0, 1, 1, 3, 4].try_into()?,
+        uuids.clone().boxed(),
+        None,
+    );
+    let struct_ = StructArray::new(
+        ArrowDataType::Struct(vec![
+            Field::new("uuid".into(), uuid_dtype.clone(), true),
+            Field::new("int".into(), ArrowDataType::Int32, true),
+        ]),
+        4,
+        vec![
+            uuids.clone().boxed(),
+            Int32Array::from_slice([1, 2, 3, 4]).boxed(),
+        ],
+        None,
+    );
+    let columns = vec![
+        Int32Array::from_slice([1, 2, 3, 4]).boxed(),
+        uuids.boxed(),
+        Utf8Array::<i32>::from_slice(["a", "b", "c", "d"]).boxed(),
+        list.boxed(),
+        Int64Array::from_slice([5, 6, 7, 8]).boxed(),
+        struct_.boxed(),
+        Int32Array::from_slice([9, 10, 11, 12]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(4, schema.clone(), columns.clone())?;
+
+    for compression in [None, Some(Compression::LZ4)] {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        // the fixed-size binary columns are read, or skipped between the selected columns
+        for projection in [vec![0, 1, 2, 3, 4, 5, 6], vec![0, 2, 4, 6], vec![1, 3, 5]] {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let batches = FileReader::new(reader, metadata, Some(projection.clone()), None)
+                .collect::<PolarsResult<Vec<_>>>()?;
+            let expected = projection
+                .iter()
+                .map(|&i| columns[i].clone())
+                .collect::<Vec<_>>();
+            assert_eq!(batches[0].arrays(), expected.as_slice());
+        }
+    }
+
+    // a fixed-size binary wrapped in an extension type can also be memory mapped
+    let uuid_dtype = ArrowDataType::Extension(Box::new(ExtensionType {
+        name: "arrow.uuid".into(),
+        inner: uuid_dtype,
+        metadata: None,
+    }));
+    let uuids = FixedSizeBinaryArray::new(
+        uuid_dtype.clone(),
+        (0..64u8).collect::<Vec<_>>().into(),
+        None,
+    );
+    let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+        "uuid".into(),
+        uuid_dtype,
+        true,
+    )]));
+    let batch = RecordBatchT::try_new(4, schema.clone(), vec![uuids.clone().boxed()])?;
+    let data = Arc::new(write(&[batch], &schema, None, None)?);
+    let metadata = read_file_metadata(&mut Cursor::new(data.as_slice()))?;
+    let batch = unsafe { arrow::mmap::mmap_unchecked(&metadata, &Default::default(), data, 0)? };
+    assert_eq!(batch.arrays()[0].as_ref(), &uuids as &dyn Array);
+    Ok(())
+}
+
+#[test]
+fn read_column_iter() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 1, 1]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("ints".into(), ArrowDataType::Int64, true),
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+    ]));
+    let batches = (0..4)
+        .map(|i| {
+            RecordBatchT::try_new(
+                3,
+                schema.clone(),
+                vec![
+                    Int64Array::from_slice([i, i + 1, i + 2]).boxed(),
+                    dictionary.clone().boxed(),
+                ],
+            )
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let data = write(&batches, &schema, None, None)?;
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let sum = column_iter(reader, metadata, 0, Default::default())?
+        .map(|array| {
+            let array = array?;
+            let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+            Ok(array.values_iter().sum::<i64>())
+        })
+        .sum::<PolarsResult<i64>>()?;
+    assert_eq!(sum, (0..4).map(|i| 3 * i + 3).sum::<i64>());
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let arrays = column_iter(reader, metadata.clone(), 1, Default::default())?
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(arrays.len(), 4);
+    for array in arrays {
+        let array = array
+            .as_any()
+            .downcast_ref::<DictionaryArray<i32>>()
+            .unwrap();
+        assert_eq!(array.keys(), dictionary.keys());
+        assert_eq!(array.values(), dictionary.values());
+    }
+
+    assert!(column_iter(Cursor::new(&data), metadata, 2, Default::default()).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_validity_present_or_omitted() -> PolarsResult<()> {
+    // without nulls, a writer may emit an all-set validity buffer or an empty one
+    let columns = |validity: Option<Bitmap>| {
+        let ints = Int32Array::new(
+            ArrowDataType::Int32,
+            vec![1, 2, 3, 4].into(),
+            validity.clone(),
+        );
+        let strings =
+            Utf8Array::<i32>::from_slice(["a", "bb", "", "d"]).with_validity(validity.clone());
+        let list = ListArray::<i32>::new(
+            ArrowDataType::List(Box::new(Field::new(
+                "item".into(),
+                ArrowDataType::Int32,
+                true,
+            ))),
+            vec![0, 1, 1, 3, 4].try_into().unwrap(),
+            ints.clone().boxed(),
+            validity.clone(),
+        );
+        let struct_ = StructArray::new(
+            ArrowDataType::Struct(vec![
+                Field::new("ints".into(), ArrowDataType::Int32, true),
+                Field::new("strings".into(), ArrowDataType::Utf8, true),
+            ]),
+            4,
+            vec![ints.clone().boxed(), strings.clone().boxed()],
+            validity,
+        );
+        vec![ints.boxed(), strings.boxed(), list.boxed(), struct_.boxed()]
+    };
+    let present = columns(Some(Bitmap::new_with_value(true, 4)));
+    let omitted = columns(None);
+    let schema = Arc::new(ArrowSchema::from_iter(present.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+
+    for compression in [None, Some(Compression::LZ4)] {
+        let mut decoded = vec![];
+        let mut sizes = vec![];
+        for columns in [&present, &omitted] {
+            let batch = RecordBatchT::try_new(4, schema.clone(), columns.clone())?;
+            let data = write(&[batch], &schema, None, compression)?;
+            sizes.push(data.len());
+            for projection in [None, Some(vec![1, 3]), Some(vec![0, 2])] {
+                let mut reader = Cursor::new(&data);
+                let metadata = read_file_metadata(&mut reader)?;
+                let batches = FileReader::new(reader, metadata, projection, None)
+                    .collect::<PolarsResult<Vec<_>>>()?;
+                decoded.push(batches);
+            }
+        }
+        assert!(sizes[0] > sizes[1]);
+        let (from_present, from_omitted) = decoded.split_at(3);
+        assert_eq!(from_present, from_omitted);
+        assert_eq!(from_present[0][0].arrays(), omitted.as_slice());
+    }
+    Ok(())
+}
+
+#[test]
+fn read_mixed_compressed_and_raw_buffers() -> PolarsResult<()> {
+    let mut state = 0x2545F4914F6CDD1Du64;
+    let mut random = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let length = 10_000;
+    let validity = (0..length).map(|_| random() % 2 == 0).collect::<Bitmap>();
+    let columns = vec![
+        Int64Array::from_vec((0..length as i64).map(|i| i % 7).collect()).boxed(),
+        Float64Array::from_vec((0..length).map(|_| (random() >> 11) as f64).collect())
+            .with_validity(Some(validity))
+            .boxed(),
+        Utf8Array::<i32>::from_iter_values((0..length).map(|i| format!("{}", i % 3))).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(length, schema.clone(), columns)?;
+
+    for compression in [Compression::LZ4, Compression::ZSTD] {
+        let options = WriteOptions {
+            compression: Some(compression),
+        };
+        // the pseudo-random values and validity of the second column are stored raw
+        let compression_by_column = CompressionByColumn::from_iter([(1, None)]);
+        let mut record = EncodedData::default();
+        encode_record_batch_with_compression_by_column(
+            &batch,
+            &options,
+            &compression_by_column,
+            &mut record,
+        )?;
+
+        let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+        let message: Message = message.try_into().unwrap();
+        let Some(MessageHeader::RecordBatch(header)) = message.header else {
+            unreachable!()
+        };
+        let lengths = header
+            .buffers
+            .unwrap()
+            .iter()
+            .filter(|buffer| buffer.length > 0)
+            .map(|buffer| {
+                let start = buffer.offset as usize;
+                i64::from_le_bytes(record.arrow_data[start..start + 8].try_into().unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert!(lengths.contains(&-1));
+        assert!(lengths.iter().any(|&length| length > 0));
+
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&[], &record)?;
+        writer.finish()?;
+        let data = writer.into_inner();
+
+        for projection in [vec![0, 1, 2], vec![1], vec![0, 2]] {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let batches = FileReader::new(reader, metadata, Some(projection.clone()), None)
+                .collect::<PolarsResult<Vec<_>>>()?;
+            let expected = projection
+                .iter()
+                .map(|&i| batch.arrays()[i].clone())
+                .collect::<Vec<_>>();
+            assert_eq!(batches[0].arrays(), expected.as_slice());
+        }
+    }
+    Ok(())
+}
+
+/// A reader that records the byte ranges that are read from it.
+struct TrackingReader<R> {
+    inner: R,
+    reads: Vec<std::ops::Range<u64>>,
+}
+
+impl<R: Read + Seek> Read for TrackingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let start = self.inner.stream_position()?;
+        let read = self.inner.read(buf)?;
+        self.reads.push(start..start + read as u64);
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Seek for TrackingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn read_skip_dictionary_resolution() -> PolarsResult<()> {
+    let values = Utf8Array::<i32>::from_slice(["x", "y", "z"]).boxed();
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from([Some(2), None, Some(0)]),
+        values.clone(),
+    )?;
+    let list = ListArray::<i32>::new(
+        ArrowDataType::List(Box::new(Field::new(
+            "item".into(),
+            dictionary.dtype().clone(),
+            true,
+        ))),
+        vec![0, 1, 1, 3].try_into()?,
+        dictionary.clone().boxed(),
+        None,
+    );
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+        Field::new("list".into(), list.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(
+        3,
+        schema.clone(),
+        vec![dictionary.clone().boxed(), list.clone().boxed()],
+    )?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    let metadata = read_file_metadata(&mut Cursor::new(&data))?;
+    let id = metadata.ipc_schema.fields[0].dictionary_id.unwrap();
+    // the dictionary batch of the top-level column
+    let block = metadata
+        .dictionary_blocks()
+        .iter()
+        .find(|block| {
+            let start = block.offset as usize + 8;
+            let end = block.offset as usize + block.meta_data_length as usize;
+            let message = MessageRef::read_as_root(&data[start..end]).unwrap();
+            let message: Message = message.try_into().unwrap();
+            matches!(message.header, Some(MessageHeader::DictionaryBatch(batch)) if batch.id == id)
+        })
+        .unwrap();
+    // only the message of a dictionary batch is read to find its id, never its body
+    let body_start = (block.offset + block.meta_data_length as i64) as u64;
+    let body = body_start..body_start + block.body_length as u64;
+
+    let options = ReadOptions {
+        skip_dictionary_resolution: true,
+        ..Default::default()
+    };
+    let reader = TrackingReader {
+        inner: Cursor::new(&data),
+        reads: vec![],
+    };
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(options);
+    // the schema declares the columns as they are read
+    assert_eq!(
+        reader.schema().get_at_index(0).unwrap().1.dtype,
+        ArrowDataType::Int32
+    );
+    assert_eq!(
+        &reader.schema().get_at_index(1).unwrap().1.dtype,
+        list.dtype()
+    );
+    let batches = reader.by_ref().collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches[0].arrays()[0], dictionary.keys().clone().boxed());
+    assert_eq!(batches[0].schema(), reader.schema());
+    // the dictionary nested in the list is still resolved
+    let read_list = batches[0].arrays()[1]
+        .as_any()
+        .downcast_ref::<ListArray<i32>>()
+        .unwrap();
+    assert_eq!(read_list.offsets(), list.offsets());
+    let nested = read_list
+        .values()
+        .as_any()
+        .downcast_ref::<DictionaryArray<i32>>()
+        .unwrap();
+    assert_eq!(nested.keys(), dictionary.keys());
+    assert_eq!(nested.values(), &values);
+    assert!(
+        reader
+            .into_inner()
+            .reads
+            .iter()
+            .all(|read| read.end <= body.start || read.start >= body.end)
+    );
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let options = ReadOptions {
+        skip_dictionary_resolution: true,
+        ..Default::default()
+    };
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(options);
+    assert_eq!(reader.resolve_dictionary(id)?, values);
+    assert!(reader.resolve_dictionary(42).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_list_offset_widths() -> PolarsResult<()> {
+    fn list<O: Offset>(values: Box<dyn Array>, offsets: Vec<O>) -> Box<dyn Array> {
+        let field = Box::new(Field::new("item".into(), values.dtype().clone(), true));
+        let dtype = if O::IS_LARGE {
+            ArrowDataType::LargeList(field)
+        } else {
+            ArrowDataType::List(field)
+        };
+        ListArray::<O>::new(dtype, offsets.try_into().unwrap(), values, None).boxed()
+    }
+
+    let ints = Int32Array::from_slice([1, 2, 3, 4, 5, 6]).boxed();
+    let columns = vec![
+        list::<i32>(ints.clone(), vec![0, 2, 2, 6]),
+        list::<i64>(ints.clone(), vec![0, 1, 5, 6]),
+        list::<i32>(list::<i64>(ints.clone(), vec![0, 3, 6]), vec![0, 1, 1, 2]),
+        list::<i64>(list::<i32>(ints.clone(), vec![0, 3, 6]), vec![0, 0, 2, 2]),
+        // the offsets of a large list exceed the range of 32-bit offsets
+        list::<i64>(
+            NullArray::new(ArrowDataType::Null, 3 << 31).boxed(),
+            vec![0, 1 << 31, (1 << 31) + 1, 3 << 31],
+        ),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns.clone())?;
+
+    for compression in [None, Some(Compression::LZ4)] {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        for projection in [vec![0, 1, 2, 3, 4], vec![1, 3], vec![0, 4]] {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let batches = FileReader::new(reader, metadata, Some(projection.clone()), None)
+                .collect::<PolarsResult<Vec<_>>>()?;
+            let expected = projection
+                .iter()
+                .map(|&i| columns[i].clone())
+                .collect::<Vec<_>>();
+            assert_eq!(batches[0].arrays(), expected.as_slice());
+        }
+
+        // reading the 32-bit offsets of a list as 64-bit ones is an error, not a desync
+        let mut reader = Cursor::new(&data);
+        let mut metadata = read_file_metadata(&mut reader)?;
+        let mut schema = metadata.schema.as_ref().clone();
+        schema.get_mut("c0").unwrap().dtype = columns[1].dtype().clone();
+        metadata.schema = Arc::new(schema);
+        let batches =
+            FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>();
+        assert!(batches.is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn read_stream_with_on_block() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 1, 0]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("ints".into(), ArrowDataType::Int64, true),
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+    ]));
+    let batches = (0..5)
+        .map(|i: i64| {
+            let length = (i as usize + 1).min(3);
+            RecordBatchT::try_new(
+                length,
+                schema.clone(),
+                vec![
+                    Int64Array::from_vec((i..i + length as i64).collect()).boxed(),
+                    dictionary.clone().sliced(0, length).boxed(),
+                ],
+            )
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    for batch in &batches {
+        writer.write(batch, None)?;
+    }
+    writer.finish()?;
+    let data = writer.into_inner();
+
+    let infos = Arc::new(Mutex::new(vec![]));
+    let mut reader = Cursor::new(&data);
+    let metadata = read_stream_metadata(&mut reader)?;
+    let reader = StreamReader::new(reader, metadata, None).with_on_block({
+        let infos = infos.clone();
+        move |info| {
+            infos.lock().unwrap().push(info);
+            if info.index % 2 == 1 {
+                BlockAction::Skip
+            } else {
+                BlockAction::Decode
+            }
+        }
+    });
+    let read = reader
+        .map(|state| state.map(|state| state.unwrap()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    assert_eq!(read.len(), 3);
+    for (read, expected) in read.iter().zip(batches.iter().step_by(2)) {
+        assert_eq!(read.arrays()[0], expected.arrays()[0]);
+        let keys = read.arrays()[1]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i32>>()
+            .unwrap()
+            .keys();
+        assert_eq!(keys.len(), expected.len());
+    }
+    let infos = infos.lock().unwrap();
+    assert_eq!(
+        infos.iter().map(|info| info.index).collect::<Vec<_>>(),
+        [0, 1, 2, 3, 4]
+    );
+    assert_eq!(
+        infos.iter().map(|info| info.num_rows).collect::<Vec<_>>(),
+        [1, 2, 3, 3, 3]
+    );
+    assert!(infos.iter().all(|info| info.body_length > 0));
+    Ok(())
+}
+
+/// Shifts the offsets of the utf8 values of an uncompressed dictionary batch by `shift`, and
+/// prepends as many bytes to the values, as writers of sliced arrays may do.
+fn shift_dictionary_offsets(mut encoded: EncodedData, shift: i32) -> EncodedData {
+    let message = MessageRef::read_as_root(&encoded.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    let Some(MessageHeader::DictionaryBatch(batch)) = &mut message.header else {
+        unreachable!()
+    };
+    let data = batch.data.as_mut().unwrap();
+    let buffers = data.buffers.as_mut().unwrap();
+    let slice = |buffer: &arrow::io::ipc::format::ipc::Buffer| {
+        &encoded.arrow_data[buffer.offset as usize..(buffer.offset + buffer.length) as usize]
+    };
+    let validity = slice(&buffers[0]).to_vec();
+    let offsets = slice(&buffers[1])
+        .chunks_exact(4)
+        .flat_map(|offset| (i32::from_le_bytes(offset.try_into().unwrap()) + shift).to_le_bytes())
+        .collect::<Vec<_>>();
+    let values = std::iter::repeat_n(b'#', shift as usize)
+        .chain(slice(&buffers[2]).iter().copied())
+        .collect::<Vec<_>>();
+
+    let mut body = vec![];
+    for (buffer, bytes) in buffers.iter_mut().zip([validity, offsets, values]) {
+        buffer.offset = body.len() as i64;
+        buffer.length = bytes.len() as i64;
+        body.extend_from_slice(&bytes);
+        body.resize(body.len().next_multiple_of(8), 0);
+    }
+    message.body_length = body.len() as i64;
+    encoded.ipc_message = Builder::new().finish(&message, None).to_vec();
+    encoded.arrow_data = body;
+    encoded
+}
+
+#[test]
+fn read_sliced_dictionary() -> PolarsResult<()> {
+    let values = Utf8Array::<i32>::from_slice(["a", "bb", "ccc", "dddd", "eeeee"]);
+    // the dictionary's values are a slice of a larger array
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from([Some(2), None, Some(0), Some(1)]),
+        values.clone().sliced(2, 3).boxed(),
+    )?;
+    let schema = prep_schema(&dictionary);
+    let batch = RecordBatchT::try_new(4, schema.clone(), vec![dictionary.clone().boxed()])?;
+    let expected = [Some("eeeee"), None, Some("ccc"), Some("dddd")];
+
+    let assert_read = |data: &[u8]| -> PolarsResult<()> {
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let batches =
+            FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+        let read = batches[0].arrays()[0]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i32>>()
+            .unwrap();
+        let values = read
+            .values()
+            .as_any()
+            .downcast_ref::<Utf8Array<i32>>()
+            .unwrap();
+        let read = read
+            .keys_iter()
+            .map(|key| key.map(|key| values.value(key)))
+            .collect::<Vec<_>>();
+        assert_eq!(read, expected);
+        Ok(())
+    };
+
+    // a writer of the sliced array
+    for compression in [None, Some(Compression::LZ4)] {
+        assert_read(&write(&[batch.clone()], &schema, None, compression)?)?;
+    }
+
+    // a dictionary batch whose offsets don't start at zero
+    let options = WriteOptions { compression: None };
+    let mut dictionaries = vec![];
+    encode_dictionary(0, &dictionary, &options, &mut dictionaries)?;
+    let dictionaries = dictionaries
+        .into_iter()
+        .map(|dictionary| shift_dictionary_offsets(dictionary, 3))
+        .collect::<Vec<_>>();
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let mut writer = FileWriter::try_new(vec![], schema, None, options)?;
+    writer.write_encoded(&dictionaries, &record)?;
+    writer.finish()?;
+    assert_read(&writer.into_inner())
+}
+
+#[test]
+fn read_max_rows_per_output_batch() -> PolarsResult<()> {
+    let length = 1_000_000;
+    let ints = Int32Array::from_vec((0..length as i32).collect());
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_vec((0..length as i32).map(|i| i % 2).collect()),
+        Utf8Array::<i32>::from_slice(["even", "odd"]).boxed(),
+    )?;
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("ints".into(), ArrowDataType::Int32, true),
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(
+        length,
+        schema.clone(),
+        vec![ints.clone().boxed(), dictionary.boxed()],
+    )?;
+    let options = ReadOptions {
+        max_rows_per_output_batch: Some(100_000),
+        ..Default::default()
+    };
+    let check = |batches: Vec<RecordBatchT<Box<dyn Array>>>| {
+        assert_eq!(batches.len(), 10);
+        assert!(batches.iter().all(|batch| batch.len() == 100_000));
+        for (i, batch) in batches.iter().enumerate() {
+            assert_eq!(
+                batch.arrays()[0],
+                ints.clone().sliced(i * 100_000, 100_000).boxed()
+            );
+            // the slices share the dictionary of the record batch
+            let values = batch.arrays()[1]
+                .as_any()
+                .downcast_ref::<DictionaryArray<i32>>()
+                .unwrap()
+                .values();
+            assert_eq!(values.len(), 2);
+        }
+    };
+
+    let data = write(&[batch.clone()], &schema, None, None)?;
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches = FileReader::new(reader, metadata, None, None)
+        .with_options(options.clone())
+        .collect::<PolarsResult<Vec<_>>>()?;
+    check(batches);
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    writer.write(&batch, None)?;
+    writer.finish()?;
+    let data = writer.into_inner();
+    let mut reader = Cursor::new(&data);
+    let metadata = read_stream_metadata(&mut reader)?;
+    let batches = StreamReader::new(reader, metadata, None)
+        .with_options(options)
+        .map(|state| state.map(|state| state.unwrap()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    check(batches);
+    Ok(())
+}
+
+#[test]
+fn read_with_trailing_bytes() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let data = write(&[batch.clone(), batch.clone()], &schema, None, None)?;
+
+    let trailer = b"{\"manifest\": \"ARROW1\"}".to_vec();
+    let mut suffixed = data.clone();
+    suffixed.extend_from_slice(&trailer);
+    let mut reader = Cursor::new(suffixed);
+    let metadata = read_file_metadata(&mut reader)?;
+    assert_eq!(metadata.trailing_bytes(), trailer.as_slice());
+    let batches =
+        FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches, vec![batch.clone(), batch]);
+
+    let metadata = read_file_metadata(&mut Cursor::new(&data))?;
+    assert!(metadata.trailing_bytes().is_empty());
+
+    // without a footer, the file is invalid
+    let mut invalid = data[..data.len() - 10].to_vec();
+    invalid.extend_from_slice(&trailer);
+    assert!(read_file_metadata(&mut Cursor::new(invalid)).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_oversized_footer_length() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    // a footer that is longer than the file
+    for footer_len in [data.len() as i32, i32::MAX] {
+        let mut invalid = data.clone();
+        let at = invalid.len() - 10;
+        invalid[at..at + 4].copy_from_slice(&footer_len.to_le_bytes());
+        let err = read_file_metadata(&mut Cursor::new(invalid)).unwrap_err();
+        assert!(err.to_string().contains("InvalidFooter"), "{err}");
+    }
+    Ok(())
+}
+
+#[test]
+fn check_schema_mismatches() -> PolarsResult<()> {
+    let inner = Field::new("item".into(), ArrowDataType::Int32, true);
+    let expected = ArrowSchema::from_iter([
+        Field::new("a".into(), ArrowDataType::Int32, true),
+        Field::new(
+            "b".into(),
+            ArrowDataType::Struct(vec![
+                Field::new("x".into(), ArrowDataType::Utf8, true),
+                Field::new(
+                    "y".into(),
+                    ArrowDataType::List(Box::new(inner.clone())),
+                    true,
+                ),
+            ]),
+            true,
+        ),
+        Field::new("c".into(), ArrowDataType::Float64, false),
+    ]);
+    let options = SchemaCheckOptions::default();
+    check_schema(&expected, &expected, options)?;
+
+    let message = |found: &ArrowSchema, options| {
+        check_schema(&expected, found, options)
+            .unwrap_err()
+            .to_string()
+    };
+
+    // a different type of a nested field
+    let mut found = expected.clone();
+    found.get_mut("b").unwrap().dtype = ArrowDataType::Struct(vec![
+        Field::new("x".into(), ArrowDataType::Utf8, true),
+        Field::new(
+            "y".into(),
+            ArrowDataType::List(Box::new(inner.with_dtype(ArrowDataType::Int64))),
+            true,
+        ),
+    ]);
+    let error = message(&found, options);
+    assert!(
+        error.contains(r#"field 1.1.0 ("item"): expected type Int32, found Int64"#),
+        "{error}"
+    );
+
+    // a renamed and a missing field
+    let found = ArrowSchema::from_iter([
+        Field::new("A".into(), ArrowDataType::Int32, true),
+        expected.get_at_index(1).unwrap().1.clone(),
+    ]);
+    let error = message(&found, options);
+    assert!(
+        error.contains(r#"field 0: expected name "a", found "A""#),
+        "{error}"
+    );
+    assert!(
+        error.contains(r#"field 2: expected field "c", found none"#),
+        "{error}"
+    );
+
+    // an extra field
+    let mut found = expected.clone();
+    found.insert(
+        "d".into(),
+        Field::new("d".into(), ArrowDataType::Boolean, true),
+    );
+    let error = message(&found, options);
+    assert!(
+        error.contains(r#"field 3: found unexpected field "d""#),
+        "{error}"
+    );
+
+    // nullability, unless ignored
+    let mut found = expected.clone();
+    found.get_mut("c").unwrap().is_nullable = true;
+    let error = message(&found, options);
+    assert!(
+        error.contains(r#"field 2 ("c"): expected nullable false, found true"#),
+        "{error}"
+    );
+    let lenient = SchemaCheckOptions {
+        check_nullability: false,
+        ..options
+    };
+    check_schema(&expected, &found, lenient)?;
+
+    // metadata, only if checked
+    let mut found = expected.clone();
+    let field = found.get_mut("a").unwrap();
+    *field = field.clone().with_metadata(
+        [(PlSmallStr::from("key"), PlSmallStr::from("value"))]
+            .into_iter()
+            .collect(),
+    );
+    check_schema(&expected, &found, options)?;
+    let strict = SchemaCheckOptions {
+        check_metadata: true,
+        ..options
+    };
+    let error = message(&found, strict);
+    assert!(
+        error.contains(r#"field 0 ("a"): expected metadata None"#),
+        "{error}"
+    );
+    Ok(())
+}
+
+#[test]
+fn read_projection_mask() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    let mask = prepare_projection_mask(&schema, &[true, false, true])?;
+    let indices = prepare_projection(&schema, vec![0, 2]);
+    assert_eq!(mask.columns, indices.columns);
+    assert_eq!(mask.schema, indices.schema);
+
+    let read = |projection| {
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        FileReader::new_with_projection_info(reader, metadata, Some(projection), None)
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    assert_eq!(read(mask)?, read(indices)?);
+
+    assert!(prepare_projection_mask(&schema, &[true, false]).is_err());
+    Ok(())
+}
+
+#[test]
+#[allow(deprecated)]
+fn read_projection_with_deprecated_map() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    // the columns are read in the order of the projection, which the map leaves as it is
+    let projection = prepare_projection(&schema, vec![2, 0]);
+    let map = projection.map.clone();
+    assert_eq!(map, [(0, 0), (1, 1)].into_iter().collect());
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches = FileReader::new_with_projection_info(reader, metadata, Some(projection), None)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let reordered = arrow::io::ipc::read::apply_projection(batches[0].clone(), &map);
+    assert_eq!(reordered, batches[0]);
+    Ok(())
+}
+
+/// Converts the buffers of a native little-endian record batch to big-endian, given the byte
+/// width of the values of every buffer (1 for buffers that need no swapping).
+fn into_big_endian(mut encoded: EncodedData, widths: &[usize]) -> EncodedData {
+    let message = MessageRef::read_as_root(&encoded.ipc_message).unwrap();
+    let message: Message = message.try_into().unwrap();
+    let batch = match &message.header {
+        Some(MessageHeader::RecordBatch(batch)) => batch,
+        Some(MessageHeader::DictionaryBatch(dictionary)) => dictionary.data.as_ref().unwrap(),
+        _ => unreachable!(),
+    };
+    let buffers = batch.buffers.as_ref().unwrap();
+    assert_eq!(buffers.len(), widths.len());
+    for (buffer, &width) in buffers.iter().zip(widths) {
+        let (start, length) = (buffer.offset as usize, buffer.length as usize);
+        for value in encoded.arrow_data[start..start + length].chunks_exact_mut(width) {
+            value.reverse();
+        }
+    }
+    encoded
+}
+
+#[test]
+fn read_time_units() -> PolarsResult<()> {
+    let times: [(Box<dyn Array>, usize); 4] = [
+        (
+            Int32Array::from([Some(1), None, Some(86_399)])
+                .to(ArrowDataType::Time32(TimeUnit::Second))
+                .boxed(),
+            4,
+        ),
+        (
+            Int32Array::from([Some(1), None, Some(86_399_999)])
+                .to(ArrowDataType::Time32(TimeUnit::Millisecond))
+                .boxed(),
+            4,
+        ),
+        (
+            Int64Array::from([Some(1), None, Some(86_399_999_999)])
+                .to(ArrowDataType::Time64(TimeUnit::Microsecond))
+                .boxed(),
+            8,
+        ),
+        (
+            Int64Array::from([Some(1), None, Some(86_399_999_999_999)])
+                .to(ArrowDataType::Time64(TimeUnit::Nanosecond))
+                .boxed(),
+            8,
+        ),
+    ];
+    for (time, width) in times {
+        let item = Field::new("item".into(), time.dtype().clone(), true);
+        let list = ListArray::<i32>::new(
+            ArrowDataType::List(Box::new(item)),
+            vec![0, 2, 2, 3].try_into()?,
+            time.clone(),
+            Some(Bitmap::from([true, false, true])),
+        );
+        let columns = vec![
+            time.clone(),
+            list.boxed(),
+            Int32Array::from([Some(7), Some(8), None]).boxed(),
+        ];
+        let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+            |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+        )));
+        let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+        let options = WriteOptions { compression: None };
+        let encode = || {
+            let mut record = EncodedData::default();
+            encode_record_batch(&batch, &options, &mut record);
+            record
+        };
+        let big_endian = into_big_endian(encode(), &[1, width, 1, 4, 1, width, 1, 4]);
+
+        for (record, is_little_endian) in [(encode(), true), (big_endian, false)] {
+            let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+            writer.write_encoded(&[], &record)?;
+            writer.finish()?;
+            let data = writer.into_inner();
+
+            // the columns after the time columns are only decoded correctly if the time columns
+            // are read and skipped with the width of their unit
+            for projection in [vec![0, 1, 2], vec![1, 2], vec![2]] {
+                let mut reader = Cursor::new(&data);
+                let mut metadata = read_file_metadata(&mut reader)?;
+                assert_eq!(metadata.schema, schema);
+                metadata.ipc_schema.is_little_endian = is_little_endian;
+                let batches = FileReader::new(reader, metadata, Some(projection.clone()), None)
+                    .collect::<PolarsResult<Vec<_>>>()?;
+                let expected = projection
+                    .iter()
+                    .map(|&i| batch.arrays()[i].clone())
+                    .collect::<Vec<_>>();
+                assert_eq!(batches[0].arrays(), expected.as_slice());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_big_endian_dictionary() -> PolarsResult<()> {
+    let values = Int64Array::from([Some(-1), None, Some(1 << 40), Some(7)]);
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i16>::from([Some(2), None, Some(0), Some(3), Some(2), Some(1)]),
+        values.clone().boxed(),
+    )?;
+    let schema = prep_schema(&dictionary);
+    let batch = RecordBatchT::try_new(6, schema.clone(), vec![dictionary.clone().boxed()])?;
+
+    let options = WriteOptions { compression: None };
+    let encode = || -> PolarsResult<_> {
+        let mut dictionaries = vec![];
+        encode_dictionary(0, &dictionary, &options, &mut dictionaries)?;
+        let mut record = EncodedData::default();
+        encode_record_batch(&batch, &options, &mut record);
+        Ok((dictionaries, record))
+    };
+    let big_endian = {
+        let (dictionaries, record) = encode()?;
+        let dictionaries = dictionaries
+            .into_iter()
+            .map(|dictionary| into_big_endian(dictionary, &[1, 8]))
+            .collect::<Vec<_>>();
+        (dictionaries, into_big_endian(record, &[1, 2]))
+    };
+
+    for ((dictionaries, record), is_little_endian) in [(encode()?, true), (big_endian, false)] {
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&dictionaries, &record)?;
+        writer.finish()?;
+        let data = writer.into_inner();
+
+        let mut reader = Cursor::new(&data);
+        let mut metadata = read_file_metadata(&mut reader)?;
+        metadata.ipc_schema.is_little_endian = is_little_endian;
+        let batches =
+            FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+        let read = batches[0].arrays()[0]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i16>>()
+            .unwrap();
+        assert_eq!(read.keys(), dictionary.keys());
+        assert_eq!(read.values().as_ref(), &values as &dyn Array);
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_dictionary_cache() -> PolarsResult<()> {
+    let file = |keys: [i32; 3], values: [&str; 2]| {
+        let dictionary = DictionaryArray::try_from_keys(
+            PrimitiveArray::<i32>::from_slice(keys),
+            Utf8Array::<i32>::from_slice(values).boxed(),
+        )?;
+        let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+            "dictionary".into(),
+            dictionary.dtype().clone(),
+            true,
+        )]));
+        let batch = RecordBatchT::try_new(3, schema.clone(), vec![dictionary.boxed()])?;
+        let data = write(&[batch.clone()], &schema, None, None)?;
+        PolarsResult::Ok((data, batch))
+    };
+    let files = [
+        file([0, 1, 0], ["x", "y"])?,
+        file([1, 1, 0], ["x", "y"])?,
+        file([0, 0, 1], ["a", "b"])?,
+    ];
+
+    let cache = Arc::new(DictionaryCache::new());
+    let options = ReadOptions {
+        dictionary_cache: Some(cache.clone()),
+        ..Default::default()
+    };
+    for (data, expected) in &files {
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let batches = FileReader::new(reader, metadata, None, None)
+            .with_options(options.clone())
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let read = batches[0].arrays()[0]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i32>>()
+            .unwrap();
+        let expected = expected.arrays()[0]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i32>>()
+            .unwrap();
+        assert_eq!(read.keys(), expected.keys());
+        assert_eq!(read.values(), expected.values());
+    }
+    // the dictionary that the first two files share is only decoded once
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.hits(), 1);
+    Ok(())
+}
+
+#[test]
+fn read_contiguous_output_batches() -> PolarsResult<()> {
+    let length = 10;
+    let ints = Int64Array::from_iter((0..length as i64).map(|i| (i % 3 != 0).then_some(i)));
+    let strings = Utf8Array::<i32>::from_iter_values((0..length).map(|i| "x".repeat(i)));
+    let lists = ListArray::<i32>::new(
+        ArrowDataType::List(Box::new(Field::new(
+            "item".into(),
+            ArrowDataType::Int32,
+            true,
+        ))),
+        (0..=length as i32)
+            .map(|i| i * 2)
+            .collect::<Vec<_>>()
+            .try_into()?,
+        Int32Array::from_vec((0..2 * length as i32).collect()).boxed(),
+        None,
+    );
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_vec((0..length as i32).map(|i| i % 2).collect()),
+        Utf8Array::<i32>::from_slice(["a", "b"]).boxed(),
+    )?;
+    let columns = vec![
+        ints.boxed(),
+        strings.boxed(),
+        lists.boxed(),
+        dictionary.boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(length, schema.clone(), columns)?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    let read = |contiguous| {
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            max_rows_per_output_batch: Some(4),
+            contiguous,
+            ..Default::default()
+        };
+        FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    let sliced = read(false)?;
+    let contiguous = read(true)?;
+    assert_eq!(contiguous.len(), 3);
+    for (sliced, contiguous) in sliced.iter().zip(&contiguous) {
+        let arrays = contiguous.arrays();
+        assert_eq!(arrays[..3], sliced.arrays()[..3]);
+
+        let ints = arrays[0].as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(ints.values().offset(), 0);
+        assert!(!ints.values().is_sliced());
+
+        let strings = arrays[1].as_any().downcast_ref::<Utf8Array<i32>>().unwrap();
+        assert_eq!(strings.offsets().buffer().offset(), 0);
+        assert_eq!(*strings.offsets().first(), 0);
+        assert!(!strings.values().is_sliced());
+
+        let lists = arrays[2].as_any().downcast_ref::<ListArray<i32>>().unwrap();
+        assert_eq!(lists.offsets().buffer().offset(), 0);
+        assert_eq!(*lists.offsets().first(), 0);
+        let items = lists
+            .values()
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(items.values().offset(), 0);
+        assert!(!items.values().is_sliced());
+
+        let keys = arrays[3]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i32>>()
+            .unwrap()
+            .keys();
+        let sliced_keys = sliced.arrays()[3]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i32>>()
+            .unwrap()
+            .keys();
+        assert_eq!(keys, sliced_keys);
+        assert_eq!(keys.values().offset(), 0);
+        assert!(!keys.values().is_sliced());
+    }
+    Ok(())
+}
+
+#[test]
+fn read_missing_columns_with_defaults() -> PolarsResult<()> {
+    let columns = vec![
+        Int64Array::from([Some(1), None, Some(3)]).boxed(),
+        Utf8Array::<i32>::from([Some("a"), Some("b"), None]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("a".into(), ArrowDataType::Int64, true),
+        Field::new("b".into(), ArrowDataType::Utf8, true),
+    ]));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    // a newer schema, with a required column and a nullable column that the file lacks
+    let reader_schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("b".into(), ArrowDataType::Utf8, true),
+        Field::new("required".into(), ArrowDataType::Int32, false),
+        Field::new("a".into(), ArrowDataType::Int64, true),
+        Field::new("optional".into(), ArrowDataType::Utf8View, true),
+    ]));
+    let read = |defaults: Vec<(&str, Box<dyn Scalar>)>| {
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            reader_schema: Some(reader_schema.clone()),
+            defaults: defaults
+                .into_iter()
+                .map(|(name, default)| (PlSmallStr::from(name), default))
+                .collect(),
+            ..Default::default()
+        };
+        let reader = FileReader::new(reader, metadata, None, None).with_options(options);
+        assert_eq!(reader.schema(), reader_schema.as_ref());
+        reader.collect::<PolarsResult<Vec<_>>>()
+    };
+
+    let batches = read(vec![(
+        "required",
+        Box::new(PrimitiveScalar::new(ArrowDataType::Int32, Some(42))),
+    )])?;
+    assert_eq!(batches[0].schema(), reader_schema.as_ref());
+    assert_eq!(
+        batches[0].arrays(),
+        [
+            Utf8Array::<i32>::from([Some("a"), Some("b"), None]).boxed(),
+            Int32Array::from_slice([42, 42, 42]).boxed(),
+            Int64Array::from([Some(1), None, Some(3)]).boxed(),
+            Utf8ViewArray::from_slice::<&str, _>([None, None, None]).boxed(),
+        ]
+    );
+
+    // a required column without a default, or with a null default, cannot be read
+    let error = read(vec![]).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains(r#"column "required" is missing"#),
+        "{error}"
+    );
+    let error = read(vec![(
+        "required",
+        Box::new(PrimitiveScalar::<i32>::new(ArrowDataType::Int32, None)),
+    )])
+    .unwrap_err();
+    assert!(error.to_string().contains("is null"), "{error}");
+    Ok(())
+}
+
+#[test]
+fn read_truncated_file() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let data = write(
+        &[batch.clone(), batch.clone(), batch.clone()],
+        &schema,
+        None,
+        None,
+    )?;
+    let metadata = read_file_metadata(&mut Cursor::new(&data))?;
+    let last = metadata.blocks[2];
+    let body_start = (last.offset + last.meta_data_length as i64) as usize;
+    let truncated = &data[..body_start + last.body_length as usize / 2];
+
+    let read = |tolerate_truncation| {
+        let mut reader = Cursor::new(truncated);
+        let metadata = read_unfinished_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            tolerate_truncation,
+            ..Default::default()
+        };
+        let mut reader = StreamReader::new(reader, metadata, None).with_options(options);
+        let batches = reader
+            .by_ref()
+            .map(|state| state.map(|state| state.unwrap()))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        PolarsResult::Ok((batches, reader.truncation().copied()))
+    };
+
+    let (batches, truncation) = read(true)?;
+    assert_eq!(batches, vec![batch.clone(), batch]);
+    let truncation = truncation.unwrap();
+    assert_eq!(truncation.block_index, 2);
+    assert_eq!(truncation.declared_length, last.body_length as usize);
+    assert_eq!(truncation.available_length, last.body_length as usize / 2);
+
+    assert!(read(false).is_err());
+    // a file without its footer has no file metadata
+    assert!(read_file_metadata(&mut Cursor::new(truncated)).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_projection_by_type() -> PolarsResult<()> {
+    let columns = vec![
+        Int32Array::from_slice([1, 2]).boxed(),
+        Utf8Array::<i32>::from_slice(["a", "b"]).boxed(),
+        UInt64Array::from_slice([3, 4]).boxed(),
+        Float64Array::from_slice([0.5, 1.5]).boxed(),
+        Int8Array::from_slice([5, 6]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(2, schema.clone(), columns)?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+
+    let projection = prepare_projection_by_type(&schema, |dtype| {
+        matches!(
+            dtype,
+            ArrowDataType::Int8
+                | ArrowDataType::Int16
+                | ArrowDataType::Int32
+                | ArrowDataType::Int64
+                | ArrowDataType::UInt8
+                | ArrowDataType::UInt16
+                | ArrowDataType::UInt32
+                | ArrowDataType::UInt64
+        )
+    });
+    assert_eq!(projection.columns, [0, 2, 4]);
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches = FileReader::new_with_projection_info(reader, metadata, Some(projection), None)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let names = batches[0]
+        .schema()
+        .iter_names()
+        .map(|name| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["c0", "c2", "c4"]);
+    assert_eq!(
+        batches[0].arrays(),
+        [
+            batch.arrays()[0].clone(),
+            batch.arrays()[2].clone(),
+            batch.arrays()[4].clone()
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn read_non_string_dictionaries() -> PolarsResult<()> {
+    let ints = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i8>::from([Some(1), None, Some(0), Some(1)]),
+        Int64Array::from([Some(i64::MIN), Some(42)]).boxed(),
+    )?;
+    let dates = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i16>::from([Some(0), Some(2), Some(1), None]),
+        Int32Array::from([Some(0), None, Some(19_000)])
+            .to(ArrowDataType::Date32)
+            .boxed(),
+    )?;
+    let columns = vec![ints.clone().boxed(), dates.clone().boxed()];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(4, schema.clone(), columns)?;
+
+    for compression in [None, Some(Compression::ZSTD)] {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        assert_eq!(metadata.schema, schema);
+        let batches =
+            FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+
+        let read = batches[0].arrays()[0]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i8>>()
+            .unwrap();
+        assert_eq!(read.keys(), ints.keys());
+        assert_eq!(read.values(), ints.values());
+        let read = batches[0].arrays()[1]
+            .as_any()
+            .downcast_ref::<DictionaryArray<i16>>()
+            .unwrap();
+        assert_eq!(read.keys(), dates.keys());
+        assert_eq!(read.values(), dates.values());
+        assert_eq!(read.values().dtype(), &ArrowDataType::Date32);
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_block_filter() -> PolarsResult<()> {
+    let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+        "a".into(),
+        ArrowDataType::Int32,
+        true,
+    )]));
+    let options = WriteOptions { compression: None };
+    let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+    for (values, with_statistics) in [(0..10, true), (10..20, true), (20..30, false)] {
+        let array = Int32Array::from_vec(values.clone().collect()).boxed();
+        let chunk = RecordBatchT::try_new(10, schema.clone(), vec![array])?;
+        let mut encoded = EncodedData::default();
+        encode_record_batch(&chunk, &options, &mut encoded);
+        if with_statistics {
+            let statistics = BatchStatistics {
+                columns: [(
+                    "a".into(),
+                    ColumnStatistics {
+                        min: Some(values.start.to_string().into()),
+                        max: Some((values.end - 1).to_string().into()),
+                        null_count: Some(0),
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            };
+            let mut message: Message = MessageRef::read_as_root(&encoded.ipc_message)
+                .unwrap()
+                .try_into()
+                .unwrap();
+            message.custom_metadata = Some(
+                statistics
+                    .to_metadata()
+                    .into_iter()
+                    .map(|(key, value)| KeyValue {
+                        key: Some(key),
+                        value: Some(value),
+                    })
+                    .collect(),
+            );
+            encoded.ipc_message = Builder::new().finish(&message, None).to_vec();
+        }
+        writer.write_encoded(&[], &encoded)?;
+    }
+    writer.finish()?;
+    let mut data = writer.into_inner();
+
+    let read = |data: &[u8], options: ReadOptions| {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let counter = calls.clone();
+        let firsts = FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .with_block_filter(move |statistics| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                statistics.max::<i32>("a").is_none_or(|max| max >= 15)
+            })
+            .map(|batch| {
+                let batch = batch?;
+                let a = batch.arrays()[0]
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                Ok(a.value(0))
+            })
+            .collect::<Vec<PolarsResult<_>>>();
+        PolarsResult::Ok((firsts, calls.load(Ordering::Relaxed)))
+    };
+    // the first block is skipped; the last one has no statistics and is always read
+    for block_prefetch in [
+        None,
+        Some(BlockPrefetch {
+            max_blocks: 2,
+            max_bytes: None,
+            parallelism: Parallelism::Serial,
+        }),
+    ] {
+        let (firsts, calls) = read(
+            &data,
+            ReadOptions {
+                block_prefetch,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            firsts.into_iter().collect::<PolarsResult<Vec<_>>>()?,
+            [10, 20]
+        );
+        // the filter is evaluated once for every block with statistics
+        assert_eq!(calls, 2);
+    }
+
+    // a block whose message is corrupt is an error once, after which the next block is read
+    let block = read_file_metadata(&mut Cursor::new(&data))?.blocks[0];
+    let start = block.offset as usize + 8;
+    data[start..block.offset as usize + block.meta_data_length as usize].fill(0xFF);
+    let (firsts, _) = read(&data, Default::default())?;
+    assert_eq!(firsts.len(), 3);
+    assert!(firsts[0].is_err());
+    assert_eq!(
+        firsts[1..]
+            .iter()
+            .map(|x| *x.as_ref().unwrap())
+            .collect::<Vec<_>>(),
+        [10, 20]
+    );
+    Ok(())
+}
+
+#[test]
+fn read_with_inflight_cap_stalls() -> PolarsResult<()> {
+    let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+        "a".into(),
+        ArrowDataType::Int32,
+        false,
+    )]));
+    let batches = (0..4)
+        .map(|i| {
+            let array = Int32Array::from_vec(vec![i; 100]).boxed();
+            RecordBatchT::try_new(100, schema.clone(), vec![array])
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let data = write(&batches, &schema, None, None)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let mut reader = FileReader::new(reader, metadata, None, None);
+    let batch_size = reader.estimate_batch_size(0)?;
+
+    // the cap allows for two batches in flight
+    let mut inflight = read_with_inflight_cap(reader, 2 * batch_size);
+    let first = inflight.next().unwrap()?;
+    let second = inflight.next().unwrap()?;
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(inflight.produced(), 2);
+    assert_eq!(inflight.inflight_bytes(), 2 * batch_size);
+
+    drop(first);
+    let third = inflight.next().unwrap()?;
+    assert_eq!(third.arrays()[0].len(), 100);
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(inflight.produced(), 3);
+
+    drop((second, third));
+    let values = inflight
+        .map(|batch| {
+            let batch = batch?;
+            let a = batch.arrays()[0]
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            Ok(a.value(0))
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(values, [3]);
+    Ok(())
+}
+
+#[test]
+fn read_with_inflight_cap_options() -> PolarsResult<()> {
+    let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+        "a".into(),
+        ArrowDataType::Int32,
+        false,
+    )]));
+    // record batches of different sizes
+    let batches = (1..4)
+        .map(|i| {
+            let array = Int32Array::from_vec(vec![i; 100 * i as usize]).boxed();
+            RecordBatchT::try_new(100 * i as usize, schema.clone(), vec![array])
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let data = write(&batches, &schema, None, None)?;
+    let reader = |options: ReadOptions| -> PolarsResult<_> {
+        let mut reader = Cursor::new(data.clone());
+        let metadata = read_file_metadata(&mut reader)?;
+        Ok(FileReader::new(reader, metadata, None, None).with_options(options))
+    };
+
+    // the reader stops after `max_batches` record batches
+    let options = ReadOptions {
+        max_batches: Some(1),
+        ..Default::default()
+    };
+    let read = read_with_inflight_cap(reader(options)?, usize::MAX)
+        .map(|batch| batch.map(|batch| batch.len()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read, [100]);
+
+    // in reverse, every batch is sized by its own record batch
+    let mut sizes = reader(Default::default())?;
+    let sizes = (0..3)
+        .rev()
+        .map(|index| sizes.estimate_batch_size(index))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let options = ReadOptions {
+        reverse: true,
+        ..Default::default()
+    };
+    let read = read_with_inflight_cap(reader(options)?, usize::MAX)
+        .map(|batch| batch.map(|batch| (batch.len(), batch.bytes())))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read, [(300, sizes[0]), (200, sizes[1]), (100, sizes[2])]);
+    Ok(())
+}
+
+#[test]
+fn read_deeply_nested_schema() -> PolarsResult<()> {
+    // serializing the schema recurses once per level, which needs a larger stack than reading
+    let serialize = |depth: usize| {
+        std::thread::Builder::new()
+            .stack_size(256 << 20)
+            .spawn(move || {
+                let mut dtype = ArrowDataType::Int32;
+                for _ in 0..depth {
+                    dtype = ArrowDataType::List(Box::new(Field::new("item".into(), dtype, true)));
+                }
+                let schema = ArrowSchema::from_iter([Field::new("a".into(), dtype, true)]);
+                let ipc_fields = default_ipc_fields(schema.iter_values());
+                schema_to_bytes(&schema, &ipc_fields, None)
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    };
+
+    let (schema, _, _) = deserialize_schema(&serialize(MAX_NESTING_DEPTH - 1))?;
+    assert_eq!(schema.len(), 1);
+
+    let err = deserialize_schema(&serialize(4000)).unwrap_err();
+    assert!(err.to_string().contains("NestingTooDeep"));
+    Ok(())
+}
+
+#[test]
+fn read_dictionary_history() -> PolarsResult<()> {
+    let values = Utf8Array::<i32>::from_slice(["a", "b", "c", "d", "e"]).boxed();
+    let keys = PrimitiveArray::<i32>::from_slice([0, 2, 1]);
+    let dictionary = DictionaryArray::try_from_keys(keys, values.clone())?.boxed();
+    let schema = prep_schema(dictionary.as_ref());
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![dictionary])?;
+
+    // a dictionary, its replacement and a delta of the replacement
+    let options = WriteOptions { compression: None };
+    let mut dictionaries = vec![];
+    for (start, len) in [(0, 2), (0, 2), (2, 3)] {
+        let part = DictionaryArray::try_from_keys(
+            PrimitiveArray::<i32>::from_slice([]),
+            values.sliced(start, len),
+        )?;
+        encode_dictionary(0, &part, &options, &mut dictionaries)?;
+    }
+    let delta = into_delta(dictionaries.pop().unwrap());
+    dictionaries.push(delta);
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+
+    let mut writer = FileWriter::try_new(vec![], schema, None, options)?;
+    writer.write_encoded(&dictionaries, &record)?;
+    writer.finish()?;
+
+    let mut reader = Cursor::new(writer.into_inner());
+    let metadata = read_file_metadata(&mut reader)?;
+    let mut reader = FileReader::new(reader, metadata, None, None);
+    assert!(reader.dictionary_history(0).is_empty());
+    let read = reader.next().unwrap()?;
+    let read = read.arrays()[0]
+        .as_any()
+        .downcast_ref::<DictionaryArray<i32>>()
+        .unwrap();
+    assert_eq!(read.values(), &values);
+
+    let event = |block_index, kind, length| DictionaryEvent {
+        block_index,
+        kind,
+        length,
+    };
+    assert_eq!(
+        reader.dictionary_history(0),
+        [
+            event(0, DictionaryEventKind::Replacement, 2),
+            event(1, DictionaryEventKind::Replacement, 2),
+            event(2, DictionaryEventKind::Delta, 3),
+        ]
+    );
+    assert!(reader.dictionary_history(1).is_empty());
+    Ok(())
+}
+
+#[test]
+fn read_with_validate_offsets() -> PolarsResult<()> {
+    let array = Utf8Array::<i32>::from_slice(["ab", "c", "def"]).boxed();
+    let schema = prep_schema(array.as_ref());
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![array])?;
+    let offsets = |offsets: [i32; 4]| {
+        offsets
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>()
+    };
+
+    // replaces the offsets `[0, 2, 3, 6]` of the encoded batch
+    let write_with_offsets = |new_offsets: [i32; 4]| -> PolarsResult<Vec<u8>> {
+        let options = WriteOptions { compression: None };
+        let mut encoded = EncodedData::default();
+        encode_record_batch(&batch, &options, &mut encoded);
+        let old = offsets([0, 2, 3, 6]);
+        let start = encoded
+            .arrow_data
+            .windows(old.len())
+            .position(|window| window == old)
+            .unwrap();
+        encoded.arrow_data[start..start + old.len()].copy_from_slice(&offsets(new_offsets));
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&[], &encoded)?;
+        writer.finish()?;
+        Ok(writer.into_inner())
+    };
+    let read = |data: Vec<u8>, validate_offsets: bool| {
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            validate_offsets,
+            ..Default::default()
+        };
+        FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+
+    let batches = read(write_with_offsets([0, 2, 3, 6])?, true)?;
+    assert_eq!(batches[0], batch);
+
+    let err = read(write_with_offsets([0, 3, 2, 6])?, true).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("offset 2 of column \"a\" (2) is smaller than the offset before it (3)")
+    );
+    assert!(read(write_with_offsets([0, 3, 2, 6])?, false).is_err());
+
+    // the last offset falls short of the values: only an error when validating
+    let err = read(write_with_offsets([0, 2, 3, 5])?, true).unwrap_err();
+    assert!(err.to_string().contains(
+        "the last offset of column \"a\" (5) does not equal the length of its values (6)"
+    ));
+    let batches = read(write_with_offsets([0, 2, 3, 5])?, false)?;
+    assert_eq!(batches[0].arrays()[0].len(), 3);
+    Ok(())
+}
+
+#[test]
+fn read_with_string_cache() -> PolarsResult<()> {
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("a".into(), ArrowDataType::Utf8, true),
+        Field::new("b".into(), ArrowDataType::Int32, true),
+    ]));
+    let batch = |values: [Option<&str>; 3]| {
+        RecordBatchT::try_new(
+            3,
+            schema.clone(),
+            vec![
+                Utf8Array::<i32>::from(values).boxed(),
+                Int32Array::from_slice([1, 2, 3]).boxed(),
+            ],
+        )
+    };
+    let first = [Some("a string that is not inlined"), Some("short"), None];
+    let second = [
+        Some("another string that is not inlined"),
+        Some("a string that is not inlined"),
+        Some("a string that is not inlined"),
+    ];
+    let data = write(&[batch(first)?, batch(second)?], &schema, None, None)?;
+
+    let cache = Arc::new(StringCache::new());
+    let options = ReadOptions {
+        string_cache: Some(cache.clone()),
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let reader = FileReader::new(reader, metadata, None, None).with_options(options);
+    assert_eq!(
+        reader.schema().get_at_index(0).unwrap().1.dtype,
+        ArrowDataType::Utf8View
+    );
+    let batches = reader.collect::<PolarsResult<Vec<_>>>()?;
+
+    let strings = batches
+        .iter()
+        .map(|batch| {
+            batch.arrays()[0]
+                .as_any()
+                .downcast_ref::<Utf8ViewArray>()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(strings[0].iter().collect::<Vec<_>>(), first);
+    assert_eq!(strings[1].iter().collect::<Vec<_>>(), second);
+    assert_eq!(
+        batches[1].arrays()[1],
+        Int32Array::from_slice([1, 2, 3]).boxed()
+    );
+
+    // the repeated string is stored once, in the buffer of the first batch
+    assert_eq!(cache.len(), 2);
+    assert_eq!(
+        cache.total_bytes(),
+        first[0].unwrap().len() + second[0].unwrap().len()
+    );
+    assert_eq!(strings[0].views()[0], strings[1].views()[1]);
+    assert_eq!(strings[1].views()[1], strings[1].views()[2]);
+    let (first_buffer, second_buffers) = (&strings[0].data_buffers()[0], strings[1].data_buffers());
+    assert_eq!(second_buffers.len(), 2);
+    assert_eq!(first_buffer.as_ptr(), second_buffers[0].as_ptr());
+    Ok(())
+}
+
+#[test]
+fn read_binary_offset_widths() -> PolarsResult<()> {
+    fn list<O: Offset>(values: Box<dyn Array>, offsets: Vec<O>) -> Box<dyn Array> {
+        let field = Box::new(Field::new("item".into(), values.dtype().clone(), true));
+        let dtype = if O::IS_LARGE {
+            ArrowDataType::LargeList(field)
+        } else {
+            ArrowDataType::List(field)
+        };
+        ListArray::<O>::new(dtype, offsets.try_into().unwrap(), values, None).boxed()
+    }
+
+    let values = [Some(&b"blob"[..]), None, Some(b""), Some(&[0, 255, 1])];
+    let binary = BinaryArray::<i32>::from(values).boxed();
+    let large_binary = BinaryArray::<i64>::from(values).boxed();
+    let columns = vec![
+        binary.clone(),
+        large_binary.clone(),
+        list::<i32>(large_binary.clone(), vec![0, 2, 2, 3, 4]),
+        list::<i64>(binary.clone(), vec![0, 0, 1, 3, 4]),
+        StructArray::new(
+            ArrowDataType::Struct(vec![
+                Field::new("binary".into(), binary.dtype().clone(), true),
+                Field::new("large_binary".into(), large_binary.dtype().clone(), true),
+            ]),
+            4,
+            vec![binary.clone(), large_binary.clone()],
+            None,
+        )
+        .boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(4, schema.clone(), columns.clone())?;
+
+    for compression in [None, Some(Compression::LZ4)] {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        for projection in [vec![0, 1, 2, 3, 4], vec![1, 3], vec![0, 4]] {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let batches = FileReader::new(reader, metadata, Some(projection.clone()), None)
+                .collect::<PolarsResult<Vec<_>>>()?;
+            let expected = projection
+                .iter()
+                .map(|&i| columns[i].clone())
+                .collect::<Vec<_>>();
+            assert_eq!(batches[0].arrays(), expected.as_slice());
+        }
+
+        // reading the 32-bit offsets of binary values as 64-bit ones is an error, not a desync
+        let mut reader = Cursor::new(&data);
+        let mut metadata = read_file_metadata(&mut reader)?;
+        let mut schema = metadata.schema.as_ref().clone();
+        schema.get_mut("c0").unwrap().dtype = ArrowDataType::LargeBinary;
+        metadata.schema = Arc::new(schema);
+        let batches =
+            FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>();
+        assert!(batches.is_err());
+    }
+    Ok(())
+}
+
+#[test]
+#[ignore = "writes and reads more than 2 GiB"]
+fn read_large_binary_beyond_32_bit_offsets() -> PolarsResult<()> {
+    let length = (1usize << 31) + 3;
+    let mut values = vec![0u8; length];
+    values[length - 3..].copy_from_slice(b"end");
+    let array = BinaryArray::<i64>::new(
+        ArrowDataType::LargeBinary,
+        vec![0, 1 << 31, length as i64].try_into()?,
+        values.into(),
+        None,
+    )
+    .boxed();
+    let schema = prep_schema(array.as_ref());
+    let batch = RecordBatchT::try_new(2, schema.clone(), vec![array])?;
+
+    let path = std::env::temp_dir().join("read_large_binary_beyond_32_bit_offsets.arrow");
+    let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+    let mut writer = FileWriter::try_new(file, schema, None, WriteOptions::default())?;
+    writer.write(&batch, None)?;
+    writer.finish()?;
+    drop((writer, batch));
+
+    let mut reader = std::fs::File::open(&path)?;
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches = FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>();
+    std::fs::remove_file(&path)?;
+    let batches = batches?;
+    let read = batches[0].arrays()[0]
+        .as_any()
+        .downcast_ref::<BinaryArray<i64>>()
+        .unwrap();
+    assert_eq!(read.value(0).len(), 1 << 31);
+    assert_eq!(read.value(1), b"end");
+    Ok(())
+}
+
+#[test]
+fn read_with_alignment() -> PolarsResult<()> {
+    let columns = vec![
+        Int8Array::from([Some(1), None, Some(3)]).boxed(),
+        Int64Array::from([Some(1), Some(2), None]).boxed(),
+        Float32Array::from([Some(0.5), None, Some(1.5)]).boxed(),
+        Utf8Array::<i32>::from([Some("a"), Some("bc"), None]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+    let is_aligned = |ptr: *const u8, align: usize| (ptr as usize) % align == 0;
+    for compression in [None, Some(Compression::LZ4)] {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        let read = |align: usize| {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let options = ReadOptions {
+                align,
+                ..Default::default()
+            };
+            FileReader::new(reader, metadata, None, None)
+                .with_options(options)
+                .collect::<PolarsResult<Vec<_>>>()
+        };
+
+        for align in [64, 4096] {
+            let batches = read(align)?;
+            assert_eq!(batches[0], batch);
+            let arrays = batches[0].arrays();
+            let downcast = |i: usize| arrays[i].as_any();
+            let int8 = downcast(0).downcast_ref::<Int8Array>().unwrap();
+            let int64 = downcast(1).downcast_ref::<Int64Array>().unwrap();
+            let float32 = downcast(2).downcast_ref::<Float32Array>().unwrap();
+            let utf8 = downcast(3).downcast_ref::<Utf8Array<i32>>().unwrap();
+            assert!(is_aligned(int8.values().as_ptr().cast(), align));
+            assert!(is_aligned(int64.values().as_ptr().cast(), align));
+            assert!(is_aligned(float32.values().as_ptr().cast(), align));
+            assert!(is_aligned(utf8.offsets().buffer().as_ptr().cast(), align));
+        }
+
+        let err = read(48).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("the alignment of buffers must be a power of two, got 48")
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn read_embedded_window() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([1, 0]),
+        Utf8Array::<i32>::from_slice(["a", "b"]).boxed(),
+    )?;
+    let mut fields = schema.iter_values().cloned().collect::<Vec<_>>();
+    fields.push(Field::new("d".into(), dictionary.dtype().clone(), true));
+    let schema = Arc::new(ArrowSchema::from_iter(fields));
+    let mut arrays = batch.arrays().to_vec();
+    arrays.push(dictionary.boxed());
+    let batch = RecordBatchT::try_new(2, schema.clone(), arrays)?;
+    let file = write(&[batch.clone(), batch.clone()], &schema, None, None)?;
+
+    // an archive with another member before and after the file
+    let start = 1000;
+    let mut archive = vec![0xab; start];
+    archive.extend_from_slice(&file);
+    archive.extend_from_slice(&[0xcd; 333]);
+
+    let reader = read_window(Cursor::new(&archive), start as u64, file.len() as u64)?;
+    assert_eq!(reader.metadata().size, file.len() as u64);
+    assert_eq!(reader.metadata().schema, schema);
+    let batches = reader.collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 2);
+    for read in batches {
+        assert_eq!(read.arrays()[..3], batch.arrays()[..3]);
+        let keys = |batch: &RecordBatchT<Box<dyn Array>>| {
+            let array = batch.arrays()[3]
+                .as_any()
+                .downcast_ref::<DictionaryArray<i32>>()
+                .unwrap();
+            (array.keys().clone(), array.values().clone())
+        };
+        assert_eq!(keys(&read), keys(&batch));
+    }
+
+    // a window with a projection
+    let mut window = Window::try_new(Cursor::new(&archive), start as u64, file.len() as u64)?;
+    let metadata = read_file_metadata(&mut window)?;
+    let batches =
+        FileReader::new(window, metadata, Some(vec![1]), None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches[0].arrays(), &batch.arrays()[1..2]);
+
+    // a window that does not end at the end of the file
+    let len = file.len() as u64 - 1;
+    assert!(read_window(Cursor::new(&archive), start as u64, len).is_err());
+    let Err(err) = read_window(Cursor::new(&archive), start as u64, archive.len() as u64) else {
+        panic!("the window exceeds the archive")
+    };
+    assert!(err.to_string().contains("exceeds the"));
+    Ok(())
+}
+
+#[test]
+fn read_record_batch_message_size() -> PolarsResult<()> {
+    let columns = vec![
+        Int64Array::from([Some(1), None, Some(3)]).boxed(),
+        Utf8Array::<i32>::from([Some("a"), Some("a longer string"), None]).boxed(),
+        BinaryViewArray::from_slice_values([b"short".as_slice(), &[7; 100], b""]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+    let slice = |offset: usize, length: usize| {
+        let arrays = batch
+            .arrays()
+            .iter()
+            .map(|array| array.sliced(offset, length));
+        RecordBatchT::try_new(length, schema.clone(), arrays.collect())
+    };
+
+    for compression in [None, Some(Compression::LZ4), Some(Compression::ZSTD)] {
+        let batches = [batch.clone(), slice(0, 0)?, slice(1, 2)?];
+        let data = write(&batches, &schema, None, compression)?;
+        let metadata = read_file_metadata(&mut Cursor::new(&data))?;
+        assert_eq!(metadata.blocks.len(), 3);
+        for block in &metadata.blocks {
+            let offset = block.offset as usize;
+            assert_eq!(data[offset..offset + 4], [0xff; 4]);
+            let length = i32::from_le_bytes(data[offset + 4..offset + 8].try_into().unwrap());
+            let metadata_bytes = &data[offset + 8..offset + 8 + length as usize];
+            let message = MessageRef::read_as_root(metadata_bytes).unwrap();
+            let Some(MessageHeaderRef::RecordBatch(record_batch)) = message.header().unwrap()
+            else {
+                unreachable!()
+            };
+            assert_eq!(
+                record_batch_body_length(record_batch)?,
+                block.body_length as u64
+            );
+            assert_eq!(
+                record_batch_message_size(record_batch, metadata_bytes.len())?,
+                block.meta_data_length as u64 + block.body_length as u64
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_detected_format() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(2)?;
+    let batches = [batch.clone(), batch.clone()];
+    let file = write(&batches, &schema, None, None)?;
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    for batch in &batches {
+        writer.write(batch, None)?;
+    }
+    writer.finish()?;
+    let stream = writer.into_inner();
+
+    let mut prefixed = b"custom header block\n".to_vec();
+    prefixed.extend_from_slice(&file);
+
+    for (data, is_file) in [(file, true), (prefixed, true), (stream, false)] {
+        let reader = open(Cursor::new(&data))?;
+        assert_eq!(reader.is_file(), is_file);
+        assert_eq!(reader.schema(), schema.as_ref());
+        let read = reader.collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(read, batches);
+
+        let options = ReadOptions {
+            max_rows_per_output_batch: Some(1),
+            ..Default::default()
+        };
+        let read = open(Cursor::new(&data))?
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(read.len(), 4);
+    }
+
+    assert!(open(Cursor::new(b"ARR")).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_batches_into_reused_columns() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(8)?;
+    let num_batches = 10_000;
+    let data = write(&vec![batch.clone(); num_batches], &schema, None, None)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+
+    let (mut message_scratch, mut data_scratch) = (vec![], vec![]);
+    let mut columns = Vec::with_capacity(8);
+    let allocation = columns.as_ptr();
+    for projection in [None, Some([1, 4, 7].as_slice())] {
+        for index in 0..num_batches {
+            let read = read_batch_into(
+                &mut reader,
+                &Default::default(),
+                &metadata,
+                projection,
+                None,
+                index,
+                &mut message_scratch,
+                &mut data_scratch,
+                &mut columns,
+                &Default::default(),
+            )?;
+            assert!(columns.is_empty());
+            match projection {
+                None => assert_eq!(read, batch),
+                Some(projection) => assert_eq!(read.arrays()[2], batch.arrays()[projection[2]]),
+            }
+
+            // the columns of the consumed batch are decoded into the same allocation
+            columns = read.into_arrays();
+            assert_eq!(columns.as_ptr(), allocation);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_row_index() -> PolarsResult<()> {
+    // the values of column "a" are the positions of their rows in the file
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("a".into(), ArrowDataType::Int32, true),
+        Field::new("b".into(), ArrowDataType::Utf8, true),
+    ]));
+    let batches = [0..3, 3..8, 8..9, 9..13]
+        .into_iter()
+        .map(|rows| {
+            let a = Int32Array::from_vec(rows.clone().collect());
+            let b = Utf8Array::<i32>::from_iter_values(rows.clone().map(|row| row.to_string()));
+            RecordBatchT::try_new(rows.len(), schema.clone(), vec![a.boxed(), b.boxed()])
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let file = write(&batches, &schema, None, None)?;
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    for batch in &batches {
+        writer.write(batch, None)?;
+    }
+    writer.finish()?;
+    let stream = writer.into_inner();
+
+    let offset = 100;
+    let options = |max_rows_per_output_batch| ReadOptions {
+        row_index: Some(("index".into(), offset)),
+        max_rows_per_output_batch,
+        ..Default::default()
+    };
+    // checks that the row index counts the rows of "a" from `offset`, and returns the rows read
+    let check = |batches: Vec<RecordBatchT<Box<dyn Array>>>| {
+        let mut rows = vec![];
+        for batch in batches {
+            assert_eq!(batch.schema().get_at_index(0).unwrap().0, "index");
+            let index = batch.arrays()[0]
+                .as_any()
+                .downcast_ref::<PrimitiveArray<IdxSize>>()
+                .unwrap();
+            let a = batch.arrays()[1]
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            assert_eq!(index.null_count(), 0);
+            for (index, a) in index.values_iter().zip(a.values_iter()) {
+                assert_eq!(*index, offset + *a as IdxSize);
+                rows.push(*a);
+            }
+        }
+        rows
+    };
+
+    for max_rows in [None, Some(2)] {
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let reader =
+            FileReader::new(reader, metadata, Some(vec![0]), None).with_options(options(max_rows));
+        assert_eq!(
+            reader.schema().iter_names().collect::<Vec<_>>(),
+            ["index", "a"]
+        );
+        let read = reader.collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(check(read), (0..13).collect::<Vec<_>>());
+
+        let mut reader = Cursor::new(&stream);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let read = StreamReader::new(reader, metadata, None)
+            .with_options(options(max_rows))
+            .map(|state| state.map(|state| state.unwrap()))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(check(read), (0..13).collect::<Vec<_>>());
+    }
+
+    // the rows of skipped record batches are counted
+    let mut reader = Cursor::new(&stream);
+    let metadata = read_stream_metadata(&mut reader)?;
+    let read = StreamReader::new(reader, metadata, None)
+        .with_options(options(None))
+        .with_on_block(|info| match info.index {
+            1 => BlockAction::Skip,
+            _ => BlockAction::Decode,
+        })
+        .map(|state| state.map(|state| state.unwrap()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(check(read), [0, 1, 2, 8, 9, 10, 11, 12]);
+
+    // a limit ends the row index with the rows read
+    let mut reader = Cursor::new(&file);
+    let metadata = read_file_metadata(&mut reader)?;
+    let read = FileReader::new(reader, metadata, None, Some(4))
+        .with_options(options(None))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(check(read), [0, 1, 2, 3]);
+
+    // an index beyond the range of its type
+    let mut reader = Cursor::new(&file);
+    let metadata = read_file_metadata(&mut reader)?;
+    let options = ReadOptions {
+        row_index: Some(("index".into(), IdxSize::MAX - 5)),
+        ..Default::default()
+    };
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(options);
+    assert!(reader.next().unwrap().is_ok());
+    let err = reader.next().unwrap().unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("the row index \"index\" overflows")
+    );
+    Ok(())
+}
+
+#[test]
+fn read_shared_dictionary_id() -> PolarsResult<()> {
+    let values = Utf8Array::<i32>::from_slice(["a", "b", "c"]).boxed();
+    let x = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from([Some(2), None, Some(0), Some(1)]),
+        values.clone(),
+    )?;
+    let y = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i8>::from_slice([1, 1, 2, 0]),
+        values.clone(),
+    )?;
+    let item = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 2, 2, 1, 0]),
+        values.clone(),
+    )?;
+    let z = ListArray::<i32>::new(
+        ListArray::<i32>::default_datatype(item.dtype().clone()),
+        vec![0, 2, 2, 3, 5].try_into()?,
+        item.clone().boxed(),
+        None,
+    );
+    let columns = vec![x.clone().boxed(), y.clone().boxed(), z.boxed()];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(4, schema.clone(), columns)?;
+
+    // the three columns share the dictionary of id 0, which is written once
+    let shared = || IpcField {
+        fields: vec![],
+        dictionary_id: Some(0),
+    };
+    let ipc_fields = vec![
+        shared(),
+        shared(),
+        IpcField {
+            fields: vec![shared()],
+            dictionary_id: None,
+        },
+    ];
+    let file = write(&[batch.clone()], &schema, Some(ipc_fields.clone()), None)?;
+    let dictionary_batches = MessageCursor::try_new_file(Cursor::new(&file))?
+        .filter(|message| {
+            let header = message.as_ref().map(|message| &message.message.header);
+            matches!(header, Ok(Some(MessageHeader::DictionaryBatch(_))))
+        })
+        .count();
+    assert_eq!(dictionary_batches, 1);
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, Some(ipc_fields.clone()))?;
+    writer.write(&batch, Some(&ipc_fields))?;
+    writer.finish()?;
+    let stream = writer.into_inner();
+
+    // checks that each column of `read`, the columns `projection` of `batch`, resolves its keys
+    // against the shared dictionary
+    let check = |read: &RecordBatchT<Box<dyn Array>>, projection: &[usize]| {
+        assert_eq!(read.arrays().len(), projection.len());
+        for (array, &column) in read.arrays().iter().zip(projection) {
+            let array = match column {
+                2 => {
+                    let list = array.as_any().downcast_ref::<ListArray<i32>>().unwrap();
+                    assert_eq!(list.offsets().as_slice(), [0, 2, 2, 3, 5]);
+                    list.values().clone()
+                },
+                _ => array.clone(),
+            };
+            let strings = |array: &dyn Array| match array.dtype() {
+                ArrowDataType::Dictionary(IntegerType::Int8, _, _) => {
+                    let array = array
+                        .as_any()
+                        .downcast_ref::<DictionaryArray<i8>>()
+                        .unwrap();
+                    dictionary_strings(array)
+                },
+                _ => {
+                    let array = array
+                        .as_any()
+                        .downcast_ref::<DictionaryArray<i32>>()
+                        .unwrap();
+                    dictionary_strings(array)
+                },
+            };
+            let expected = [x.to_boxed(), y.to_boxed(), item.to_boxed()];
+            assert_eq!(strings(array.as_ref()), strings(expected[column].as_ref()));
+        }
+    };
+
+    for projection in [vec![0, 1, 2], vec![1], vec![2], vec![1, 2]] {
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let read = FileReader::new(reader, metadata, Some(projection.clone()), None)
+            .collect::<PolarsResult<Vec<_>>>()?;
+        check(&read[0], &projection);
+
+        let mut reader = Cursor::new(&stream);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let read = StreamReader::new(reader, metadata, Some(projection.clone()))
+            .map(|state| state.map(|state| state.unwrap()))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        check(&read[0], &projection);
+    }
+
+    for column in 0..3 {
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let arrays = column_iter(reader, metadata, column, Default::default())?
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let read = RecordBatchT::new(4, Arc::new(schema.try_project_indices(&[column])?), arrays);
+        check(&read, &[column]);
+    }
+    Ok(())
+}
+
+#[test]
+fn write_with_compression_by_column() -> PolarsResult<()> {
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("a".into(), ArrowDataType::Int64, false),
+        Field::new("b".into(), ArrowDataType::Utf8, true),
+        Field::new("c".into(), ArrowDataType::Int64, true),
+    ]));
+    let batch = RecordBatchT::new(
+        1000,
+        schema.clone(),
+        vec![
+            Int64Array::from_vec((0..1000).collect()).boxed(),
+            Utf8Array::<i32>::from_iter((0..1000).map(|i| Some(format!("uncompressed-{i}"))))
+                .boxed(),
+            Int64Array::from_iter((0..1000).map(|i| (i % 3 != 0).then_some(i % 7))).boxed(),
+        ],
+    );
+
+    let cases = [
+        (
+            Some(Compression::LZ4),
+            CompressionByColumn::from_iter([(1, None), (2, Some(Compression::LZ4))]),
+        ),
+        (
+            None,
+            CompressionByColumn::from_iter([(0, Some(Compression::ZSTD))]),
+        ),
+    ];
+    for (compression, compression_by_column) in cases {
+        let options = WriteOptions { compression };
+
+        let mut writer = FileWriter::new(vec![], schema.clone(), None, options);
+        writer.set_compression_by_column(compression_by_column.clone())?;
+        writer.start()?;
+        writer.write(&batch, None)?;
+        writer.finish()?;
+        let file = writer.into_inner();
+        let contains = |data: &[u8], value: &[u8]| data.windows(value.len()).any(|w| w == value);
+        // the strings are not compressed
+        assert!(contains(&file, b"uncompressed-999"));
+
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let read =
+            FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(read, vec![batch.clone()]);
+
+        let mut writer = StreamWriter::new(vec![], options);
+        writer.set_compression_by_column(compression_by_column)?;
+        writer.start(&schema, None)?;
+        writer.write(&batch, None)?;
+        writer.finish()?;
+        let stream = writer.into_inner();
+
+        let mut reader = Cursor::new(&stream);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let read = StreamReader::new(reader, metadata, None)
+            .map(|state| state.map(|state| state.unwrap()))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(read, vec![batch.clone()]);
+    }
+
+    let options = WriteOptions {
+        compression: Some(Compression::LZ4),
+    };
+    let compression_by_column = CompressionByColumn::from_iter([(0, Some(Compression::ZSTD))]);
+    let mut writer = FileWriter::new(vec![], schema.clone(), None, options);
+    let err = writer
+        .set_compression_by_column(compression_by_column.clone())
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("can only be compressed with a single codec")
+    );
+    let mut writer = StreamWriter::new(vec![], options);
+    assert!(
+        writer
+            .set_compression_by_column(compression_by_column)
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn read_into_single_arena() -> PolarsResult<()> {
+    let item = |dtype| Box::new(Field::new(PlSmallStr::from_static("item"), dtype, true));
+    let dictionary_values = Utf8Array::<i64>::from_slice(["a", "b", "c"]).boxed();
+    let batch = |offset: i32| -> PolarsResult<Vec<Box<dyn Array>>> {
+        Ok(vec![
+            Int32Array::from([Some(offset), None, Some(offset + 2)]).boxed(),
+            BooleanArray::from([Some(true), None, Some(false)]).boxed(),
+            Utf8Array::<i32>::from([Some("x"), Some("yy"), None]).boxed(),
+            Utf8ViewArray::from_slice([Some("a string that is not inlined"), None, Some("z")])
+                .boxed(),
+            ListArray::<i32>::new(
+                ArrowDataType::List(item(ArrowDataType::Int64)),
+                vec![0, 2, 2, 3].try_into()?,
+                Int64Array::from_slice([1, 2, offset as i64]).boxed(),
+                Some([true, false, true].into()),
+            )
+            .boxed(),
+            StructArray::new(
+                ArrowDataType::Struct(vec![Field::new(
+                    PlSmallStr::from_static("x"),
+                    ArrowDataType::Int8,
+                    true,
+                )]),
+                3,
+                vec![Int8Array::from([Some(1), None, Some(3)]).boxed()],
+                None,
+            )
+            .boxed(),
+            DictionaryArray::try_from_keys(
+                PrimitiveArray::<i32>::from([Some(2), None, Some(0)]),
+                dictionary_values.clone(),
+            )?
+            .boxed(),
+        ])
+    };
+    let arrays = batch(0)?;
+    let schema = Arc::new(ArrowSchema::from_iter(arrays.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batches = [
+        RecordBatchT::try_new(3, schema.clone(), arrays)?,
+        RecordBatchT::try_new(3, schema.clone(), batch(10)?)?,
+    ];
+    let file = write(&batches, &schema, None, Some(Compression::LZ4))?;
+
+    let mut reader = Cursor::new(&file);
+    let metadata = read_file_metadata(&mut reader)?;
+    let packed = read_into_arena(FileReader::new(reader, metadata, None, None))?;
+    assert_eq!(packed.batches(), batches);
+
+    let arena = packed.arena().as_slice().as_ptr_range();
+    let in_arena = |ptr: *const u8| arena.contains(&ptr);
+    for batch in packed.batches() {
+        let ints = batch.arrays()[0]
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert!(in_arena(ints.values().as_ptr().cast()));
+        assert!(in_arena(ints.validity().unwrap().as_slice().0.as_ptr()));
+        let strings = batch.arrays()[2]
+            .as_any()
+            .downcast_ref::<Utf8Array<i32>>()
+            .unwrap();
+        assert!(in_arena(strings.values().as_ptr()));
+        assert!(in_arena(strings.offsets().buffer().as_ptr().cast()));
+    }
+
+    // the dictionary shared by the batches is packed once
+    let dictionary_values = packed
+        .batches()
+        .iter()
+        .map(|batch| {
+            let dictionary = batch.arrays()[6]
+                .as_any()
+                .downcast_ref::<DictionaryArray<i32>>()
+                .unwrap();
+            let values = dictionary.values().as_any();
+            values
+                .downcast_ref::<Utf8Array<i64>>()
+                .unwrap()
+                .values()
+                .as_ptr()
+        })
+        .collect::<Vec<_>>();
+    assert!(in_arena(dictionary_values[0]));
+    assert_eq!(dictionary_values[0], dictionary_values[1]);
+
+    drop(packed);
+    let empty = read_into_arena(std::iter::empty())?;
+    assert!(empty.batches().is_empty() && empty.arena().is_empty());
+    Ok(())
+}
+
+#[test]
+fn read_skipping_validity_when_no_nulls() -> PolarsResult<()> {
+    let columns = [
+        Int64Array::from_vec(vec![1, 2, 3]).boxed(),
+        Int32Array::from([Some(1), None, Some(3)]).boxed(),
+        Int32Array::new(
+            ArrowDataType::Int32,
+            vec![1, 2, 3].into(),
+            Some(Bitmap::from([true, true, true])),
+        )
+        .boxed(),
+    ];
+    let batch = |n: usize| {
+        let columns = columns[..n].to_vec();
+        let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+            |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+        )));
+        RecordBatchT::try_new(3, schema, columns)
+    };
+    let options = WriteOptions { compression: None };
+    let encode = |batch: &RecordBatchT<Box<dyn Array>>| {
+        let mut record = EncodedData::default();
+        encode_record_batch(batch, &options, &mut record);
+        record
+    };
+    let read = |batch: &RecordBatchT<Box<dyn Array>>,
+                record: &EncodedData,
+                skip_validity_when_no_nulls|
+     -> PolarsResult<_> {
+        let mut writer =
+            FileWriter::try_new(vec![], Arc::new(batch.schema().clone()), None, options)?;
+        writer.write_encoded(&[], record)?;
+        writer.finish()?;
+        let mut reader = Cursor::new(writer.into_inner());
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            skip_validity_when_no_nulls,
+            ..Default::default()
+        };
+        let mut batches = FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()?;
+        Ok(batches.remove(0))
+    };
+
+    // without validity buffers for the arrays without nulls
+    let plain = batch(2)?;
+    for skip_validity_when_no_nulls in [false, true] {
+        assert_eq!(
+            read(&plain, &encode(&plain), skip_validity_when_no_nulls)?,
+            plain
+        );
+    }
+
+    // the validity of an array that declares no nulls is not materialized
+    let full = batch(3)?;
+    let read_full = read(&full, &encode(&full), false)?;
+    assert_eq!(read_full, full);
+    assert!(read_full.arrays()[2].validity().is_none());
+    assert!(read(&full, &encode(&full), true).is_err());
+
+    // a writer that declares no nulls for an array that has nulls
+    let record = encode(&plain);
+    let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    let Some(MessageHeader::RecordBatch(header)) = &mut message.header else {
+        unreachable!()
+    };
+    header.nodes.as_mut().unwrap()[1].null_count = 0;
+    let undercounted = EncodedData {
+        ipc_message: Builder::new().finish(&message, None).to_vec(),
+        arrow_data: record.arrow_data,
+    };
+    let read_undercounted = read(&plain, &undercounted, false)?;
+    assert!(read_undercounted.arrays()[1].validity().is_none());
+    assert_eq!(read_undercounted.arrays()[0], plain.arrays()[0]);
+    assert!(read(&plain, &undercounted, true).is_err());
+    Ok(())
+}
+
+#[test]
+fn diff_files_by_schema_and_dictionaries() -> PolarsResult<()> {
+    let file = |values: &[&str], extra: bool| -> PolarsResult<Vec<u8>> {
+        let mut columns = vec![
+            Int32Array::from_slice([1, 2, 3]).boxed(),
+            DictionaryArray::try_from_keys(
+                PrimitiveArray::<i32>::from_slice([0, 1, 2]),
+                Utf8Array::<i32>::from_slice(values).boxed(),
+            )?
+            .boxed(),
+        ];
+        if extra {
+            columns.push(Utf8Array::<i32>::from_slice(["x", "y", "z"]).boxed());
+        }
+        let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+            |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+        )));
+        let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+        write(&[batch], &schema, None, None)
+    };
+    let a = file(&["a", "b", "c"], false)?;
+    let b = file(&["a", "c", "d"], true)?;
+
+    let diff = diff_files(&mut Cursor::new(&a), &mut Cursor::new(&b))?;
+    assert_eq!(
+        diff,
+        FileDiff {
+            fields: vec![FieldChange::Added {
+                name: "c2".into(),
+                dtype: ArrowDataType::Utf8,
+            }],
+            dictionaries: vec![DictionaryChange {
+                id: 0,
+                added: vec!["d".to_string()],
+                removed: vec!["b".to_string()],
+            }],
+            ..Default::default()
+        }
+    );
+
+    let diff = diff_files(&mut Cursor::new(&b), &mut Cursor::new(&a))?;
+    assert_eq!(
+        diff.fields,
+        [FieldChange::Removed {
+            name: "c2".into(),
+            dtype: ArrowDataType::Utf8,
+        }]
+    );
+    assert_eq!(diff.dictionaries[0].added, ["b"]);
+    assert!(diff_files(&mut Cursor::new(&a), &mut Cursor::new(&a))?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn read_with_variadic_buffer_cap() -> PolarsResult<()> {
+    let array = Utf8ViewArray::from_slice([Some("a string that is not inlined"), None, Some("z")]);
+    assert_eq!(array.data_buffers().len(), 1);
+    let schema = prep_schema(&array);
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![array.boxed()])?;
+
+    let options = WriteOptions { compression: None };
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let file = |record: &EncodedData| -> PolarsResult<Vec<u8>> {
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&[], record)?;
+        writer.finish()?;
+        Ok(writer.into_inner())
+    };
+    let read = |file: &[u8], max_variadic_buffers| -> PolarsResult<_> {
+        let mut reader = Cursor::new(file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            max_variadic_buffers,
+            ..Default::default()
+        };
+        FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+
+    let valid = file(&record)?;
+    assert_eq!(read(&valid, None)?, [batch.clone()]);
+    assert_eq!(read(&valid, Some(1))?, [batch.clone()]);
+    let err = read(&valid, Some(0)).unwrap_err();
+    assert!(err.to_string().contains("InvalidVariadicBufferCount"));
+
+    // a crafted message that declares an absurd number of variadic buffers
+    for count in [1 << 40, -1] {
+        let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+        let mut message: Message = message.try_into().unwrap();
+        let Some(MessageHeader::RecordBatch(header)) = &mut message.header else {
+            unreachable!()
+        };
+        header.variadic_buffer_counts = Some(vec![count]);
+        let crafted = EncodedData {
+            ipc_message: Builder::new().finish(&message, None).to_vec(),
+            arrow_data: record.arrow_data.clone(),
+        };
+        let err = read(&file(&crafted)?, None).unwrap_err();
+        assert!(err.to_string().contains("InvalidVariadicBufferCount"));
+    }
+    Ok(())
+}
+
+#[test]
+fn read_batch_compression() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(3)?;
+    for compression in [None, Some(Compression::ZSTD), Some(Compression::LZ4)] {
+        let file = write(&[batch.clone()], &schema, None, compression)?;
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let mut reader = FileReader::new(reader, metadata, None, None);
+        assert_eq!(reader.compression(), None);
+        reader.next().unwrap()?;
+        assert_eq!(reader.compression(), compression);
+
+        let mut writer = StreamWriter::new(vec![], WriteOptions { compression });
+        writer.start(&schema, None)?;
+        writer.write(&batch, None)?;
+        writer.finish()?;
+        let stream = writer.into_inner();
+        let mut reader = Cursor::new(&stream);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let mut reader = StreamReader::new(reader, metadata, None);
+        assert_eq!(reader.compression(), None);
+        reader.next().unwrap()?;
+        assert_eq!(reader.compression(), compression);
+    }
+    Ok(())
+}
+
+#[test]
+fn read_non_seekable_via_spill() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(4)?;
+    let batches = vec![batch.clone(), batch];
+    let file = write(&batches, &schema, None, Some(Compression::LZ4))?;
+
+    for (threshold, is_spilled) in [
+        (usize::MAX, false),
+        (file.len(), false),
+        (file.len() - 1, true),
+        (0, true),
+    ] {
+        // `&[u8]` implements `Read` but not `Seek`, like a pipe
+        let reader = read_spilled(file.as_slice(), threshold)?;
+        assert_eq!(reader.schema(), schema.as_ref());
+        let read = reader.collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(read, batches);
+
+        assert_eq!(spill(file.as_slice(), threshold)?.is_spilled(), is_spilled);
+    }
+    Ok(())
+}
+
+/// Round-trips a dictionary-encoded column with keys of type `K`, followed by a column that is
+/// only read correctly if the keys are read with their declared width.
+fn round_trip_dictionary_keys<K: DictionaryKey>(keys: &[Option<usize>]) -> PolarsResult<()> {
+    let keys = keys
+        .iter()
+        .map(|key| key.map(|key| K::try_from(key).ok().unwrap()))
+        .collect::<PrimitiveArray<K>>();
+    let values = Utf8Array::<i64>::from_slice(["a", "b", "c", "d"]).boxed();
+    let dictionary = DictionaryArray::try_from_keys(keys, values)?;
+    let length = dictionary.len();
+    let columns = vec![
+        dictionary.boxed(),
+        Int64Array::from_iter((0..length as i64).map(Some)).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(length, schema.clone(), columns)?;
+    for compression in [None, Some(Compression::LZ4)] {
+        round_trip(batch.clone(), schema.clone(), None, compression)?;
+    }
+
+    let mut reader = Cursor::new(write(&[batch.clone()], &schema, None, None)?);
+    let metadata = read_file_metadata(&mut reader)?;
+    let read =
+        FileReader::new(reader, metadata, Some(vec![1]), None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read[0].arrays(), &batch.arrays()[1..]);
+    Ok(())
+}
+
+#[test]
+fn read_dictionary_key_types() -> PolarsResult<()> {
+    let keys = [Some(3), None, Some(0), Some(2), Some(1), Some(3), None];
+    round_trip_dictionary_keys::<i8>(&keys)?;
+    round_trip_dictionary_keys::<i16>(&keys)?;
+    round_trip_dictionary_keys::<i32>(&keys)?;
+    round_trip_dictionary_keys::<i64>(&keys)?;
+    round_trip_dictionary_keys::<u8>(&keys)?;
+    round_trip_dictionary_keys::<u16>(&keys)?;
+    round_trip_dictionary_keys::<u32>(&keys)?;
+    round_trip_dictionary_keys::<u64>(&keys)?;
+    Ok(())
+}
+
+#[test]
+fn read_in_reverse() -> PolarsResult<()> {
+    // the values of column "a" are the positions of their rows in the file
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("a".into(), ArrowDataType::Int32, true),
+        Field::new("b".into(), ArrowDataType::LargeUtf8, true),
+    ]));
+    let batches = [0..3, 3..8, 8..9, 9..13]
+        .into_iter()
+        .map(|rows| {
+            let a = Int32Array::from_iter(rows.clone().map(|row| (row % 4 != 1).then_some(row)));
+            let b = Utf8Array::<i64>::from_iter_values(rows.clone().map(|row| row.to_string()));
+            RecordBatchT::try_new(rows.len(), schema.clone(), vec![a.boxed(), b.boxed()])
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let file = write(&batches, &schema, None, None)?;
+
+    let read = |options: ReadOptions, limit| {
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        FileReader::new(reader, metadata, None, limit)
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    // returns the rows of every batch, from the column "b" at position `column`
+    let rows = |batches: &[RecordBatchT<Box<dyn Array>>], column: usize| {
+        batches
+            .iter()
+            .map(|batch| {
+                let b = batch.arrays()[column]
+                    .as_any()
+                    .downcast_ref::<Utf8Array<i64>>()
+                    .unwrap();
+                b.values_iter()
+                    .map(|value| value.parse().unwrap())
+                    .collect::<Vec<usize>>()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let reversed = read(
+        ReadOptions {
+            reverse: true,
+            ..Default::default()
+        },
+        None,
+    )?;
+    assert_eq!(reversed, batches.iter().rev().cloned().collect::<Vec<_>>());
+
+    let options = ReadOptions {
+        reverse: true,
+        reverse_rows: true,
+        ..Default::default()
+    };
+    let reversed = read(options.clone(), None)?;
+    assert_eq!(
+        rows(&reversed, 1),
+        [
+            vec![12, 11, 10, 9],
+            vec![8],
+            vec![7, 6, 5, 4, 3],
+            vec![2, 1, 0]
+        ]
+    );
+    for batch in &reversed {
+        let [a, b] = batch.arrays() else { panic!() };
+        let b = b.as_any().downcast_ref::<Utf8Array<i64>>().unwrap();
+        let expected = b
+            .values_iter()
+            .map(|row| row.parse::<i32>().unwrap())
+            .map(|row| (row % 4 != 1).then_some(row));
+        assert_eq!(a.as_ref(), &Int32Array::from_iter(expected) as &dyn Array);
+    }
+
+    // the limit keeps the last rows of a reversed record batch; as when reading forward, the
+    // record batches past the limit are read empty
+    let limited = read(options.clone(), Some(6))?;
+    assert_eq!(
+        rows(&limited, 1),
+        [vec![12, 11, 10, 9], vec![8], vec![7], vec![]]
+    );
+    let limited = read(
+        ReadOptions {
+            reverse_rows: true,
+            ..Default::default()
+        },
+        Some(5),
+    )?;
+    assert_eq!(
+        rows(&limited, 1),
+        [vec![2, 1, 0], vec![7, 6], vec![], vec![]]
+    );
+
+    // the row index counts the rows in file order
+    let indexed = read(
+        ReadOptions {
+            row_index: Some(("index".into(), 10)),
+            max_rows_per_output_batch: Some(2),
+            ..options
+        },
+        None,
+    )?;
+    let mut read_rows = vec![];
+    for (batch, rows) in indexed.iter().zip(rows(&indexed, 2)) {
+        let index = batch.arrays()[0]
+            .as_any()
+            .downcast_ref::<PrimitiveArray<IdxSize>>()
+            .unwrap();
+        for (index, row) in index.values_iter().zip(rows) {
+            assert_eq!(*index as usize, 10 + row);
+            read_rows.push(row);
+        }
+    }
+    assert_eq!(read_rows, (0..13).rev().collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn read_projection_by_field_id() -> PolarsResult<()> {
+    let field = |name: &str, id: Option<(&str, i64)>| {
+        let field = Field::new(name.into(), ArrowDataType::Int32, true);
+        match id {
+            Some((key, id)) => field
+                .with_metadata([(PlSmallStr::from(key), PlSmallStr::from(id.to_string()))].into()),
+            None => field,
+        }
+    };
+    let column = |values: &[i32]| Int32Array::from_slice(values).boxed();
+    // the same columns, reordered and renamed, with a column without field id inserted
+    let before = Arc::new(ArrowSchema::from_iter([
+        field("a", Some(("PARQUET:field_id", 1))),
+        field("b", Some(("PARQUET:field_id", 2))),
+        field("c", Some(("polars:field_id", 3))),
+    ]));
+    let after = Arc::new(ArrowSchema::from_iter([
+        field("c_renamed", Some(("polars:field_id", 3))),
+        field("x", None),
+        field("a", Some(("PARQUET:field_id", 1))),
+        field("b", Some(("PARQUET:field_id", 2))),
+    ]));
+    let files = [
+        write(
+            &[RecordBatchT::try_new(
+                2,
+                before.clone(),
+                vec![column(&[1, 2]), column(&[3, 4]), column(&[5, 6])],
+            )?],
+            &before,
+            None,
+            None,
+        )?,
+        write(
+            &[RecordBatchT::try_new(
+                2,
+                after.clone(),
+                vec![
+                    column(&[5, 6]),
+                    column(&[0, 0]),
+                    column(&[1, 2]),
+                    column(&[3, 4]),
+                ],
+            )?],
+            &after,
+            None,
+            None,
+        )?,
+    ];
+
+    for file in &files {
+        let mut reader = Cursor::new(file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let projection = prepare_projection_by_field_id(&metadata.schema, &[3, 1])?;
+        let batches =
+            FileReader::new_with_projection_info(reader, metadata, Some(projection), None)
+                .collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(batches[0].arrays(), [column(&[5, 6]), column(&[1, 2])]);
+    }
+
+    let Err(err) = prepare_projection_by_field_id(&after, &[1, 4]) else {
+        panic!()
+    };
+    assert!(err.to_string().contains("field id 4"));
+    let Err(err) = prepare_projection_by_field_id(&after, &[1, 1]) else {
+        panic!()
+    };
+    assert!(matches!(err, PolarsError::Duplicate(_)));
+    Ok(())
+}
+
+#[test]
+fn read_dictionary_cardinality() -> PolarsResult<()> {
+    let values = Utf8Array::<i64>::from_slice(["a", "b", "c", "d", "e"]).boxed();
+    let keys = PrimitiveArray::<u32>::from([Some(4), None, Some(0), Some(4), Some(2)]);
+    let dictionary = DictionaryArray::try_from_keys(keys, values)?.boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+        "a".into(),
+        dictionary.dtype().clone(),
+        true,
+    )]));
+    let batch = RecordBatchT::try_new(dictionary.len(), schema.clone(), vec![dictionary])?;
+
+    let mut reader = Cursor::new(write(&[batch.clone()], &schema, None, None)?);
+    let metadata = read_file_metadata(&mut reader)?;
+    let id = metadata.ipc_schema.fields[0].dictionary_id.unwrap();
+    let mut reader = FileReader::new(reader, metadata, None, None);
+    assert_eq!(reader.dictionary_cardinality(id), None);
+    reader.next().unwrap()?;
+    assert_eq!(reader.dictionary_cardinality(id), Some(5));
+    assert_eq!(reader.dictionary_cardinality(id + 1), None);
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    writer.write(&batch, None)?;
+    writer.finish()?;
+    let mut stream = Cursor::new(writer.into_inner());
+    let metadata = read_stream_metadata(&mut stream)?;
+    let mut reader = StreamReader::new(stream, metadata, None);
+    assert_eq!(reader.dictionary_cardinality(id), None);
+    reader.next().unwrap()?;
+    assert_eq!(reader.dictionary_cardinality(id), Some(5));
+    Ok(())
+}
+
+#[test]
+fn read_with_missing_dictionary() -> PolarsResult<()> {
+    let values = Utf8Array::<i64>::from_slice(["a", "b"]).boxed();
+    let keys = PrimitiveArray::<u32>::from([Some(1), None, Some(0)]);
+    let columns = vec![
+        DictionaryArray::try_from_keys(keys, values)?.boxed(),
+        Int32Array::from_slice([1, 2, 3]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+    // a file without the dictionary batch of its dictionary-encoded column
+    let options = WriteOptions { compression: None };
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+    writer.write_encoded(&[], &record)?;
+    writer.finish()?;
+    let file = writer.into_inner();
+
+    let read = |missing_dictionary_policy| {
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        FileReader::new(reader, metadata, None, None)
+            .with_options(ReadOptions {
+                missing_dictionary_policy,
+                ..Default::default()
+            })
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    let err = read(MissingDictionaryPolicy::Fail).unwrap_err();
+    assert!(
+        err.to_string().contains("MissingDictionary { id: 0 }"),
+        "{err}"
+    );
+
+    let read = read(MissingDictionaryPolicy::Empty)?;
+    let [dictionary, column] = read[0].arrays() else {
+        panic!()
+    };
+    assert_eq!(dictionary.dtype(), batch.arrays()[0].dtype());
+    assert_eq!(dictionary.null_count(), 3);
+    assert_eq!(column, &batch.arrays()[1]);
+    Ok(())
+}
+
+#[test]
+fn read_sample_of_first_rows() -> PolarsResult<()> {
+    let values = Utf8Array::<i64>::from_slice(["a", "b", "c"]).boxed();
+    let batch = |rows: std::ops::Range<i32>| {
+        let keys = rows.clone().map(|row| Some(row as u32 % 3)).collect();
+        let columns = vec![
+            Int32Array::from_iter(rows.clone().map(Some)).boxed(),
+            DictionaryArray::try_from_keys(keys, values.clone())?.boxed(),
+        ];
+        let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+            |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+        )));
+        RecordBatchT::try_new(rows.len(), schema, columns)
+    };
+    let batches = [batch(0..4)?, batch(4..5)?, batch(5..8)?];
+    let schema = batches[0].schema().clone().into();
+
+    for compression in [None, Some(Compression::LZ4)] {
+        let file = write(&batches, &schema, None, compression)?;
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions::default();
+
+        let sample = sample_first_rows(&mut reader, &metadata, 1, &options)?;
+        assert_eq!(sample.len(), 3);
+        assert_eq!(
+            sample.arrays()[0].as_ref(),
+            &Int32Array::from_slice([0, 4, 5]) as &dyn Array
+        );
+        let dictionary = sample.arrays()[1]
+            .as_any()
+            .downcast_ref::<DictionaryArray<u32>>()
+            .unwrap();
+        let strings = dictionary
+            .iter_typed::<Utf8Array<i64>>()?
+            .map(|value| value.unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(strings, ["a", "b", "c"]);
+
+        // the rows of blocks that have fewer rows are all sampled
+        let sample = sample_first_rows(&mut reader, &metadata, 2, &options)?;
+        assert_eq!(
+            sample.arrays()[0].as_ref(),
+            &Int32Array::from_slice([0, 1, 4, 5, 6]) as &dyn Array
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn read_cancelled() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(4)?;
+    let batches = vec![batch.clone(), batch.clone(), batch];
+    let file = write(&batches, &schema, None, None)?;
+
+    for (parallelism, projection) in [
+        (Parallelism::Serial, None),
+        (Parallelism::Serial, Some(vec![1, 3])),
+        (Parallelism::Threads(2), None),
+    ] {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        let mut reader =
+            FileReader::new(reader, metadata, projection, None).with_options(ReadOptions {
+                parallelism,
+                cancelled: Some(cancelled.clone()),
+                ..Default::default()
+            });
+        reader.next().unwrap()?;
+        cancelled.store(true, Ordering::Relaxed);
+        let err = reader.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("cancelled"), "{err}");
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_threads_reuses_threads() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(4)?;
+    let batches = vec![batch; 100];
+    let data = write(&batches, &schema, None, None)?;
+
+    // the threads that decode the columns allocate their buffers
+    let threads = Arc::new(Mutex::new(std::collections::HashSet::new()));
+    let allocator = {
+        let threads = threads.clone();
+        BufferAllocator::new(move |size| {
+            threads.lock().unwrap().insert(std::thread::current().id());
+            Vec::with_capacity(size)
+        })
+    };
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let options = ReadOptions {
+        allocator: Some(allocator),
+        parallelism: Parallelism::Threads(2),
+        ..Default::default()
+    };
+    let read = FileReader::new(reader, metadata, None, None)
+        .with_options(options)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read, batches);
+    // spawning threads for every record batch takes at least one new thread per batch
+    let threads = threads.lock().unwrap().len();
+    assert!(threads < batches.len(), "{threads}");
+    Ok(())
+}
+
+#[test]
+fn read_writer_info() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(2)?;
+    let write = |level: Option<&str>| {
+        let options = WriteOptions {
+            compression: Some(Compression::ZSTD),
+        };
+        let mut writer = FileWriter::new(vec![], schema.clone(), None, options);
+        if let Some(level) = level {
+            writer.set_custom_schema_metadata(Arc::new(
+                [(COMPRESSION_LEVEL_KEY.into(), level.into())].into(),
+            ));
+        }
+        writer.start()?;
+        writer.write(&batch, None)?;
+        writer.finish()?;
+        PolarsResult::Ok(writer.into_inner())
+    };
+
+    let mut reader = Cursor::new(write(Some("19"))?);
+    let metadata = read_file_metadata(&mut reader)?;
+    let info = metadata.writer_info()?;
+    assert_eq!(
+        info,
+        WriterInfo {
+            compression_level: Some(19)
+        }
+    );
+    let mut reader = FileReader::new(reader, metadata, None, None);
+    reader.next().unwrap()?;
+    let codec = reader.compression().unwrap();
+    assert_eq!(codec, Compression::ZSTD);
+    assert_eq!(info.compression_level_or_default(codec), 19);
+
+    // without a recorded level, the level is the default level of the codec
+    let mut reader = Cursor::new(write(None)?);
+    let info = read_file_metadata(&mut reader)?.writer_info()?;
+    assert_eq!(info.compression_level, None);
+    assert_eq!(info.compression_level_or_default(Compression::ZSTD), 3);
+
+    let mut reader = Cursor::new(write(Some("high"))?);
+    assert!(read_file_metadata(&mut reader)?.writer_info().is_err());
+    Ok(())
+}
+
+#[test]
+fn read_with_max_batches() -> PolarsResult<()> {
+    let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+        "a".into(),
+        ArrowDataType::Int32,
+        true,
+    )]));
+    let batches = [0..1, 1..11, 11..12, 12..40, 40..41]
+        .into_iter()
+        .map(|rows| {
+            let a = Int32Array::from_vec(rows.clone().collect());
+            RecordBatchT::try_new(rows.len(), schema.clone(), vec![a.boxed()])
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let file = write(&batches, &schema, None, None)?;
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    for batch in &batches {
+        writer.write(batch, None)?;
+    }
+    writer.finish()?;
+    let stream = writer.into_inner();
+
+    let options = |max_rows_per_output_batch| ReadOptions {
+        max_batches: Some(2),
+        max_rows_per_output_batch,
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(&file);
+    let metadata = read_file_metadata(&mut reader)?;
+    let read = FileReader::new(reader, metadata, None, None)
+        .with_options(options(None))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read, batches[..2]);
+
+    let mut reader = Cursor::new(&stream);
+    let metadata = read_stream_metadata(&mut reader)?;
+    let read = StreamReader::new(reader, metadata, None)
+        .with_options(options(None))
+        .map(|state| state.map(|state| state.unwrap()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read, batches[..2]);
+
+    // the output batches of a split record batch are all returned
+    let mut reader = Cursor::new(&file);
+    let metadata = read_file_metadata(&mut reader)?;
+    let read = FileReader::new(reader, metadata, None, None)
+        .with_options(options(Some(4)))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(
+        read.iter().map(|batch| batch.len()).collect::<Vec<_>>(),
+        [1, 4, 4, 2]
+    );
+    Ok(())
+}
+
+#[test]
+fn read_zero_copy_slices() -> PolarsResult<()> {
+    let ints = Int64Array::from_iter((0..10).map(|i| (i % 3 != 0).then_some(i)));
+    let schema = prep_schema(&ints);
+    let batch = RecordBatchT::try_new(10, schema.clone(), vec![ints.boxed()])?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    let read = |contiguous| {
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            max_rows_per_output_batch: Some(4),
+            contiguous,
+            ..Default::default()
+        };
+        FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+    let ints = |batch: &RecordBatchT<Box<dyn Array>>| {
+        batch.arrays()[0]
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap()
+            .clone()
+    };
+
+    // the slices are views into the buffers of the decoded record batch, at their offset
+    let sliced = read(false)?;
+    let first = ints(&sliced[0]);
+    for (i, batch) in sliced.iter().enumerate() {
+        let slice = ints(batch);
+        assert_eq!(slice.values().offset(), 4 * i);
+        assert_eq!(
+            slice.values().as_ptr(),
+            first.values().as_ptr().wrapping_add(4 * i)
+        );
+        let (bytes, bit_offset, _) = slice.validity().unwrap().as_slice();
+        assert_eq!(bit_offset, (4 * i) % 8);
+        assert_eq!(
+            bytes.as_ptr(),
+            first
+                .validity()
+                .unwrap()
+                .as_slice()
+                .0
+                .as_ptr()
+                .wrapping_add(4 * i / 8)
+        );
+    }
+
+    // unless they are copied into buffers of their own
+    let contiguous = read(true)?;
+    for (i, batch) in contiguous.iter().enumerate().skip(1) {
+        let slice = ints(batch);
+        assert_eq!(slice.values().offset(), 0);
+        assert_ne!(
+            slice.values().as_ptr(),
+            ints(&contiguous[0]).values().as_ptr().wrapping_add(4 * i)
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_schema_rewrite() -> PolarsResult<()> {
+    let timestamps = Int64Array::from_slice([0, 3_600_000_000]).to(ArrowDataType::Timestamp(
+        TimeUnit::Microsecond,
+        Some("UTC".into()),
+    ));
+    let ints = Int32Array::from_slice([1, 2]);
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("t".into(), timestamps.dtype().clone(), true),
+        Field::new("i".into(), ints.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(2, schema.clone(), vec![timestamps.boxed(), ints.boxed()])?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+
+    let relabeled =
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("Europe/Amsterdam".into()));
+    let relabel = |mut schema: ArrowSchema| {
+        schema.get_mut("t").unwrap().dtype = relabeled.clone();
+        Ok(schema)
+    };
+    let mut reader = Cursor::new(&data);
+    let mut metadata = read_file_metadata(&mut reader)?;
+    metadata.rewrite_schema(relabel)?;
+    let read = FileReader::new(reader, metadata, None, None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read[0].arrays()[0].dtype(), &relabeled);
+    assert_eq!(read[0].schema().get("t").unwrap().dtype, relabeled);
+    let values = read[0].arrays()[0]
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(values.values().as_slice(), [0, 3_600_000_000]);
+    assert_eq!(read[0].arrays()[1], batch.arrays()[1]);
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    writer.write(&batch, None)?;
+    writer.finish()?;
+    let mut stream = Cursor::new(writer.into_inner());
+    let mut metadata = read_stream_metadata(&mut stream)?;
+    metadata.rewrite_schema(relabel)?;
+    let state = StreamReader::new(stream, metadata, None).next().unwrap()?;
+    assert_eq!(state.unwrap().arrays()[0].dtype(), &relabeled);
+
+    // a rewrite that changes the buffers of a field is rejected
+    let mut reader = Cursor::new(&data);
+    let mut metadata = read_file_metadata(&mut reader)?;
+    let err = metadata
+        .rewrite_schema(|mut schema| {
+            schema.get_mut("i").unwrap().dtype = ArrowDataType::Int64;
+            Ok(schema)
+        })
+        .unwrap_err();
+    assert!(matches!(err, PolarsError::SchemaMismatch(_)), "{err}");
+    let err = metadata
+        .rewrite_schema(|schema| Ok(schema.iter_values().take(1).cloned().collect()))
+        .unwrap_err();
+    assert!(matches!(err, PolarsError::SchemaMismatch(_)), "{err}");
+    Ok(())
+}
+
+#[test]
+fn read_block_descriptors() -> PolarsResult<()> {
+    let values = Utf8Array::<i64>::from_slice(["a", "b"]).boxed();
+    let keys = PrimitiveArray::<u32>::from_slice([1, 0, 1]);
+    let columns = vec![
+        DictionaryArray::try_from_keys(keys, values)?.boxed(),
+        Int32Array::from_slice([1, 2, 3]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+    let data = write(&[batch.clone(), batch], &schema, None, None)?;
+
+    let metadata = read_file_metadata(&mut Cursor::new(&data))?;
+    let descriptors = metadata.block_descriptors()?;
+    assert_eq!(
+        descriptors
+            .iter()
+            .map(|descriptor| (descriptor.kind, descriptor.index))
+            .collect::<Vec<_>>(),
+        [
+            (BlockKind::Dictionary, 0),
+            (BlockKind::RecordBatch, 0),
+            (BlockKind::RecordBatch, 1)
+        ]
+    );
+    for (descriptor, next) in descriptors.iter().zip(descriptors.iter().skip(1)) {
+        // the blocks are laid out back to back
+        assert_eq!(
+            descriptor.offset + descriptor.metadata_len + descriptor.body_len,
+            next.offset
+        );
+    }
+    for descriptor in &descriptors {
+        // the message of a block is prefixed by the continuation marker and its length
+        let start = descriptor.offset as usize;
+        assert_eq!(data[start..start + 4], [0xff; 4]);
+        let length = i32::from_le_bytes(data[start + 4..start + 8].try_into().unwrap());
+        assert_eq!(length as u64 + 8, descriptor.metadata_len);
+
+        let message = MessageRef::read_as_root(&data[start + 8..start + 8 + length as usize])
+            .map_err(|err| polars_err!(ComputeError: "{err}"))?;
+        assert_eq!(message.body_length().unwrap() as u64, descriptor.body_len);
+        let kind = match message.header().unwrap().unwrap() {
+            MessageHeaderRef::DictionaryBatch(_) => BlockKind::Dictionary,
+            MessageHeaderRef::RecordBatch(_) => BlockKind::RecordBatch,
+            _ => panic!(),
+        };
+        assert_eq!(kind, descriptor.kind);
+    }
+    Ok(())
+}
+
+#[test]
+fn read_short_validity() -> PolarsResult<()> {
+    // the nulls are within the first byte of the validity, which is all that is written
+    let array = Int32Array::from_iter((0..20).map(|i| (i % 3 != 1 || i >= 8).then_some(i)));
+    let schema = prep_schema(&array);
+    let batch = RecordBatchT::try_new(20, schema.clone(), vec![array.clone().boxed()])?;
+
+    let options = WriteOptions { compression: None };
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    let Some(MessageHeader::RecordBatch(ipc_batch)) = &mut message.header else {
+        unreachable!()
+    };
+    ipc_batch.buffers.as_mut().unwrap()[0].length = 1;
+    record.ipc_message = Builder::new().finish(&message, None).to_vec();
+
+    let mut writer = FileWriter::try_new(vec![], schema, None, options)?;
+    writer.write_encoded(&[], &record)?;
+    writer.finish()?;
+    let data = writer.into_inner();
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches =
+        FileReader::new(reader, metadata.clone(), None, None).collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches[0].arrays()[0].as_ref(), &array as &dyn Array);
+    assert_eq!(batches[0].arrays()[0].null_count(), 3);
+
+    let options = ReadOptions {
+        strict_validity_length: true,
+        ..Default::default()
+    };
+    let mut reader =
+        FileReader::new(Cursor::new(&data), metadata, None, None).with_options(options);
+    assert!(reader.next().unwrap().is_err());
+    Ok(())
+}
+
+#[test]
+fn read_scan_source() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(4)?;
+    let data = write(&[batch.clone(), batch.clone(), batch], &schema, None, None)?;
+
+    let source = IpcScanSource::try_new(Cursor::new(data))?;
+    assert_eq!(source.schema(), schema.as_ref());
+    assert_eq!(source.row_count_estimate(), Some(6));
+
+    let pushdown = ScanPushdown {
+        projection: Some(prepare_projection(&schema, vec![2, 0])),
+        limit: Some(3),
+        ..Default::default()
+    };
+    let batches = source
+        .scan(pushdown)?
+        .filter(|batch| !matches!(batch, Ok(batch) if batch.is_empty()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let columns = |batch: &RecordBatchT<Box<dyn Array>>| {
+        let names = batch.schema().iter_names().cloned().collect::<Vec<_>>();
+        (names, batch.arrays().to_vec())
+    };
+    assert_eq!(
+        batches.iter().map(columns).collect::<Vec<_>>(),
+        [
+            (
+                vec!["c2".into(), "c0".into()],
+                vec![
+                    Int32Array::from_slice([2, 3]).boxed(),
+                    Int32Array::from_slice([0, 1]).boxed()
+                ]
+            ),
+            (
+                vec!["c2".into(), "c0".into()],
+                vec![
+                    Int32Array::from_slice([2]).boxed(),
+                    Int32Array::from_slice([0]).boxed()
+                ]
+            ),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn read_durations() -> PolarsResult<()> {
+    for unit in [
+        TimeUnit::Second,
+        TimeUnit::Millisecond,
+        TimeUnit::Microsecond,
+        TimeUnit::Nanosecond,
+    ] {
+        let duration = Int64Array::from([Some(-1), None, Some(i64::MAX)])
+            .to(ArrowDataType::Duration(unit))
+            .boxed();
+        let item = Field::new("item".into(), duration.dtype().clone(), true);
+        let nested = StructArray::new(
+            ArrowDataType::Struct(vec![item]),
+            3,
+            vec![duration.clone()],
+            Some(Bitmap::from([true, true, false])),
+        );
+        let columns = vec![
+            duration,
+            nested.boxed(),
+            Int32Array::from([Some(7), Some(8), None]).boxed(),
+        ];
+        let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+            |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+        )));
+        let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+        let options = WriteOptions { compression: None };
+        let encode = || {
+            let mut record = EncodedData::default();
+            encode_record_batch(&batch, &options, &mut record);
+            record
+        };
+        let big_endian = into_big_endian(encode(), &[1, 8, 1, 1, 8, 1, 4]);
+
+        for (record, is_little_endian) in [(encode(), true), (big_endian, false)] {
+            let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+            writer.write_encoded(&[], &record)?;
+            writer.finish()?;
+            let data = writer.into_inner();
+
+            // the columns after the durations are only decoded correctly if the durations are
+            // read and skipped as 8 bytes per value
+            for projection in [vec![0, 1, 2], vec![1, 2], vec![2]] {
+                let mut reader = Cursor::new(&data);
+                let mut metadata = read_file_metadata(&mut reader)?;
+                assert_eq!(metadata.schema, schema);
+                metadata.ipc_schema.is_little_endian = is_little_endian;
+                let batches = FileReader::new(reader, metadata, Some(projection.clone()), None)
+                    .collect::<PolarsResult<Vec<_>>>()?;
+                let expected = projection
+                    .iter()
+                    .map(|&i| batch.arrays()[i].clone())
+                    .collect::<Vec<_>>();
+                assert_eq!(batches[0].arrays(), expected.as_slice());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_shared_dictionary() -> PolarsResult<()> {
+    let a = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from([Some(0), Some(1), Some(0), None]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let b = DictionaryArray::try_from_keys(
+        PrimitiveArray::<u8>::from([Some(1), Some(0), None, Some(2)]),
+        Utf8Array::<i32>::from_slice(["z", "x", "w"]).boxed(),
+    )?;
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new(PlSmallStr::from_static("a"), a.dtype().clone(), true),
+        Field::new(PlSmallStr::from_static("b"), b.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(
+        4,
+        schema.clone(),
+        vec![a.clone().boxed(), b.clone().boxed()],
+    )?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    let options = ReadOptions {
+        shared_dictionary_columns: vec!["a".into(), "b".into()],
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let batches = FileReader::new(reader, metadata, None, None)
+        .with_options(options)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let arrays = batches[0].arrays();
+    let shared_a = arrays[0]
+        .as_any()
+        .downcast_ref::<DictionaryArray<i32>>()
+        .unwrap();
+    let shared_b = arrays[1]
+        .as_any()
+        .downcast_ref::<DictionaryArray<u8>>()
+        .unwrap();
+
+    let values = Utf8Array::<i32>::from_slice(["x", "y", "z", "w"]);
+    assert_eq!(shared_a.values().as_ref(), &values as &dyn Array);
+    assert_eq!(shared_b.values().as_ref(), &values as &dyn Array);
+    assert_eq!(
+        shared_a.keys(),
+        &PrimitiveArray::<i32>::from([Some(0), Some(1), Some(0), None])
+    );
+    assert_eq!(
+        shared_b.keys(),
+        &PrimitiveArray::<u8>::from([Some(0), Some(2), None, Some(3)])
+    );
+    assert_eq!(dictionary_strings(shared_a), dictionary_strings(&a));
+    assert_eq!(dictionary_strings(shared_b), dictionary_strings(&b));
+    Ok(())
+}
+
+#[test]
+fn read_with_complexity_budget() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(10)?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+    let budget = ComplexityBudget {
+        max_fields: 9,
+        ..Default::default()
+    };
+    let err = read_file_metadata_with_budget(&mut Cursor::new(&data), &budget).unwrap_err();
+    assert!(err.to_string().contains("TooManyFields"), "{err}");
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    writer.write(&batch, None)?;
+    writer.finish()?;
+    let stream = writer.into_inner();
+    let err = read_stream_metadata_with_budget(&mut Cursor::new(&stream), &budget).unwrap_err();
+    assert!(err.to_string().contains("TooManyFields"), "{err}");
+
+    let budget = ComplexityBudget {
+        max_fields: 10,
+        max_nodes: 9,
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata_with_budget(&mut reader, &budget)?;
+    let options = ReadOptions {
+        complexity_budget: budget,
+        ..Default::default()
+    };
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(options);
+    let err = reader.next().unwrap().unwrap_err();
+    assert!(err.to_string().contains("TooManyNodes"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn read_ipc_schema() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 1, 0]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let list = ListArray::<i32>::new(
+        ArrowDataType::List(Box::new(Field::new(
+            PlSmallStr::from_static("item"),
+            dictionary.dtype().clone(),
+            true,
+        ))),
+        vec![0, 2, 2, 3].try_into()?,
+        dictionary.boxed(),
+        None,
+    );
+    let columns = vec![Int32Array::from_slice([1, 2, 3]).boxed(), list.boxed()];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let ipc_fields = vec![
+        IpcField::default(),
+        IpcField {
+            fields: vec![IpcField {
+                fields: vec![],
+                dictionary_id: Some(7),
+            }],
+            dictionary_id: None,
+        },
+    ];
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+    let data = write(&[batch], &schema, Some(ipc_fields.clone()), None)?;
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let reader = FileReader::new(reader, metadata, Some(vec![1]), None);
+    assert_eq!(reader.ipc_schema().fields, ipc_fields);
+    assert!(reader.ipc_schema().is_little_endian);
+
+    let reader = open(Cursor::new(&data))?;
+    assert_eq!(reader.ipc_schema().fields, ipc_fields);
+    Ok(())
+}
+
+#[test]
+fn read_with_structure_validation() -> PolarsResult<()> {
+    let item = Field::new("item".into(), ArrowDataType::Int32, true);
+    let pairs = FixedSizeListArray::new(
+        ArrowDataType::FixedSizeList(Box::new(item), 2),
+        3,
+        Int32Array::from_slice([1, 2, 3, 4, 5, 6]).boxed(),
+        None,
+    );
+    let fields = vec![
+        Field::new("a".into(), ArrowDataType::Int32, true),
+        Field::new("b".into(), pairs.dtype().clone(), true),
+    ];
+    let nested = StructArray::new(
+        ArrowDataType::Struct(fields),
+        3,
+        vec![Int32Array::from_slice([7, 8, 9]).boxed(), pairs.boxed()],
+        None,
+    );
+    let columns = vec![nested.boxed(), Int32Array::from_slice([0, 1, 2]).boxed()];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+    let options = WriteOptions { compression: None };
+    let write_file = |record: &EncodedData| -> PolarsResult<Vec<u8>> {
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&[], record)?;
+        writer.finish()?;
+        Ok(writer.into_inner())
+    };
+    let read = |data: &[u8]| -> PolarsResult<Vec<RecordBatchT<Box<dyn Array>>>> {
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let options = ReadOptions {
+            validate_structure: true,
+            ..Default::default()
+        };
+        FileReader::new(reader, metadata, None, None)
+            .with_options(options)
+            .collect()
+    };
+
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    assert_eq!(read(&write_file(&record)?)?[0], batch);
+
+    // drops the field node of the items of `c0.b`, such that they take the node of `c1`
+    let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    let Some(MessageHeader::RecordBatch(ipc_batch)) = &mut message.header else {
+        unreachable!()
+    };
+    ipc_batch.nodes.as_mut().unwrap().remove(3);
+    record.ipc_message = Builder::new().finish(&message, None).to_vec();
+
+    let err = read(&write_file(&record)?).unwrap_err().to_string();
+    assert!(err.contains("at field 'c0.b.item'"), "{err}");
+    assert!(err.contains("3 rows but its parent implies 6"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn read_dictionary_keys_iter() -> PolarsResult<()> {
+    let category = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from([Some(1), Some(0), None, Some(1)]),
+        Utf8Array::<i32>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let other = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 0, 0, 0]),
+        Utf8Array::<i32>::from_slice(["unused"]).boxed(),
+    )?;
+    let nested = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([0, 1, 1]),
+        Utf8Array::<i64>::from_slice(["p", "q"]).boxed(),
+    )?;
+    let list = ListArray::<i32>::new(
+        ArrowDataType::List(Box::new(Field::new(
+            PlSmallStr::from_static("item"),
+            nested.dtype().clone(),
+            true,
+        ))),
+        vec![0, 1, 1, 3, 3].try_into()?,
+        nested.boxed(),
+        None,
+    );
+    let columns = vec![
+        category.clone().boxed(),
+        Int64Array::from_slice([10, 20, 30, 40]).boxed(),
+        other.boxed(),
+        list.clone().boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(4, schema.clone(), columns)?;
+    let data = write(&[batch.clone(), batch], &schema, None, None)?;
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let (values, batches) =
+        dictionary_keys_iter(reader, metadata.clone(), 0, &[3, 1], Default::default())?;
+    assert_eq!(values.as_ref(), category.values().as_ref());
+    let batches = batches.collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 2);
+    for batch in batches {
+        assert_eq!(
+            batch.arrays(),
+            [
+                category.keys().clone().boxed(),
+                list.clone().boxed(),
+                Int64Array::from_slice([10, 20, 30, 40]).boxed()
+            ]
+        );
+    }
+
+    let reader = Cursor::new(&data);
+    let Err(err) = dictionary_keys_iter(reader, metadata, 1, &[], Default::default()) else {
+        panic!()
+    };
+    assert!(err.to_string().contains("not dictionary-encoded"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn read_with_orphan_dictionary() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from_slice([1, 0, 1]),
+        Utf8Array::<i64>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let schema = prep_schema(&dictionary);
+    let batch = RecordBatchT::try_new(3, schema.clone(), vec![dictionary.clone().boxed()])?;
+
+    // no field refers to the dictionary of id 99
+    let options = WriteOptions { compression: None };
+    let mut dictionaries = vec![];
+    encode_dictionary(99, &dictionary, &options, &mut dictionaries)?;
+    encode_dictionary(0, &dictionary, &options, &mut dictionaries)?;
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let mut writer = FileWriter::try_new(vec![], schema, None, options)?;
+    writer.write_encoded(&dictionaries, &record)?;
+    writer.finish()?;
+    let data = writer.into_inner();
+
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let err = FileReader::new(reader, metadata.clone(), None, None)
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert!(err.to_string().contains("InvalidId"), "{err}");
+
+    let options = ReadOptions {
+        skip_orphan_dictionaries: true,
+        ..Default::default()
+    };
+    let mut reader =
+        FileReader::new(Cursor::new(&data), metadata, None, None).with_options(options);
+    let batches = reader.by_ref().collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(batches, [batch]);
+    assert_eq!(reader.take_diagnostics().orphan_dictionaries, [99]);
+    assert!(reader.take_diagnostics().orphan_dictionaries.is_empty());
+    Ok(())
+}
+
+#[test]
+fn read_fixed_size_list_of_struct() -> PolarsResult<()> {
+    let fields = vec![
+        Field::new("a".into(), ArrowDataType::Int32, true),
+        Field::new("b".into(), ArrowDataType::Float64, true),
+    ];
+    let values = StructArray::new(
+        ArrowDataType::Struct(fields),
+        6,
+        vec![
+            Int32Array::from([Some(1), None, Some(3), Some(4), Some(5), Some(6)]).boxed(),
+            Float64Array::from([Some(0.5), Some(1.5), None, Some(3.5), Some(4.5), Some(5.5)])
+                .boxed(),
+        ],
+        Some(Bitmap::from([true, true, true, false, true, true])),
+    );
+    let item = Field::new("item".into(), values.dtype().clone(), true);
+    let pairs = FixedSizeListArray::new(
+        ArrowDataType::FixedSizeList(Box::new(item), 2),
+        3,
+        values.boxed(),
+        Some(Bitmap::from([true, false, true])),
+    );
+    let columns = vec![
+        Int32Array::from([Some(7), Some(8), None]).boxed(),
+        pairs.boxed(),
+        Utf8Array::<i32>::from([Some("x"), None, Some("zz")]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+    for compression in [None, Some(Compression::LZ4)] {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        // the selected columns after the list are only decoded correctly if it is skipped with
+        // the buffers of the struct's children, and it is limited with the multiplied limit
+        for (projection, limit) in [(vec![0, 2], 3), (vec![0, 1, 2], 3), (vec![1, 2], 2)] {
+            let mut reader = Cursor::new(&data);
+            let metadata = read_file_metadata(&mut reader)?;
+            let batches = FileReader::new(reader, metadata, Some(projection.clone()), Some(limit))
+                .collect::<PolarsResult<Vec<_>>>()?;
+            let expected = projection
+                .iter()
+                .map(|&i| batch.arrays()[i].sliced(0, limit))
+                .collect::<Vec<_>>();
+            assert_eq!(batches[0].arrays(), expected.as_slice());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_dictionary_keys_of_index_type() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<u16>::from([Some(2), None, Some(0), Some(1)]),
+        Utf8Array::<i32>::from_slice(["x", "y", "z"]).boxed(),
+    )?;
+    let schema = prep_schema(&dictionary);
+    let batch = RecordBatchT::try_new(4, schema.clone(), vec![dictionary.clone().boxed()])?;
+    let options = ReadOptions {
+        dictionary_keys: true,
+        ..Default::default()
+    };
+
+    let data = write(&[batch.clone()], &schema, None, None)?;
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(options.clone());
+    let keys = reader.next().unwrap()?.into_arrays().pop().unwrap();
+    assert_eq!(keys.dtype(), &ArrowDataType::UInt16);
+    let keys = keys.as_any().downcast_ref::<UInt16Array>().unwrap();
+    assert_eq!(keys, dictionary.keys());
+    assert_eq!(
+        reader.dictionaries().unwrap().values().next().unwrap(),
+        dictionary.values()
+    );
+
+    let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+    writer.start(&schema, None)?;
+    writer.write(&batch, None)?;
+    writer.finish()?;
+    let stream = writer.into_inner();
+    let mut reader = Cursor::new(&stream);
+    let metadata = read_stream_metadata(&mut reader)?;
+    let batches = StreamReader::new(reader, metadata, None)
+        .with_options(options)
+        .map(|state| state.map(|state| state.unwrap()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let batch = &batches[0];
+    assert_eq!(batch.arrays()[0].dtype(), &ArrowDataType::UInt16);
+    assert_eq!(
+        batch.arrays()[0].as_any().downcast_ref::<UInt16Array>(),
+        Some(dictionary.keys())
+    );
+    Ok(())
+}
+
+#[test]
+fn read_legacy_union() -> PolarsResult<()> {
+    for mode in [UnionMode::Dense, UnionMode::Sparse] {
+        let columns = vec![
+            Int32Array::from_slice([1, 2, 3, 4]).boxed(),
+            union_array(mode)?,
+            Int32Array::from_slice([5, 6, 7, 8]).boxed(),
+        ];
+        let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+            |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+        )));
+        let batch = RecordBatchT::try_new(4, schema.clone(), columns)?;
+
+        let options = WriteOptions { compression: None };
+        let write_file = |record: &EncodedData| -> PolarsResult<Vec<u8>> {
+            let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+            writer.write_encoded(&[], record)?;
+            writer.finish()?;
+            Ok(writer.into_inner())
+        };
+        let read = |data: &[u8], projection: Option<Vec<usize>>| {
+            let mut reader = Cursor::new(data);
+            let metadata = read_file_metadata(&mut reader)?;
+            FileReader::new(reader, metadata, projection, None).collect::<PolarsResult<Vec<_>>>()
+        };
+
+        let mut record = EncodedData::default();
+        encode_record_batch(&batch, &options, &mut record);
+        let modern = write_file(&record)?;
+
+        // before V5, unions have a validity buffer before their types buffer, which follows
+        // the validity and values buffers of `c0`
+        let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+        let mut message: Message = message.try_into().unwrap();
+        message.version = MetadataVersion::V4;
+        let Some(MessageHeader::RecordBatch(ipc_batch)) = &mut message.header else {
+            unreachable!()
+        };
+        let validity = Buffer {
+            offset: 0,
+            length: 0,
+        };
+        ipc_batch.buffers.as_mut().unwrap().insert(2, validity);
+        record.ipc_message = Builder::new().finish(&message, None).to_vec();
+        let legacy = write_file(&record)?;
+
+        // equality of union scalars is not implemented, compare the union's components
+        let expected = batch.arrays()[1]
+            .as_any()
+            .downcast_ref::<UnionArray>()
+            .unwrap();
+        for data in [&modern, &legacy] {
+            let batches = read(data, None)?;
+            let arrays = batches[0].arrays();
+            assert_eq!(arrays[0], batch.arrays()[0]);
+            let result = arrays[1].as_any().downcast_ref::<UnionArray>().unwrap();
+            assert_eq!(result.types(), expected.types());
+            assert_eq!(
+                result.offsets().map(|x| x.as_slice()),
+                expected.offsets().map(|x| x.as_slice())
+            );
+            assert_eq!(result.fields(), expected.fields());
+            assert_eq!(arrays[2], batch.arrays()[2]);
+
+            // skipping the union skips its validity buffer
+            let projected = read(data, Some(vec![2]))?;
+            assert_eq!(projected[0].arrays(), &batch.arrays()[2..]);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn read_with_injected_dictionary() -> PolarsResult<()> {
+    let values = Utf8Array::<i64>::from_slice(["a", "b"]).boxed();
+    let keys = PrimitiveArray::<u32>::from([Some(1), None, Some(0)]);
+    let columns = vec![
+        DictionaryArray::try_from_keys(keys, values.clone())?.boxed(),
+        Int32Array::from_slice([1, 2, 3]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+    // a file without the dictionary batch of its dictionary-encoded column, of id 0
+    let options = WriteOptions { compression: None };
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+    writer.write_encoded(&[], &record)?;
+    writer.finish()?;
+    let file = writer.into_inner();
+
+    let read = |dictionary_resolver| {
+        let mut reader = Cursor::new(&file);
+        let metadata = read_file_metadata(&mut reader)?;
+        FileReader::new(reader, metadata, None, None)
+            .with_options(ReadOptions {
+                dictionary_resolver,
+                ..Default::default()
+            })
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+
+    let dictionaries = [(0, values)].into_iter().collect();
+    let resolver = DictionaryResolver::from_dictionaries(dictionaries);
+    assert_eq!(read(Some(resolver))?, [batch]);
+
+    // the ids that the resolver does not know are missing
+    let resolver = DictionaryResolver::new(|_| None);
+    let err = read(Some(resolver)).unwrap_err();
+    assert!(
+        err.to_string().contains("MissingDictionary { id: 0 }"),
+        "{err}"
+    );
+
+    // a dictionary of another type than the column's values is an error
+    let resolver = DictionaryResolver::new(|_| Some(Int32Array::from_slice([1, 2]).boxed()));
+    assert!(read(Some(resolver)).is_err());
+    Ok(())
+}
+
+#[test]
+fn read_compression_ratios() -> PolarsResult<()> {
+    // pseudo-random values, which zstd can not compress, and a repeated value
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let random = (0..4096)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as i64
+        })
+        .collect::<Vec<_>>();
+    let columns = vec![
+        Int64Array::from_vec(random).boxed(),
+        Int64Array::from_vec(vec![7; 4096]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("random".into(), ArrowDataType::Int64, true),
+        Field::new("repeated".into(), ArrowDataType::Int64, true),
+    ]));
+    let batch = RecordBatchT::try_new(4096, schema.clone(), columns)?;
+
+    let read = |compression| -> PolarsResult<Vec<(PlSmallStr, f64)>> {
+        let data = write(&[batch.clone()], &schema, None, compression)?;
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        FileReader::new(reader, metadata, None, None).compression_ratios(0)
+    };
+
+    let ratios = read(Some(Compression::ZSTD))?;
+    assert_eq!(ratios[0].0, "random");
+    assert!((0.95..=1.05).contains(&ratios[0].1), "{ratios:?}");
+    assert_eq!(ratios[1].0, "repeated");
+    assert!(ratios[1].1 > 10.0, "{ratios:?}");
+
+    let ratios = read(None)?;
+    assert!(ratios.iter().all(|(_, ratio)| *ratio == 1.0), "{ratios:?}");
+    Ok(())
+}
+
+#[test]
+fn read_concatenated_dictionary_versions() -> PolarsResult<()> {
+    let dictionary = |keys: &[Option<u32>], values: &[&str]| -> PolarsResult<Box<dyn Array>> {
+        let keys = PrimitiveArray::<u32>::from(keys.to_vec());
+        let values = Utf8Array::<i64>::from_slice(values).boxed();
+        Ok(DictionaryArray::try_from_keys(keys, values)?.boxed())
+    };
+    let first = dictionary(&[Some(0), Some(1), Some(0)], &["a", "b"])?;
+    let schema = Arc::new(ArrowSchema::from_iter([Field::new(
+        "c0".into(),
+        first.dtype().clone(),
+        true,
+    )]));
+    let concatenate = |columns: Vec<Box<dyn Array>>| {
+        let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+        writer.start(&schema, None)?;
+        for column in columns {
+            let batch = RecordBatchT::try_new(column.len(), schema.clone(), vec![column])?;
+            writer.write(&batch, None)?;
+        }
+        writer.finish()?;
+        let mut stream = Cursor::new(writer.into_inner());
+        let metadata = read_stream_metadata(&mut stream)?;
+        let batches = StreamReader::new(stream, metadata, None)
+            .map(|state| state.map(|state| state.unwrap()))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        concatenate_batches(&batches)
+    };
+
+    // the dictionary of the second record batch replaces the first
+    let second = dictionary(&[Some(0), Some(1), None], &["c", "a"])?;
+    let batch = concatenate(vec![first.clone(), second])?;
+    let expected = dictionary(
+        &[Some(0), Some(1), Some(0), Some(2), Some(0), None],
+        &["a", "b", "c"],
+    )?;
+    assert_eq!(batch.len(), 6);
+    assert_eq!(batch.arrays(), [expected]);
+
+    // the record batches share the dictionary
+    let second = dictionary(&[None, Some(1)], &["a", "b"])?;
+    let batch = concatenate(vec![first.clone(), second])?;
+    let expected = dictionary(&[Some(0), Some(1), Some(0), None, Some(1)], &["a", "b"])?;
+    assert_eq!(batch.arrays(), [expected]);
+
+    // the dictionary of the second record batch extends the first
+    let second = dictionary(&[Some(2), Some(1)], &["a", "b", "c"])?;
+    let batch = concatenate(vec![first, second])?;
+    let expected = dictionary(
+        &[Some(0), Some(1), Some(0), Some(2), Some(1)],
+        &["a", "b", "c"],
+    )?;
+    assert_eq!(batch.arrays(), [expected]);
+    Ok(())
+}
+
+#[test]
+fn read_mmap_reader() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<u32>::from_slice([1, 0, 1]),
+        Utf8Array::<i64>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("ints".into(), ArrowDataType::Int64, true),
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+    ]));
+    let batches = (0..2)
+        .map(|i| {
+            let ints = Int64Array::from_slice([i, i + 1, i + 2]).boxed();
+            RecordBatchT::try_new(3, schema.clone(), vec![ints, dictionary.clone().boxed()])
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let data = Arc::new(write(&batches, &schema, None, None)?);
+
+    let reader = unsafe { arrow::mmap::MmapReader::try_new(data.clone())? };
+    assert_eq!(reader.num_batches(), 2);
+    let read = reader.iter().collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read, batches);
+
+    // the values of the arrays point into the file
+    let range = data.as_ptr_range();
+    for batch in &read {
+        let ints = batch.arrays()[0]
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        let values = ints.values().as_ptr() as *const u8;
+        assert!(range.contains(&values));
+    }
+
+    // the record batches keep the file alive once the reader is dropped
+    drop(reader);
+    let strong_count = Arc::strong_count(&data);
+    drop(data);
+    assert!(strong_count > 1);
+    assert_eq!(read, batches);
+
+    // compressed files can not be memory mapped
+    let data = Arc::new(write(&batches, &schema, None, Some(Compression::LZ4))?);
+    let reader = unsafe { arrow::mmap::MmapReader::try_new(data) };
+    assert!(reader.is_err());
+    Ok(())
+}
+
+#[test]
+fn read_view_without_variadic_buffers() -> PolarsResult<()> {
+    let columns = vec![
+        Utf8ViewArray::new_null(ArrowDataType::Utf8View, 3).boxed(),
+        Int32Array::from_slice([1, 2, 3]).boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+
+    let options = WriteOptions { compression: None };
+    let write_file = |record: &EncodedData| -> PolarsResult<Vec<u8>> {
+        let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+        writer.write_encoded(&[], record)?;
+        writer.finish()?;
+        Ok(writer.into_inner())
+    };
+    let read = |data: &[u8], projection: Option<Vec<usize>>, tolerate| {
+        let mut reader = Cursor::new(data);
+        let metadata = read_file_metadata(&mut reader)?;
+        FileReader::new(reader, metadata, projection, None)
+            .with_options(ReadOptions {
+                tolerate_missing_variadic_counts: tolerate,
+                ..Default::default()
+            })
+            .collect::<PolarsResult<Vec<_>>>()
+    };
+
+    // the all-null view column declares zero variadic buffers
+    let mut record = EncodedData::default();
+    encode_record_batch(&batch, &options, &mut record);
+    let message = MessageRef::read_as_root(&record.ipc_message).unwrap();
+    let mut message: Message = message.try_into().unwrap();
+    let Some(MessageHeader::RecordBatch(ipc_batch)) = &mut message.header else {
+        unreachable!()
+    };
+    assert_eq!(ipc_batch.variadic_buffer_counts.as_deref(), Some(&[0][..]));
+    let data = write_file(&record)?;
+    assert_eq!(read(&data, None, false)?, [batch.clone()]);
+    let projected = read(&data, Some(vec![1]), false)?;
+    assert_eq!(projected[0].arrays(), &batch.arrays()[1..]);
+
+    // a producer that omits the variadic buffer counts of columns without variadic buffers
+    ipc_batch.variadic_buffer_counts = None;
+    record.ipc_message = Builder::new().finish(&message, None).to_vec();
+    let data = write_file(&record)?;
+    let err = read(&data, None, false).unwrap_err();
+    assert!(err.to_string().contains("variadic buffers"), "{err}");
+    assert_eq!(read(&data, None, true)?, [batch.clone()]);
+    let projected = read(&data, Some(vec![1]), true)?;
+    assert_eq!(projected[0].arrays(), &batch.arrays()[1..]);
+    Ok(())
+}
+
+#[test]
+fn read_c_data() -> PolarsResult<()> {
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<u32>::from([Some(1), None, Some(0)]),
+        Utf8Array::<i64>::from_slice(["x", "y"]).boxed(),
+    )?;
+    let columns = vec![
+        Int64Array::from([Some(1), None, Some(3)]).boxed(),
+        Utf8Array::<i64>::from_slice(["a", "bc", ""]).boxed(),
+        dictionary.boxed(),
+    ];
+    let schema = Arc::new(ArrowSchema::from_iter(columns.iter().enumerate().map(
+        |(i, array)| Field::new(format!("c{i}").into(), array.dtype().clone(), true),
+    )));
+    let batch = RecordBatchT::try_new(3, schema.clone(), columns)?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+
+    let dictionaries = read_all_dictionaries(&mut Cursor::new(&data))?;
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let exported = read_record_batch_c_data(
+        &mut reader,
+        &dictionaries,
+        &metadata,
+        None,
+        None,
+        0,
+        &mut vec![],
+        &mut vec![],
+        &Default::default(),
+    )?;
+    assert_eq!(exported.length, 3);
+
+    let (fields, arrays): (Vec<_>, Vec<_>) = exported
+        .columns
+        .into_iter()
+        .map(|(schema, array)| {
+            let field = unsafe { arrow::ffi::import_field_from_c(&schema)? };
+            let array = unsafe { arrow::ffi::import_array_from_c(array, field.dtype.clone())? };
+            Ok((field, array))
+        })
+        .collect::<PolarsResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    assert_eq!(fields, schema.iter_values().cloned().collect::<Vec<_>>());
+    assert_eq!(arrays, batch.arrays());
+
+    // releasing the exported arrays frees the arrays that they own, here memory mapped ones
+    let data = Arc::new(data);
+    let reader = unsafe { arrow::mmap::MmapReader::try_new(data.clone())? };
+    let owners = Arc::strong_count(&data);
+    let exported = to_c_data(reader.read(0)?);
+    assert!(Arc::strong_count(&data) > owners);
+    drop(exported);
+    assert_eq!(Arc::strong_count(&data), owners);
+    Ok(())
+}
+
+#[test]
+fn read_with_column_budget() -> PolarsResult<()> {
+    let small = Int32Array::from_vec((0..100).collect()).boxed();
+    let strings = (0..100).map(|i| format!("{i:0>1000}")).collect::<Vec<_>>();
+    let big = Utf8Array::<i64>::from_slice(strings).boxed();
+    let last = Int64Array::from_vec((0..100).collect()).boxed();
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("small".into(), small.dtype().clone(), true),
+        Field::new("big".into(), big.dtype().clone(), true),
+        Field::new("last".into(), last.dtype().clone(), true),
+    ]));
+    let batch = RecordBatchT::try_new(100, schema.clone(), vec![small.clone(), big, last.clone()])?;
+    let data = write(&[batch], &schema, None, None)?;
+
+    let read = |options: &ReadOptions| {
+        let mut reader = Cursor::new(&data);
+        let metadata = read_file_metadata(&mut reader)?;
+        let mut reader =
+            FileReader::new(reader, metadata, None, None).with_options(options.clone());
+        let batches = reader.by_ref().collect::<PolarsResult<Vec<_>>>()?;
+        PolarsResult::Ok((batches, reader.take_diagnostics()))
+    };
+
+    // the strings take about 100 KB, the other columns less than 1 KB
+    for parallelism in [Parallelism::Serial, Parallelism::Threads(2)] {
+        let options = ReadOptions {
+            column_budget: ColumnBudget {
+                max_bytes: Some(10_000),
+                ..Default::default()
+            },
+            parallelism,
+            ..Default::default()
+        };
+        let (batches, diagnostics) = read(&options)?;
+        let arrays = batches[0].arrays();
+        assert_eq!(arrays[0], small);
+        assert_eq!(arrays[1].dtype(), &ArrowDataType::LargeUtf8);
+        assert_eq!(arrays[1].null_count(), 100);
+        assert_eq!(arrays[2], last);
+        let errors = diagnostics.column_errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].name, "big");
+        assert!(
+            errors[0].error.to_string().contains("budget"),
+            "{}",
+            errors[0].error
+        );
+    }
+
+    // a dictionary is not bounded by the budget of the columns that refer to it, only their keys
+    let strings = (0..100).map(|i| format!("{i:0>1000}")).collect::<Vec<_>>();
+    let values = Utf8Array::<i64>::from_slice(strings).boxed();
+    let keys = PrimitiveArray::from_vec((0..100).rev().collect::<Vec<i32>>());
+    let dictionary = DictionaryArray::try_from_keys(keys, values)?.boxed();
+    let schema = prep_schema(dictionary.as_ref());
+    let batch = RecordBatchT::try_new(100, schema.clone(), vec![dictionary.clone()])?;
+    let data = write(&[batch], &schema, None, None)?;
+    let mut reader = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(ReadOptions {
+        column_budget: ColumnBudget {
+            max_bytes: Some(10_000),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    assert_eq!(reader.next().unwrap()?.arrays()[0], dictionary);
+    assert!(reader.take_diagnostics().column_errors.is_empty());
+
+    // every column exceeds a time budget of zero, regardless of the column error policy
+    let options = ReadOptions {
+        column_budget: ColumnBudget {
+            max_duration: Some(std::time::Duration::ZERO),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (batches, diagnostics) = read(&options)?;
+    assert!(
+        batches[0]
+            .arrays()
+            .iter()
+            .all(|array| array.null_count() == 100)
+    );
+    assert_eq!(diagnostics.column_errors.len(), 3);
+
+    // a column that fails for another reason once its time budget has passed, here because it
+    // decompresses to more than its limit after a slow read, is not a timeout
+    struct SlowReader(Cursor<Vec<u8>>);
+    impl Read for SlowReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            self.0.read(buf)
+        }
+    }
+    impl Seek for SlowReader {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+    let ints = Int64Array::from_vec((0..1000).collect()).boxed();
+    let schema = prep_schema(ints.as_ref());
+    let batch = RecordBatchT::try_new(1000, schema.clone(), vec![ints])?;
+    let data = write(&[batch], &schema, None, Some(Compression::LZ4))?;
+    let read = |column_error_policy| {
+        let mut reader = Cursor::new(data.clone());
+        let metadata = read_file_metadata(&mut reader)?;
+        let mut reader =
+            FileReader::new(SlowReader(reader), metadata, None, None).with_options(ReadOptions {
+                column_budget: ColumnBudget {
+                    max_duration: Some(std::time::Duration::from_millis(1)),
+                    ..Default::default()
+                },
+                max_decompressed_size: Some(1000),
+                column_error_policy,
+                ..Default::default()
+            });
+        let batches = reader.by_ref().collect::<PolarsResult<Vec<_>>>()?;
+        PolarsResult::Ok((batches, reader.take_diagnostics()))
+    };
+    let error = read(ColumnErrorPolicy::Fail).unwrap_err();
+    assert!(error.to_string().contains("decompresses"), "{error}");
+    let (_, diagnostics) = read(ColumnErrorPolicy::NullFill)?;
+    let error = &diagnostics.column_errors[0].error;
+    assert!(error.to_string().contains("decompresses"), "{error}");
+    Ok(())
+}
+
+#[test]
+fn read_stream_delta_dictionary() -> PolarsResult<()> {
+    let values = Utf8Array::<i64>::from_slice(["a", "b", "c"]).boxed();
+    let column = |keys: &[i32], length: usize| -> PolarsResult<Box<dyn Array>> {
+        let keys = PrimitiveArray::<i32>::from_slice(keys);
+        Ok(DictionaryArray::try_from_keys(keys, values.sliced(0, length))?.boxed())
+    };
+    let first = column(&[1, 0], 2)?;
+    let second = column(&[2, 1, 2], 3)?;
+    let schema = prep_schema(first.as_ref());
+    let batches = [
+        RecordBatchT::try_new(2, schema.clone(), vec![first])?,
+        RecordBatchT::try_new(3, schema.clone(), vec![second])?,
+    ];
+
+    // the second record batch is preceded by a delta with the value of the dictionary it adds
+    let options = WriteOptions { compression: None };
+    let mut writer = FileWriter::try_new(vec![], schema.clone(), None, options)?;
+    for (batch, (start, length)) in batches.iter().zip([(0, 2), (2, 1)]) {
+        let part = DictionaryArray::try_from_keys(
+            PrimitiveArray::<i32>::from_slice([]),
+            values.sliced(start, length),
+        )?;
+        let mut dictionaries = vec![];
+        encode_dictionary(0, &part, &options, &mut dictionaries)?;
+        if start > 0 {
+            dictionaries = dictionaries.into_iter().map(into_delta).collect();
+        }
+        let mut record = EncodedData::default();
+        encode_record_batch(batch, &options, &mut record);
+        writer.write_encoded(&dictionaries, &record)?;
+    }
+    writer.finish()?;
+    let file = writer.into_inner();
+
+    // the messages of a file, between its magic and its footer, are a stream
+    let footer_length =
+        i32::from_le_bytes(file[file.len() - 10..file.len() - 6].try_into().unwrap());
+    let stream = file[8..file.len() - 10 - footer_length as usize].to_vec();
+    let mut stream = Cursor::new(stream);
+    let metadata = read_stream_metadata(&mut stream)?;
+    let read = StreamReader::new(stream, metadata, None)
+        .map(|state| state.map(|state| state.unwrap()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    assert_eq!(read, batches);
+    Ok(())
+}
+
+#[test]
+fn write_stream_delta_dictionaries() -> PolarsResult<()> {
+    let dictionary = |keys: &[u32], values: &[&str]| -> PolarsResult<Box<dyn Array>> {
+        let keys = PrimitiveArray::<u32>::from_slice(keys);
+        let values = Utf8Array::<i64>::from_slice(values).boxed();
+        Ok(DictionaryArray::try_from_keys(keys, values)?.boxed())
+    };
+    // the same dictionary, a dictionary that extends it and one that replaces it
+    let columns = [
+        dictionary(&[0, 1], &["a", "b"])?,
+        dictionary(&[1], &["a", "b"])?,
+        dictionary(&[2, 0], &["a", "b", "c"])?,
+        dictionary(&[0, 0], &["d"])?,
+    ];
+    let schema = prep_schema(columns[0].as_ref());
+    let batches = columns
+        .into_iter()
+        .map(|column| RecordBatchT::try_new(column.len(), schema.clone(), vec![column]))
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    let event = |block_index, kind, length| DictionaryEvent {
+        block_index,
+        kind,
+        length,
+    };
+    for emit_deltas in [false, true] {
+        let mut writer = StreamWriter::new(vec![], WriteOptions { compression: None });
+        writer.set_delta_dictionaries(emit_deltas);
+        writer.start(&schema, None)?;
+        for batch in &batches {
+            writer.write(batch, None)?;
+        }
+        writer.finish()?;
+
+        let mut stream = Cursor::new(writer.into_inner());
+        let metadata = read_stream_metadata(&mut stream)?;
+        let mut reader = StreamReader::new(stream, metadata, None);
+        let read = reader
+            .by_ref()
+            .map(|state| state.map(|state| state.unwrap()))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        assert_eq!(read, batches);
+
+        let extension = if emit_deltas {
+            event(1, DictionaryEventKind::Delta, 1)
+        } else {
+            event(1, DictionaryEventKind::Replacement, 3)
+        };
+        assert_eq!(
+            reader.dictionary_history(0),
+            [
+                event(0, DictionaryEventKind::Replacement, 2),
+                extension,
+                event(2, DictionaryEventKind::Replacement, 1),
+            ]
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn read_unordered_projection() -> PolarsResult<()> {
+    let (schema, batch) = int_columns(4)?;
+    let data = write(&[batch.clone()], &schema, None, None)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_file_metadata(&mut reader)?;
+    let mut read = |projection: &[usize], parallelism| {
+        let options = ReadOptions {
+            parallelism,
+            ..Default::default()
+        };
+        read_batch(
+            &mut reader,
+            &Default::default(),
+            &metadata,
+            Some(projection),
+            None,
+            0,
+            &mut vec![],
+            &mut vec![],
+            &options,
+        )
+    };
+
+    for parallelism in [Parallelism::Serial, Parallelism::Threads(2)] {
+        let read = read(&[3, 0, 2], parallelism)?;
+        let names = read.schema().iter_names().cloned().collect::<Vec<_>>();
+        assert_eq!(names, ["c3", "c0", "c2"]);
+        let arrays = batch.arrays();
+        assert_eq!(
+            read.arrays(),
+            [arrays[3].clone(), arrays[0].clone(), arrays[2].clone()]
+        );
+    }
+
+    assert!(matches!(
+        read(&[1, 0, 1], Parallelism::Serial),
+        Err(PolarsError::Duplicate(_))
+    ));
+    assert!(matches!(
+        read(&[1, 4], Parallelism::Serial),
+        Err(PolarsError::OutOfBounds(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn read_with_block_prefetch() -> PolarsResult<()> {
+    let values = Utf8Array::<i64>::from_slice(["x", "y", "z"]).boxed();
+    let dictionary =
+        DictionaryArray::try_from_keys(PrimitiveArray::<u32>::from_slice([2, 0, 1, 0]), values)?;
+    let schema = Arc::new(ArrowSchema::from_iter([
+        Field::new("ints".into(), ArrowDataType::Int64, true),
+        Field::new("dictionary".into(), dictionary.dtype().clone(), true),
+    ]));
+    let batches = (0..7)
+        .map(|i| {
+            let ints = Int64Array::from_slice([i, i + 1, i + 2, i + 3]).boxed();
+            RecordBatchT::try_new(4, schema.clone(), vec![ints, dictionary.clone().boxed()])
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let data = write(&batches, &schema, None, Some(Compression::LZ4))?;
+
+    let read = |projection: Option<Vec<usize>>,
+                limit,
+                options: ReadOptions|
+     -> PolarsResult<(Vec<_>, Option<Compression>)> {
+        let mut reader = Cursor::new(data.clone());
+        let metadata = read_file_metadata(&mut reader)?;
+        let mut reader = FileReader::new(reader, metadata, projection, limit).with_options(options);
+        let read = reader.by_ref().collect::<PolarsResult<Vec<_>>>()?;
+        Ok((read, reader.compression()))
+    };
+
+    let prefetches = [
+        BlockPrefetch {
+            max_blocks: 16,
+            max_bytes: None,
+            parallelism: Parallelism::Threads(4),
+        },
+        // only the record batch that is read fits the budget
+        BlockPrefetch {
+            max_blocks: 4,
+            max_bytes: Some(1),
+            parallelism: Parallelism::Serial,
+        },
+    ];
+    #[cfg(feature = "ipc_rayon")]
+    let prefetches = [BlockPrefetch {
+        max_blocks: 3,
+        max_bytes: None,
+        parallelism: Parallelism::Rayon,
+    }]
+    .into_iter()
+    .chain(prefetches)
+    .collect::<Vec<_>>();
+    for &prefetch in &prefetches {
+        for (projection, limit) in [(None, None), (Some(vec![1, 0]), Some(10))] {
+            for (reverse, reverse_rows, max_batches) in [
+                (false, false, None),
+                (true, true, None),
+                (false, false, Some(2)),
+            ] {
+                let options = ReadOptions {
+                    reverse,
+                    reverse_rows,
+                    max_batches,
+                    ..Default::default()
+                };
+                let expected = read(projection.clone(), limit, options.clone())?;
+                let options = ReadOptions {
+                    block_prefetch: Some(prefetch),
+                    ..options
+                };
+                let read = read(projection.clone(), limit, options)?;
+                assert_eq!(read, expected);
+                assert_eq!(read.1, Some(Compression::LZ4));
+            }
+        }
+    }
+
+    // seeking discards the record batches that were decoded ahead of time
+    let mut reader = Cursor::new(data.clone());
+    let metadata = read_file_metadata(&mut reader)?;
+    let mut reader = FileReader::new(reader, metadata, None, None).with_options(ReadOptions {
+        block_prefetch: Some(prefetches[0]),
+        ..Default::default()
+    });
+    assert_eq!(reader.next().transpose()?.as_ref(), Some(&batches[0]));
+    reader.set_current_block(5);
+    assert_eq!(reader.next().transpose()?.as_ref(), Some(&batches[5]));
+    assert_eq!(reader.next().transpose()?.as_ref(), Some(&batches[6]));
+    assert!(reader.next().is_none());
+    Ok(())
+}
+
+/// Returns the values of a dictionary array of strings.
+fn dictionary_strings<K: DictionaryKey>(array: &DictionaryArray<K>) -> Vec<Option<String>> {
+    let values = array
+        .values()
+        .as_any()
+        .downcast_ref::<Utf8Array<i32>>()
+        .unwrap();
+    array
+        .keys_iter()
+        .map(|key| key.map(|key| values.value(key).to_string()))
+        .collect()
+}
@@ -151,3 +151,55 @@ fn write_and_read_ipc_empty_series() {
     let df_read = IpcReader::new(buf).finish().unwrap();
     assert!(df.equals(&df_read));
 }
+
+#[test]
+#[cfg(feature = "dtype-categorical")]
+fn read_dictionary_batch_into_categorical_series() {
+    use std::sync::Arc;
+
+    use arrow::array::{Array, DictionaryArray, PrimitiveArray, Utf8Array};
+    use arrow::datatypes::{ArrowSchema, Field as ArrowField};
+    use arrow::io::ipc::read::{read_file_dictionaries, read_file_metadata};
+    use arrow::io::ipc::write::{FileWriter, WriteOptions};
+    use arrow::record_batch::RecordBatchT;
+
+    let dictionary = DictionaryArray::try_from_keys(
+        PrimitiveArray::<i32>::from([Some(0), Some(1), None, Some(0)]),
+        Utf8Array::<i32>::from_slice(["a", "b"]).boxed(),
+    )
+    .unwrap();
+    let schema = Arc::new(ArrowSchema::from_iter([ArrowField::new(
+        "category".into(),
+        dictionary.dtype().clone(),
+        true,
+    )]));
+    let batch = RecordBatchT::try_new(4, schema.clone(), vec![dictionary.boxed()]).unwrap();
+    let options = WriteOptions { compression: None };
+    let mut writer = FileWriter::try_new(vec![], schema, None, options).unwrap();
+    writer.write(&batch, None).unwrap();
+    writer.finish().unwrap();
+
+    let mut reader = Cursor::new(writer.into_inner());
+    let metadata = read_file_metadata(&mut reader).unwrap();
+    let dictionaries =
+        read_file_dictionaries(&mut reader, &metadata, &mut vec![], &Default::default()).unwrap();
+    let series = read_batch_series(
+        &mut reader,
+        &dictionaries,
+        &metadata,
+        None,
+        None,
+        0,
+        &mut vec![],
+        &mut vec![],
+        &Default::default(),
+    )
+    .unwrap();
+
+    assert_eq!(series.len(), 1);
+    assert_eq!(series[0].name().as_str(), "category");
+    assert!(matches!(series[0].dtype(), DataType::Categorical(_, _)));
+    let values = series[0].cast(&DataType::String).unwrap();
+    let values = values.str().unwrap().into_iter().collect::<Vec<_>>();
+    assert_eq!(values, [Some("a"), Some("b"), None, Some("a")]);
+}